@@ -0,0 +1,420 @@
+//! Every action of the table view with its name and keys. The keymap and the
+//! command palette both read from `BINDINGS`, so a new action shows up in the
+//! palette as soon as it has a key, or even without one.
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppAction {
+    Quit,
+    Next,
+    Previous,
+    ScrollCellLeft,
+    ScrollCellRight,
+    NextColor,
+    PreviousColor,
+    Delete,
+    ToggleInfo,
+    Settings,
+    OpenReader,
+    Edit,
+    Create,
+    QuickAdd,
+    Undo,
+    RecordMacro,
+    ReplayMacro,
+    NextProgress,
+    SetWaiting,
+    SetInProgress,
+    SetDone,
+    CycleDensity,
+    NextList,
+    PreviousList,
+    NewList,
+    IncreasePercent,
+    DecreasePercent,
+    PostponeDay,
+    PostponeWeek,
+    PostponeToMonday,
+    Focus,
+    PickRandom,
+    ToggleStar,
+    ShowStats,
+    ToggleSidebar,
+    EditTags,
+    AddNote,
+    GroupByDate,
+    CycleSort,
+    Search,
+    CommandLine,
+    CommandPalette,
+    ClearCompleted,
+    CycleFocus,
+    CycleLayout,
+    ToggleLane,
+    PreviousLane,
+    NextLane,
+    ShrinkColumn,
+    GrowColumn,
+    ToggleHideCompleted,
+    ExportMarkdown,
+    ExportCsv,
+    ExportHtml,
+    Import,
+    Share,
+}
+
+/// An action, its name in the palette and the keys that run it.
+pub struct Binding {
+    pub action: AppAction,
+    pub name: &'static str,
+    pub keys: &'static [KeyCode],
+    pub modifiers: KeyModifiers, // Held on top of the key, e.g. Ctrl
+    pub hint: &'static str,      // The keys as shown in the palette
+}
+
+const fn bind(
+    action: AppAction,
+    name: &'static str,
+    keys: &'static [KeyCode],
+    hint: &'static str,
+) -> Binding {
+    Binding {
+        action,
+        name,
+        keys,
+        modifiers: KeyModifiers::NONE,
+        hint,
+    }
+}
+
+const fn bind_with(
+    action: AppAction,
+    name: &'static str,
+    keys: &'static [KeyCode],
+    modifiers: KeyModifiers,
+    hint: &'static str,
+) -> Binding {
+    Binding {
+        action,
+        name,
+        keys,
+        modifiers,
+        hint,
+    }
+}
+
+/// Bindings with modifiers come before the plain ones on the same key, so
+/// e.g. Ctrl+P doesn't also cycle the layout.
+pub const BINDINGS: &[Binding] = &[
+    bind_with(
+        AppAction::ScrollCellLeft,
+        "Scroll cell left",
+        &[KeyCode::Left],
+        KeyModifiers::SHIFT,
+        "Shift+←",
+    ),
+    bind_with(
+        AppAction::ScrollCellRight,
+        "Scroll cell right",
+        &[KeyCode::Right],
+        KeyModifiers::SHIFT,
+        "Shift+→",
+    ),
+    bind_with(
+        AppAction::NextList,
+        "Next list",
+        &[KeyCode::Tab],
+        KeyModifiers::CONTROL,
+        "Ctrl+Tab",
+    ),
+    bind_with(
+        AppAction::PreviousList,
+        "Previous list",
+        &[KeyCode::BackTab],
+        KeyModifiers::CONTROL,
+        "Ctrl+Shift+Tab",
+    ),
+    bind_with(
+        AppAction::CommandPalette,
+        "Command palette",
+        &[KeyCode::Char('p')],
+        KeyModifiers::CONTROL,
+        "Ctrl+P",
+    ),
+    bind(
+        AppAction::Quit,
+        "Quit",
+        &[KeyCode::Char('q'), KeyCode::Esc],
+        "Q / Esc",
+    ),
+    bind(
+        AppAction::Next,
+        "Next todo",
+        &[KeyCode::Char('j'), KeyCode::Down],
+        "J / ↓",
+    ),
+    bind(
+        AppAction::Previous,
+        "Previous todo",
+        &[KeyCode::Char('k'), KeyCode::Up],
+        "K / ↑",
+    ),
+    bind(
+        AppAction::NextColor,
+        "Switch theme: next color",
+        &[KeyCode::Char('l'), KeyCode::Right],
+        "L / →",
+    ),
+    bind(
+        AppAction::PreviousColor,
+        "Switch theme: previous color",
+        &[KeyCode::Char('h'), KeyCode::Left],
+        "H / ←",
+    ),
+    bind(
+        AppAction::Delete,
+        "Delete todo",
+        &[KeyCode::Char('x'), KeyCode::Delete],
+        "X / Del",
+    ),
+    bind(
+        AppAction::ToggleInfo,
+        "Show info and keys",
+        &[KeyCode::Char('i')],
+        "I",
+    ),
+    bind(AppAction::Settings, "Settings", &[KeyCode::Char(',')], ","),
+    bind(
+        AppAction::OpenReader,
+        "Read todo",
+        &[KeyCode::Enter],
+        "Enter",
+    ),
+    bind(AppAction::Edit, "Edit todo", &[KeyCode::Char('r')], "R"),
+    bind(AppAction::Create, "Create todo", &[KeyCode::Char('a')], "A"),
+    bind(
+        AppAction::QuickAdd,
+        "Quick add todo",
+        &[KeyCode::Char('o')],
+        "O",
+    ),
+    bind(AppAction::Undo, "Undo", &[KeyCode::Char('u')], "U"),
+    bind(
+        AppAction::RecordMacro,
+        "Start/stop recording a macro",
+        &[KeyCode::Char('Q')],
+        "Shift+Q",
+    ),
+    bind(
+        AppAction::ReplayMacro,
+        "Replay the macro",
+        &[KeyCode::Char('@')],
+        "@",
+    ),
+    bind(
+        AppAction::NextProgress,
+        "Next status",
+        &[KeyCode::Char('n')],
+        "N",
+    ),
+    // Their keys are the `status_keys` preference
+    bind(AppAction::SetWaiting, "Set status: Waiting", &[], ""),
+    bind(AppAction::SetInProgress, "Set status: In Progress", &[], ""),
+    bind(AppAction::SetDone, "Set status: Done", &[], ""),
+    bind(
+        AppAction::CycleDensity,
+        "Cycle row density",
+        &[KeyCode::Char('d')],
+        "D",
+    ),
+    bind(
+        AppAction::NewList,
+        "New list",
+        &[KeyCode::Char('N')],
+        "Shift+N",
+    ),
+    bind(
+        AppAction::IncreasePercent,
+        "Increase percent complete",
+        &[KeyCode::Char('+'), KeyCode::Char('=')],
+        "+",
+    ),
+    bind(
+        AppAction::DecreasePercent,
+        "Decrease percent complete",
+        &[KeyCode::Char('-')],
+        "-",
+    ),
+    bind(
+        AppAction::PostponeDay,
+        "Postpone a day",
+        &[KeyCode::Char('e')],
+        "E",
+    ),
+    bind(
+        AppAction::PostponeWeek,
+        "Postpone a week",
+        &[KeyCode::Char('E')],
+        "Shift+E",
+    ),
+    bind(
+        AppAction::PostponeToMonday,
+        "Postpone to next Monday",
+        &[KeyCode::Char('m')],
+        "M",
+    ),
+    bind(
+        AppAction::Focus,
+        "Focus on task in progress",
+        &[KeyCode::Char('f')],
+        "F",
+    ),
+    bind(
+        AppAction::PickRandom,
+        "Pick a random open todo",
+        &[KeyCode::Char('w')],
+        "W",
+    ),
+    bind(
+        AppAction::ToggleStar,
+        "Star todo",
+        &[KeyCode::Char('s')],
+        "S",
+    ),
+    bind(
+        AppAction::ShowStats,
+        "Show stats",
+        &[KeyCode::Char('S')],
+        "Shift+S",
+    ),
+    bind(
+        AppAction::ToggleSidebar,
+        "Toggle tag sidebar",
+        &[KeyCode::Char('b')],
+        "B",
+    ),
+    bind(AppAction::EditTags, "Edit tags", &[KeyCode::Char('#')], "#"),
+    bind(
+        AppAction::AddNote,
+        "Add a note to the todo",
+        &[KeyCode::Char('J')],
+        "Shift+J",
+    ),
+    bind(
+        AppAction::GroupByDate,
+        "Toggle group by due date",
+        &[KeyCode::Char('g')],
+        "G",
+    ),
+    bind(
+        AppAction::CycleSort,
+        "Cycle sort order",
+        &[KeyCode::Char('O')],
+        "Shift+O",
+    ),
+    bind(
+        AppAction::Search,
+        "Search all lists",
+        &[KeyCode::Char('/')],
+        "/",
+    ),
+    bind(
+        AppAction::CommandLine,
+        "Command line",
+        &[KeyCode::Char(':')],
+        ":",
+    ),
+    bind(
+        AppAction::ClearCompleted,
+        "Clear completed todos",
+        &[KeyCode::Char('C')],
+        "Shift+C",
+    ),
+    bind(
+        AppAction::CycleFocus,
+        "Cycle focus between panes",
+        &[KeyCode::Tab],
+        "Tab",
+    ),
+    bind(
+        AppAction::CycleLayout,
+        "Cycle table/preview/kanban",
+        &[KeyCode::Char('p')],
+        "P",
+    ),
+    bind(
+        AppAction::ToggleLane,
+        "Collapse kanban lane",
+        &[KeyCode::Char('z')],
+        "Z",
+    ),
+    bind(
+        AppAction::PreviousLane,
+        "Previous kanban lane",
+        &[KeyCode::Char('[')],
+        "[",
+    ),
+    bind(
+        AppAction::NextLane,
+        "Next kanban lane",
+        &[KeyCode::Char(']')],
+        "]",
+    ),
+    bind(
+        AppAction::ShrinkColumn,
+        "Shrink column",
+        &[KeyCode::Char('<')],
+        "<",
+    ),
+    bind(
+        AppAction::GrowColumn,
+        "Grow column",
+        &[KeyCode::Char('>')],
+        ">",
+    ),
+    bind(
+        AppAction::ToggleHideCompleted,
+        "Toggle hide completed",
+        &[KeyCode::Char('t')],
+        "T",
+    ),
+    bind(AppAction::ExportMarkdown, "Export Markdown", &[], ""),
+    bind(AppAction::ExportCsv, "Export CSV", &[], ""),
+    bind(AppAction::ExportHtml, "Export HTML report", &[], ""),
+    bind(AppAction::Import, "Import todos", &[], ""),
+    bind(AppAction::Share, "Share todo", &[], ""),
+];
+
+/// The action bound to the pressed key, if any.
+pub fn for_key(key: KeyEvent) -> Option<AppAction> {
+    BINDINGS
+        .iter()
+        .find(|binding| {
+            binding.keys.contains(&key.code) && key.modifiers.contains(binding.modifiers)
+        })
+        .map(|binding| binding.action)
+}
+
+/// How well `query` matches `name` as a subsequence, ignoring case: higher for
+/// letters that follow each other or start a word, `None` if it doesn't match.
+pub fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = next + name[next..].iter().position(|&n| n == c)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !name[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    // Shorter names win ties, so "Quit" beats "Quit and save" for "q"
+    Some(score * 100 - i32::try_from(name.len()).unwrap_or(i32::MAX).min(99))
+}
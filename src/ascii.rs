@@ -0,0 +1,46 @@
+//! Plain ASCII stand-ins for the borders, bars and symbols the widgets draw,
+//! for terminals and fonts that render them incorrectly. Text typed into the
+//! todos is left alone.
+
+use ratatui::buffer::Buffer;
+
+/// The ASCII stand-in for a glyph the app draws, `None` for anything else.
+fn replacement(symbol: &str) -> Option<&'static str> {
+    let mut chars = symbol.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    Some(match c {
+        '─' | '━' | '═' | '╌' | '┄' | '░' => "-",
+        '│' | '┃' | '║' | '╎' | '┆' => "|",
+        '┌' | '┐' | '└' | '┘' | '╭' | '╮' | '╯' | '╰' | '╔' | '╗' | '╚' | '╝' | '┏' | '┓' | '┗'
+        | '┛' | '├' | '┤' | '┬' | '┴' | '┼' | '╠' | '╣' | '╦' | '╩' | '╬' => {
+            "+"
+        }
+        '█' | '▉' | '▊' | '▋' | '▌' | '▍' | '▎' | '▏' | '▇' | '▆' | '▅' | '▄' | '▃' | '▂' | '▁'
+        | '▓' | '▒' | '▀' | '▔' | '▐' | '▕' => "#",
+        // The quadrants and sextants big text is drawn with
+        '▖'..='▟' | '\u{1FB00}'..='\u{1FB3B}' => "#",
+        '✔' | '✓' => "x",
+        '★' | '🔥' | '🍅' => "*",
+        '○' => "-",
+        '◐' => "~",
+        '●' | '•' => "o",
+        '·' => "-",
+        '▸' | '▶' | '›' | '»' | '→' => ">",
+        '◂' | '◀' | '‹' | '«' | '←' => "<",
+        '▾' | '▼' | '↓' => "v",
+        '▴' | '▲' | '↑' => "^",
+        '…' => ".",
+        _ => return None,
+    })
+}
+
+/// Swaps every glyph with a stand-in for its ASCII one.
+pub fn asciify(buf: &mut Buffer) {
+    for cell in &mut buf.content {
+        if let Some(ascii) = replacement(cell.symbol()) {
+            cell.set_symbol(ascii);
+        }
+    }
+}
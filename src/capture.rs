@@ -0,0 +1,86 @@
+//! `--capture`: todos piped into the app as it starts, one per line, e.g.
+//! `grep -rn TODO src | todo-tui --capture --template "{3} | {1}:{2}"`.
+
+use regex::{Captures, Regex};
+use std::io::{self, BufRead, IsTerminal};
+use std::sync::LazyLock;
+
+const DEFAULT_TEMPLATE: &str = "{line}";
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{(line|\d+)\}").expect("valid regex"));
+
+/// How a piped line becomes a todo: the name, then optionally ` | ` and the
+/// description. `{line}` is the whole line and `{1}`, `{2}`... its
+/// `:`-separated fields, the last one used taking the rest of the line.
+pub struct Template {
+    name: String,
+    description: String,
+    fields: usize, // The highest field used
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Self {
+        let (name, description) = template.split_once('|').unwrap_or((template, ""));
+        let fields = PLACEHOLDER
+            .captures_iter(template)
+            .filter_map(|captures| captures[1].parse().ok())
+            .max()
+            .unwrap_or(0);
+        Self {
+            name: name.trim().to_string(),
+            description: description.trim().to_string(),
+            fields,
+        }
+    }
+
+    /// The name and description for `line`, `None` if the name comes out blank.
+    pub fn apply(&self, line: &str) -> Option<(String, String)> {
+        let fields: Vec<&str> = line.splitn(self.fields.max(1), ':').collect();
+        let fill = |part: &str| {
+            PLACEHOLDER
+                .replace_all(part, |captures: &Captures| match &captures[1] {
+                    "line" => line.trim().to_string(),
+                    field => field
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|field| fields.get(field.checked_sub(1)?))
+                        .map(|field| field.trim().to_string())
+                        .unwrap_or_default(),
+                })
+                .trim()
+                .to_string()
+        };
+        let name = fill(&self.name);
+        (!name.is_empty()).then(|| (name, fill(&self.description)))
+    }
+}
+
+/// The todos piped in with `--capture` as (name, description), `None`
+/// without the flag. Fails when nothing is piped in, as the todos would be
+/// typed into the terminal instead.
+pub fn read() -> io::Result<Option<Vec<(String, String)>>> {
+    let args: Vec<String> = std::env::args().collect();
+    if !args.iter().any(|arg| arg == "--capture") {
+        return Ok(None);
+    }
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--capture reads todos piped in, e.g. grep -rn TODO src | todo-tui --capture",
+        ));
+    }
+    let template = args
+        .iter()
+        .position(|arg| arg == "--template")
+        .and_then(|position| args.get(position + 1))
+        .map_or(DEFAULT_TEMPLATE, String::as_str);
+    let template = Template::parse(template);
+
+    let mut todos = Vec::new();
+    for line in stdin.lock().lines() {
+        todos.extend(template.apply(&line?));
+    }
+    Ok(Some(todos))
+}
@@ -0,0 +1,137 @@
+//! Lists kept as a Markdown checklist instead of JSON, so they can live in a
+//! notes vault and be edited by hand:
+//!
+//! ```markdown
+//! - [ ] Buy milk — from the corner shop <!-- created: 2024-01-31T10:00:00Z; due: 2024-02-01 -->
+//! - [/] Write report <!-- created: 2024-01-30T08:00:00Z; priority: High -->
+//!   A second description line
+//! - [x] Call mum
+//! ```
+//!
+//! `[/]` marks a todo in progress. The comment holds what has no Markdown form;
+//! todos written without one get it on the next save.
+
+use crate::{timestamps, Data, Priority, Progress};
+
+const SEPARATOR: &str = " — ";
+
+pub fn to_markdown(items: &[Data]) -> String {
+    let mut markdown = String::new();
+    for item in items {
+        let checkbox = match item.progress {
+            Progress::Waiting => ' ',
+            Progress::InProgress => '/',
+            Progress::Done => 'x',
+        };
+        let mut lines = item.description.lines();
+        markdown.push_str(&format!("- [{checkbox}] {}", item.name));
+        if let Some(first) = lines.next() {
+            markdown.push_str(&format!("{SEPARATOR}{first}"));
+        }
+        markdown.push_str(&format!(" <!-- {} -->\n", metadata(item)));
+        for line in lines {
+            markdown.push_str(&format!("  {line}\n"));
+        }
+    }
+    markdown
+}
+
+fn metadata(item: &Data) -> String {
+    let mut fields = vec![format!("created: {}", item.created)];
+    if let Some(due) = &item.due {
+        fields.push(format!("due: {due}"));
+    }
+    if let Some(priority) = item.priority {
+        fields.push(format!("priority: {}", priority.display().1));
+    }
+    if item.starred {
+        fields.push("starred".to_string());
+    }
+    if let Some(percent) = item.percent {
+        fields.push(format!("percent: {percent}"));
+    }
+    if let Some(estimate) = &item.estimate {
+        fields.push(format!("estimate: {estimate}"));
+    }
+    if let Some(remaining) = &item.remaining {
+        fields.push(format!("remaining: {remaining}"));
+    }
+    if !item.remind.is_empty() {
+        fields.push(format!("remind: {}", item.remind.join(", ")));
+    }
+    fields.join("; ")
+}
+
+/// Reads every `- [ ]` item, with the indented lines below it as the rest of
+/// its description. Anything else in the file is skipped.
+pub fn from_markdown(contents: &str) -> Vec<Data> {
+    let mut items: Vec<Data> = Vec::new();
+    let mut in_item = false;
+    for line in contents.lines() {
+        if let Some(item) = parse_item(line) {
+            items.push(item);
+            in_item = true;
+        } else if let Some(continued) = line
+            .strip_prefix("  ")
+            .or_else(|| line.strip_prefix('\t'))
+            .filter(|_| in_item)
+        {
+            if let Some(item) = items.last_mut() {
+                item.description.push('\n');
+                item.description.push_str(continued);
+            }
+        } else {
+            in_item = false;
+        }
+    }
+    items
+}
+
+fn parse_item(line: &str) -> Option<Data> {
+    let rest = line
+        .strip_prefix("- [")
+        .or_else(|| line.strip_prefix("* ["))?;
+    let mut chars = rest.chars();
+    let progress = match chars.next()? {
+        ' ' => Progress::Waiting,
+        '/' => Progress::InProgress,
+        'x' | 'X' => Progress::Done,
+        _ => return None,
+    };
+    let mut text = chars.as_str().strip_prefix("] ")?.trim_end();
+
+    let mut item = Data {
+        progress,
+        ..Data::default()
+    };
+    if let Some(start) = text.rfind("<!--").filter(|_| text.ends_with("-->")) {
+        let comment = &text[start + 4..text.len() - 3];
+        for field in comment.split(';') {
+            let (key, value) = field.split_once(':').unwrap_or((field, ""));
+            let value = value.trim();
+            match key.trim() {
+                "created" => item.created = value.to_string(),
+                "due" => item.due = Some(value.to_string()),
+                "priority" => item.priority = Priority::parse(value),
+                "starred" => item.starred = true,
+                "percent" => item.percent = value.parse().ok(),
+                "estimate" => item.estimate = Some(value.to_string()),
+                "remaining" => item.remaining = Some(value.to_string()),
+                "remind" => item.remind = value.split(", ").map(str::to_string).collect(),
+                _ => {}
+            }
+        }
+        text = text[..start].trim_end();
+    }
+
+    let (name, description) = text.split_once(SEPARATOR).unwrap_or((text, ""));
+    if name.trim().is_empty() {
+        return None;
+    }
+    item.name = name.trim().to_string();
+    item.description = description.trim().to_string();
+    if item.created.is_empty() {
+        item.created = timestamps::now();
+    }
+    Some(item)
+}
@@ -0,0 +1,162 @@
+use crate::date_range::{DateFilter, RANGES};
+use crate::substitute::Substitution;
+use crate::{Data, Progress};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "substitute old/new | status done - apply to the visible todos, prefix with % for all todos | clear - delete every done todo | export todos.md | import todos.csv | share todo.json - the selected todo, to the clipboard without a file | filter created:this-week completed:last-month - show only todos in those dates, filter alone shows all";
+
+pub enum Action {
+    Substitute(Substitution),
+    SetStatus(Progress),
+    ClearCompleted,
+    Export(PathBuf),
+    Import(PathBuf),
+    Share(Option<PathBuf>), // The clipboard without a file
+    Filter(Vec<DateFilter>),
+}
+
+/// A parsed `:` command. It applies to the todos visible in the table, or to
+/// every todo in the list when prefixed with `%`.
+pub struct Command {
+    pub action: Action,
+    pub all: bool,
+}
+
+/// What a command does to one todo; `None` fields are left untouched.
+pub struct Change {
+    pub index: usize,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub progress: Option<Progress>,
+    pub remove: bool,
+}
+
+impl Command {
+    pub fn parse(command: &str) -> Result<Self, String> {
+        let command = command.trim_start();
+        let (all, command) = command
+            .strip_prefix('%')
+            .map_or((false, command), |rest| (true, rest));
+
+        // `s/old/new` has no space after the command name
+        let (name, arguments) = command.split_at(command.find([' ', '/']).unwrap_or(command.len()));
+        let arguments = arguments.strip_prefix(' ').unwrap_or(arguments);
+
+        let action = match name {
+            "s" | "substitute" => Action::Substitute(Substitution::parse(arguments)?),
+            "status" => Action::SetStatus(
+                Progress::parse(arguments)
+                    .ok_or("Unknown status, use waiting, in-progress or done")?,
+            ),
+            "clear" => Action::ClearCompleted,
+            "export" if arguments.trim().is_empty() => {
+                return Err("Missing file, e.g. export todos.csv".to_string())
+            }
+            "export" => Action::Export(PathBuf::from(arguments.trim())),
+            "import" if arguments.trim().is_empty() => {
+                return Err("Missing file, e.g. import todos.json".to_string())
+            }
+            "import" => Action::Import(PathBuf::from(arguments.trim())),
+            "share" => Action::Share(
+                Some(arguments.trim())
+                    .filter(|path| !path.is_empty())
+                    .map(PathBuf::from),
+            ),
+            "filter" => Action::Filter(
+                arguments
+                    .split_whitespace()
+                    .map(|term| {
+                        DateFilter::parse(term).unwrap_or_else(|| {
+                            Err(format!(
+                                "Filter on created:RANGE or completed:RANGE, with a range of {RANGES}"
+                            ))
+                        })
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => return Err(format!("Unknown command \"{name}\"")),
+        };
+        Ok(Self { action, all })
+    }
+
+    /// Whether the command ignores the table's filters. Clearing always does,
+    /// as done todos are usually hidden.
+    pub fn covers_all(&self) -> bool {
+        self.all || matches!(self.action, Action::ClearCompleted)
+    }
+
+    /// The preview heading for a command touching `count` todos.
+    pub fn summary(&self, count: usize) -> String {
+        match &self.action {
+            Action::Export(path) => {
+                format!("{count} todo(s) will be exported to {}", path.display())
+            }
+            Action::Share(Some(path)) => format!("Will be shared as {}", path.display()),
+            Action::Share(None) => "Will be copied to the clipboard".to_string(),
+            _ => format!("{count} todo(s) will change"),
+        }
+    }
+
+    /// The notification shown once the command applied to `count` todos.
+    pub fn outcome(&self, count: usize) -> String {
+        match &self.action {
+            Action::ClearCompleted => format!("{count} completed todo(s) deleted"),
+            Action::Export(path) => format!("Exported {count} todo(s) to {}", path.display()),
+            Action::Share(Some(path)) => format!("Shared as {}", path.display()),
+            Action::Share(None) => "Copied to the clipboard".to_string(),
+            _ => format!("{count} todo(s) changed"),
+        }
+    }
+
+    /// The changes to the todos at `targets`, skipping those left as they are.
+    pub fn changes(&self, items: &[Data], targets: Vec<usize>) -> Vec<Change> {
+        targets
+            .into_iter()
+            .filter_map(|index| {
+                let item = &items[index];
+                let change = match &self.action {
+                    Action::Substitute(substitution) => Change {
+                        index,
+                        name: substitution.apply(&item.name),
+                        description: substitution.apply(&item.description),
+                        progress: None,
+                        remove: false,
+                    },
+                    Action::SetStatus(progress) => Change {
+                        index,
+                        name: None,
+                        description: None,
+                        progress: (item.progress != *progress).then(|| progress.clone()),
+                        remove: false,
+                    },
+                    Action::ClearCompleted => Change {
+                        index,
+                        name: None,
+                        description: None,
+                        progress: None,
+                        remove: item.progress == Progress::Done,
+                    },
+                    // Exporting changes nothing, but every target is listed
+                    // Imports are merged separately, see `import::merge`,
+                    // sharing only reads the selected todo and filters only
+                    // change what the table shows
+                    Action::Import(_) | Action::Share(_) | Action::Filter(_) => return None,
+                    Action::Export(_) => {
+                        return Some(Change {
+                            index,
+                            name: None,
+                            description: None,
+                            progress: None,
+                            remove: false,
+                        })
+                    }
+                };
+                (change.name.is_some()
+                    || change.description.is_some()
+                    || change.progress.is_some()
+                    || change.remove)
+                    .then_some(change)
+            })
+            .collect()
+    }
+}
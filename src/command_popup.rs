@@ -0,0 +1,47 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Style, Stylize, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
+
+/// The `:` command line with a preview of what the command will change.
+pub struct CommandPopup {
+    pub command: String,
+    pub error: Option<String>,
+    pub preview: Vec<Line<'static>>,
+    pub style: Style,
+}
+
+impl CommandPopup {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        Clear.render(area, buf);
+
+        let [input_area, preview_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(area);
+
+        let mut input_block = Block::new()
+            .title("Command")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(selected_style_fg));
+        if let Some(error) = &self.error {
+            input_block = input_block
+                .title_bottom(Line::from(format!(" {error} ")).fg(Color::Red))
+                .border_style(Style::default().fg(Color::Red));
+        }
+
+        Paragraph::new(Text::from(format!(":{}", self.command)))
+            .style(self.style)
+            .block(input_block)
+            .render(input_area, buf);
+
+        Paragraph::new(self.preview)
+            .wrap(Wrap { trim: false })
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title("Preview (Enter to apply)")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::White)),
+            )
+            .render(preview_area, buf);
+    }
+}
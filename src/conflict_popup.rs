@@ -0,0 +1,68 @@
+use crate::sync::{Conflict, Field};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Style, Stylize};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Row, Table, Widget};
+
+/// Shows one sync conflict side by side: the in-memory copy, the disk copy and
+/// the merged result built from the picked fields.
+pub struct ConflictPopup<'a> {
+    pub conflict: &'a Conflict,
+    pub remaining: usize,
+    pub selected: usize, // Index into the conflicting fields
+    pub style: Style,
+}
+
+impl ConflictPopup<'_> {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        Clear.render(area, buf);
+
+        let merged = self.conflict.merged();
+        let header = Row::new(["", "In memory", "On disk", "Merged"])
+            .style(Style::new().add_modifier(Modifier::BOLD));
+        let rows = Field::ALL.into_iter().map(|field| {
+            let first_line = |value: String| value.lines().next().unwrap_or("").to_string();
+            let row = Row::new([
+                Cell::from(field.label()),
+                Cell::from(first_line(field.value(&self.conflict.local))),
+                Cell::from(first_line(field.value(&self.conflict.remote))),
+                Cell::from(first_line(field.value(&merged))),
+            ]);
+
+            match self.conflict.fields.iter().position(|&f| f == field) {
+                Some(i) if i == self.selected => row.style(
+                    Style::new()
+                        .fg(selected_style_fg)
+                        .add_modifier(Modifier::REVERSED),
+                ),
+                Some(_) => row.style(Style::new().fg(Color::Yellow)),
+                None => row,
+            }
+        });
+
+        let block = Block::new()
+            .title(format!("Sync conflict ({} left)", self.remaining))
+            .title_bottom(
+                Line::from(
+                    " (L) keep memory | (R) keep disk | (↑↓←→) pick fields | (Enter) keep merged ",
+                )
+                .italic(),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(selected_style_fg));
+
+        Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .style(self.style)
+        .block(block)
+        .render(area, buf);
+    }
+}
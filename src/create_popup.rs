@@ -1,13 +1,19 @@
-use crate::InputFocus;
+use crate::date_picker::DatePicker;
+use crate::{InputFocus, Priority, Progress, DESCRIPTION_LIMIT, NAME_LIMIT};
 use ratatui::buffer::Buffer;
-use ratatui::layout::Rect;
-use ratatui::prelude::{Color, Style, Text};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Span, Style, Stylize, Text};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
 
-#[derive(Default)]
 pub struct CreatePopup {
     pub name: String,
     pub description: String,
+    pub duplicate_of: Option<String>,
+    pub duplicate_created: Option<String>, // Set when the name is exactly the same
+    pub name_missing: bool,
+    pub progress: Progress,
+    pub priority: Option<Priority>,
+    pub due: DatePicker,
     pub style: Style,
 }
 
@@ -21,7 +27,9 @@ impl CreatePopup {
     ) {
         Clear.render(area, buf);
 
-        let name_border_color = if input_focus == InputFocus::Name {
+        let name_border_color = if self.name_missing {
+            Color::Red
+        } else if input_focus == InputFocus::Name {
             selected_style_fg
         } else {
             Color::White
@@ -33,27 +41,127 @@ impl CreatePopup {
         };
 
         let name_area = Rect::new(area.x, area.y, area.width, 3);
+        let mut name_block = Block::new()
+            .title("Name")
+            .title_bottom(counter(self.name.chars().count(), NAME_LIMIT))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(name_border_color));
+        if self.name_missing {
+            name_block = name_block.title_bottom(Line::from(" Name is required ").red());
+        }
         Paragraph::new(Text::from(self.name))
             .wrap(Wrap { trim: true })
             .style(self.style)
+            .block(name_block)
+            .render(name_area, buf);
+
+        if let Some(existing) = self.duplicate_of {
+            let warning = match self.duplicate_created {
+                Some(created) => {
+                    format!("Already an open task, created {created} - (Ctrl+G) jump to it")
+                }
+                None => format!("Similar to open task \"{existing}\" - (Ctrl+G) jump to it"),
+            };
+            buf.set_stringn(
+                area.x + 1,
+                area.y + 3,
+                warning,
+                area.width.saturating_sub(2) as usize,
+                Style::default().fg(Color::Yellow),
+            );
+        }
+
+        // Status and priority side by side, cycled with Left/Right
+        let (progress_color, progress_text) = self.progress.display();
+        let (priority_color, priority_text) = self
+            .priority
+            .map_or((Color::DarkGray, "None".to_string()), |priority| {
+                priority.display()
+            });
+        let choice_area = Rect::new(
+            area.x,
+            area.y + 4,
+            area.width,
+            3.min(area.height.saturating_sub(4)),
+        );
+        let [status_area, priority_area] =
+            Layout::horizontal([Constraint::Fill(1); 2]).areas(choice_area);
+        for (title, text, color, focus, choice_area) in [
+            (
+                "Status",
+                progress_text,
+                progress_color,
+                InputFocus::Status,
+                status_area,
+            ),
+            (
+                "Priority",
+                priority_text,
+                priority_color,
+                InputFocus::Priority,
+                priority_area,
+            ),
+        ] {
+            let border_color = if input_focus == focus {
+                selected_style_fg
+            } else {
+                Color::White
+            };
+            Paragraph::new(Line::from(vec![
+                Span::from("◂ "),
+                Span::styled(text, Style::default().fg(color)),
+                Span::from(" ▸"),
+            ]))
+            .style(self.style)
             .block(
                 Block::new()
-                    .title("Name")
+                    .title(title)
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(name_border_color)),
+                    .border_style(Style::default().fg(border_color)),
             )
-            .render(name_area, buf);
+            .render(choice_area, buf);
+        }
 
-        let description_area = Rect::new(area.x, area.y + 4, area.width, area.height - 4);
+        let due_height = DatePicker::HEIGHT.min(area.height.saturating_sub(7));
+        let description_length = self.description.chars().count();
+        let description_area = Rect::new(
+            area.x,
+            area.y + 7,
+            area.width,
+            area.height.saturating_sub(7 + due_height),
+        );
         Paragraph::new(Text::from(self.description))
             .wrap(Wrap { trim: true })
             .style(self.style)
             .block(
                 Block::new()
                     .title("Description")
+                    .title(Line::from(" (Ctrl+E) open in $EDITOR ").right_aligned())
+                    .title_bottom(counter(description_length, DESCRIPTION_LIMIT))
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(description_border_color)),
             )
             .render(description_area, buf);
+
+        let due_area = Rect::new(area.x, area.bottom() - due_height, area.width, due_height);
+        self.due.render(
+            due_area,
+            buf,
+            input_focus == InputFocus::Due,
+            selected_style_fg,
+        );
     }
 }
+
+/// The characters left in a field, turning yellow once it's full.
+fn counter(length: usize, limit: usize) -> Line<'static> {
+    let remaining = limit.saturating_sub(length);
+    let color = if remaining == 0 {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    };
+    Line::from(format!(" {remaining} left "))
+        .fg(color)
+        .right_aligned()
+}
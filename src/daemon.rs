@@ -0,0 +1,223 @@
+//! `todo-tui daemon`: keeps `data.json` loaded and answers one command per
+//! line on a Unix socket, so quick-capture scripts don't have to parse and
+//! rewrite the file themselves, e.g. `echo "add Buy milk" | nc -U todo-tui.sock`.
+
+use crate::preferences::Preferences;
+use crate::stats::Completions;
+use crate::{modified_time, read_list, save_list, timestamps, Data, Progress, NAME_LIMIT};
+use chrono::Local;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const SOCKET_PATH: &str = "todo-tui.sock";
+
+const USAGE: &str = "commands: add <name> [-- <description>], list, done <number>";
+
+/// The list as the daemon last read or wrote it.
+struct Store {
+    path: PathBuf,
+    items: Vec<Data>,
+    modified: Option<SystemTime>,
+    new_status: Progress, // What `add` creates todos as, from the preferences
+}
+
+impl Store {
+    fn open(path: &Path, new_status: Progress) -> Self {
+        let mut store = Self {
+            path: path.to_path_buf(),
+            items: Vec::new(),
+            modified: None,
+            new_status,
+        };
+        store.refresh();
+        store
+    }
+
+    /// Rereads the file if something else, like the TUI, changed it.
+    fn refresh(&mut self) {
+        let modified = modified_time(&self.path);
+        if modified.is_some() && modified == self.modified {
+            return;
+        }
+        match read_list(&self.path) {
+            Ok(items) => self.items = items,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => self.items.clear(),
+            Err(e) => tracing::warn!(path = %self.path.display(), "daemon couldn't read: {e}"),
+        }
+        self.modified = modified;
+    }
+
+    fn save(&mut self) -> Result<(), String> {
+        save_list(&self.path, &self.items)
+            .map_err(|e| format!("couldn't save {}: {e}", self.path.display()))?;
+        self.modified = modified_time(&self.path);
+        Ok(())
+    }
+
+    /// Runs one command, returning the lines to send back before `ok`.
+    fn respond(&mut self, line: &str) -> Result<Vec<String>, String> {
+        let (command, arguments) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        let arguments = arguments.trim();
+        match command {
+            "add" => {
+                let (name, description) = arguments.split_once(" -- ").unwrap_or((arguments, ""));
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err("missing name".to_string());
+                }
+                if name.chars().count() > NAME_LIMIT {
+                    return Err(format!("name is longer than {NAME_LIMIT} characters"));
+                }
+                self.items.push(Data {
+                    name: name.to_string(),
+                    description: description.trim().to_string(),
+                    progress: self.new_status.clone(),
+                    created: timestamps::now(),
+                    ..Data::default()
+                });
+                self.save()?;
+                tracing::info!(name, "daemon added todo");
+                Ok(vec![format!("added {}", self.items.len())])
+            }
+            "list" => Ok(self
+                .items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| format!("{}\t{}\t{}", i + 1, item.progress.display().1, item.name))
+                .collect()),
+            "done" => {
+                let index = arguments
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|number| number.checked_sub(1))
+                    .filter(|&index| index < self.items.len())
+                    .ok_or_else(|| format!("no todo number {arguments:?}, see list"))?;
+                let newly_done = self.items[index].progress != Progress::Done;
+                self.items[index].progress = Progress::Done;
+                self.save()?;
+                if newly_done {
+                    let path = Completions::path(&self.path);
+                    let mut completions = Completions::load(&path);
+                    completions.record(Local::now().date_naive(), 1, 0);
+                    if let Err(e) = completions.save(&path) {
+                        tracing::warn!(path = %path.display(), "couldn't save completions: {e}");
+                    }
+                }
+                tracing::info!(name = %self.items[index].name, "daemon completed todo");
+                Ok(Vec::new())
+            }
+            _ => Err(USAGE.to_string()),
+        }
+    }
+
+    /// Answers every line a client sends with the command's output and `ok`,
+    /// or a single `error: ...` line.
+    fn serve(&mut self, reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            self.refresh();
+            match self.respond(&line) {
+                Ok(lines) => {
+                    for line in lines {
+                        writeln!(writer, "{line}")?;
+                    }
+                    writeln!(writer, "ok")?;
+                }
+                Err(e) => writeln!(writer, "error: {e}")?,
+            }
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Serves `data.json` on `todo-tui.sock` until killed.
+#[cfg(unix)]
+pub fn run() -> io::Result<()> {
+    use std::io::BufReader;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if UnixStream::connect(SOCKET_PATH).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("a daemon is already listening on {SOCKET_PATH}"),
+        ));
+    }
+    // Left behind by a daemon that was killed
+    let _ = std::fs::remove_file(SOCKET_PATH);
+
+    let listener = UnixListener::bind(SOCKET_PATH)?;
+    let preferences = Preferences::load().unwrap_or_else(|e| {
+        tracing::warn!("{e}");
+        Preferences::default()
+    });
+    let mut store = Store::open(&preferences.data_path(), preferences.new_status.clone());
+    tracing::info!(socket = SOCKET_PATH, "daemon listening");
+    println!("Listening on {SOCKET_PATH} ({USAGE})");
+
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| {
+            let reader = BufReader::new(stream.try_clone()?);
+            store.serve(reader, stream)
+        });
+        if let Err(e) = result {
+            tracing::warn!("daemon connection failed: {e}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn run() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "daemon mode needs Unix sockets",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A store on a list of its own, gone once the test ends.
+    fn store(test: &str, new_status: Progress) -> (Store, PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("todo-tui-daemon-{test}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.json");
+        (Store::open(&path, new_status), dir)
+    }
+
+    #[test]
+    fn added_todos_start_with_the_preferred_status() {
+        let (mut store, dir) = store("status", Progress::InProgress);
+        let path = store.path.clone();
+        assert_eq!(
+            store.respond("add Buy milk -- Two liters"),
+            Ok(vec!["added 1".to_string()])
+        );
+        let saved = read_list(&path).unwrap();
+        assert_eq!(saved[0].name, "Buy milk");
+        assert_eq!(saved[0].description, "Two liters");
+        assert_eq!(saved[0].progress, Progress::InProgress);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn the_name_limit_counts_characters() {
+        let (mut store, dir) = store("limit", Progress::Waiting);
+        let name = "é".repeat(NAME_LIMIT);
+        assert!(store.respond(&format!("add {name}")).is_ok());
+        assert_eq!(
+            store.respond(&format!("add {name}é")),
+            Err(format!("name is longer than {NAME_LIMIT} characters"))
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
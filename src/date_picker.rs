@@ -0,0 +1,107 @@
+use crate::preferences::WeekStart;
+use chrono::{Datelike, Local, Months, NaiveDate};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::{Color, Line, Modifier, Span, Style, Stylize};
+use ratatui::widgets::{Block, Borders, Paragraph, Widget};
+
+/// Moves `date` by whole months, keeping the day where the month allows it.
+pub fn shift_months(date: NaiveDate, months: i32) -> NaiveDate {
+    let shifted = if months < 0 {
+        date.checked_sub_months(Months::new(months.unsigned_abs()))
+    } else {
+        date.checked_add_months(Months::new(months.unsigned_abs()))
+    };
+    shifted.unwrap_or(date)
+}
+
+/// A month calendar for picking a due date, following `cursor`.
+pub struct DatePicker {
+    pub date: Option<NaiveDate>, // The picked date, `None` for no due date
+    pub time: String,            // The time of day as typed, empty for none
+    pub cursor: NaiveDate,
+    pub error: Option<String>,
+    pub week_start: WeekStart,
+    pub style: Style,
+}
+
+impl DatePicker {
+    /// Rows needed for the borders, the month and weekday headers and six weeks.
+    pub const HEIGHT: u16 = 10;
+
+    pub fn render(self, area: Rect, buf: &mut Buffer, focused: bool, selected_style_fg: Color) {
+        let border_color = if self.error.is_some() {
+            Color::Red
+        } else if focused {
+            selected_style_fg
+        } else {
+            Color::White
+        };
+
+        let chosen = self.date.map_or_else(
+            || "none".to_string(),
+            |date| format!("{} {}", date.format("%a %-d %b %Y"), self.time),
+        );
+        let mut lines = vec![
+            Line::from(vec![
+                Span::from(self.cursor.format("%B %Y").to_string()).bold(),
+                Span::from(format!("  Due: {chosen}")),
+            ]),
+            Line::from(match self.week_start {
+                WeekStart::Monday => "Mo Tu We Th Fr Sa Su",
+                WeekStart::Sunday => "Su Mo Tu We Th Fr Sa",
+            })
+            .dark_gray(),
+        ];
+
+        let first = self.cursor.with_day(1).unwrap_or(self.cursor);
+        let today = Local::now().date_naive();
+        let mut week = vec![Span::from(
+            "   ".repeat(self.week_start.day_of_week(first) as usize),
+        )];
+        let mut day = first;
+        while day.month() == first.month() {
+            let mut style = Style::new();
+            if Some(day) == self.date {
+                style = style.fg(selected_style_fg).add_modifier(Modifier::BOLD);
+            }
+            if focused && day == self.cursor {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            if day == today {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            week.push(Span::styled(format!("{:>2}", day.day()), style));
+            week.push(Span::from(" "));
+
+            if self.week_start.day_of_week(day) == 6 {
+                lines.push(Line::from(std::mem::take(&mut week)));
+            }
+            day = match day.succ_opt() {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        if !week.is_empty() {
+            lines.push(Line::from(week));
+        }
+
+        let mut block = Block::new()
+            .title("Due date")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color));
+        if let Some(error) = &self.error {
+            block = block.title_bottom(Line::from(format!(" {error} ")).red());
+        } else if focused {
+            block = block.title_bottom(
+                Line::from(" (←↑↓→) day | (PgUp/PgDn) month | (Space) pick | (0-9) time | (Backspace) clear ")
+                    .dark_gray(),
+            );
+        }
+
+        Paragraph::new(lines)
+            .style(self.style)
+            .block(block)
+            .render(area, buf);
+    }
+}
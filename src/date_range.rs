@@ -0,0 +1,171 @@
+//! Filters on when todos were created or completed, like `created:this-week`
+//! or `completed:2024-01-01..2024-01-31`, for the search and `:filter`.
+
+use crate::preferences::WeekStart;
+use crate::timestamps::{self, Clock};
+use crate::{Data, DUE_FORMAT};
+use chrono::{Datelike, Days, Months, NaiveDate, Utc};
+
+pub const RANGES: &str = "today, yesterday, this-week, last-week, this-month, last-month, this-year, last-year, 7d for the last 7 days, a YYYY-MM-DD date or FROM..TO";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    Created,
+    Completed,
+}
+
+/// A range relative to today, resolved when it's matched so it moves along
+/// with the date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Range {
+    Today,
+    Yesterday,
+    ThisWeek,
+    LastWeek,
+    ThisMonth,
+    LastMonth,
+    ThisYear,
+    LastYear,
+    LastDays(u64),
+    Between(Option<NaiveDate>, Option<NaiveDate>), // Both ends included, open if unset
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateFilter {
+    pub field: DateField,
+    pub range: Range,
+}
+
+impl DateFilter {
+    /// Parses a `created:RANGE` or `completed:RANGE` term, `None` for any other term.
+    pub fn parse(term: &str) -> Option<Result<Self, String>> {
+        let (field, range) = term.split_once(':')?;
+        let field = match field.to_lowercase().as_str() {
+            "created" => DateField::Created,
+            "completed" | "done" => DateField::Completed,
+            _ => return None,
+        };
+        Some(Range::parse(range).map(|range| Self { field, range }))
+    }
+
+    /// Whether the todo's creation or completion date, in the clock's time
+    /// zone, falls in the range. Todos that aren't done have no completion date.
+    pub fn matches(&self, item: &Data, clock: Clock, week_start: WeekStart) -> bool {
+        let timestamp = match self.field {
+            DateField::Created => Some(item.created.as_str()),
+            DateField::Completed => item.completed.as_deref(),
+        };
+        let Some(at) = timestamp.and_then(timestamps::parse) else {
+            return false;
+        };
+        let (from, to) = self.range.bounds(clock.date(Utc::now()), week_start);
+        let date = clock.date(at);
+        from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to)
+    }
+
+    /// The term this filter was parsed from, e.g. `created:this-week`.
+    pub fn term(&self) -> String {
+        let field = match self.field {
+            DateField::Created => "created",
+            DateField::Completed => "completed",
+        };
+        format!("{field}:{}", self.range.label())
+    }
+}
+
+impl Range {
+    fn parse(range: &str) -> Result<Self, String> {
+        let date = |date: &str| {
+            NaiveDate::parse_from_str(date, DUE_FORMAT)
+                .map_err(|_| format!("Unknown date range \"{range}\", use {RANGES}"))
+        };
+        let optional_date = |date_text: &str| {
+            if date_text.is_empty() {
+                Ok(None)
+            } else {
+                date(date_text).map(Some)
+            }
+        };
+        Ok(match range.to_lowercase().as_str() {
+            "today" => Self::Today,
+            "yesterday" => Self::Yesterday,
+            "this-week" => Self::ThisWeek,
+            "last-week" => Self::LastWeek,
+            "this-month" => Self::ThisMonth,
+            "last-month" => Self::LastMonth,
+            "this-year" => Self::ThisYear,
+            "last-year" => Self::LastYear,
+            range => {
+                let days = range
+                    .strip_suffix('d')
+                    .and_then(|days| days.parse().ok())
+                    .filter(|&days| days > 0);
+                if let Some((from, to)) = range.split_once("..") {
+                    Self::Between(optional_date(from)?, optional_date(to)?)
+                } else if let Some(days) = days {
+                    Self::LastDays(days)
+                } else {
+                    let day = date(range)?;
+                    Self::Between(Some(day), Some(day))
+                }
+            }
+        })
+    }
+
+    /// The first and last day in the range, seen from `today`.
+    pub fn bounds(
+        self,
+        today: NaiveDate,
+        week_start: WeekStart,
+    ) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        let week = |weeks_back: u64| {
+            let start = today
+                - Days::new(u64::from(week_start.day_of_week(today)))
+                - Days::new(7 * weeks_back);
+            (start, start + Days::new(6))
+        };
+        let month = |months_back: u32| {
+            let start = today.with_day(1).unwrap_or(today) - Months::new(months_back);
+            (start, start + Months::new(1) - Days::new(1))
+        };
+        let year = |year: i32| {
+            (
+                NaiveDate::from_ymd_opt(year, 1, 1),
+                NaiveDate::from_ymd_opt(year, 12, 31),
+            )
+        };
+        let (from, to) = match self {
+            Self::Today => (today, today),
+            Self::Yesterday => (today - Days::new(1), today - Days::new(1)),
+            Self::ThisWeek => week(0),
+            Self::LastWeek => week(1),
+            Self::ThisMonth => month(0),
+            Self::LastMonth => month(1),
+            Self::ThisYear => return year(today.year()),
+            Self::LastYear => return year(today.year() - 1),
+            Self::LastDays(days) => (today - Days::new(days - 1), today),
+            Self::Between(from, to) => return (from, to),
+        };
+        (Some(from), Some(to))
+    }
+
+    fn label(self) -> String {
+        let date = |date: Option<NaiveDate>| {
+            date.map(|date| date.format(DUE_FORMAT).to_string())
+                .unwrap_or_default()
+        };
+        match self {
+            Self::Today => "today".to_string(),
+            Self::Yesterday => "yesterday".to_string(),
+            Self::ThisWeek => "this-week".to_string(),
+            Self::LastWeek => "last-week".to_string(),
+            Self::ThisMonth => "this-month".to_string(),
+            Self::LastMonth => "last-month".to_string(),
+            Self::ThisYear => "this-year".to_string(),
+            Self::LastYear => "last-year".to_string(),
+            Self::LastDays(days) => format!("{days}d"),
+            Self::Between(from, to) if from == to && from.is_some() => date(from),
+            Self::Between(from, to) => format!("{}..{}", date(from), date(to)),
+        }
+    }
+}
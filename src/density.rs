@@ -0,0 +1,39 @@
+use ratatui::text::Text;
+use serde::{Deserialize, Serialize};
+
+/// How many terminal lines each table row takes up.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Density {
+    Compact,
+    Cozy,
+    #[default]
+    Full,
+}
+
+impl Density {
+    pub const fn row_height(self) -> usize {
+        match self {
+            Self::Compact => 1,
+            Self::Cozy => 2,
+            Self::Full => 4,
+        }
+    }
+
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Compact => Self::Cozy,
+            Self::Cozy => Self::Full,
+            Self::Full => Self::Compact,
+        }
+    }
+
+    /// The selection marker, padded so it stays vertically centered in the row.
+    pub fn highlight_symbol(self, ascii: bool) -> Text<'static> {
+        let marker = if ascii { " > " } else { " █ " };
+        match self {
+            Self::Compact => Text::from(marker),
+            Self::Cozy => Text::from(vec![marker.into(), marker.into()]),
+            Self::Full => Text::from(vec!["".into(), marker.into(), marker.into(), "".into()]),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Todos that wait on others: the `blocked_by` field names them, e.g.
+//! `blocked_by: Order parts, Book the van`, and the todo shows as blocked for
+//! as long as one of them is open in its list.
+
+use crate::{Data, Progress};
+
+pub const BLOCKED_BY_FIELD: &str = "blocked_by";
+
+/// The open todos of `items` that `item` waits on, matched by name ignoring
+/// case. Names that aren't in the list don't block it.
+pub fn blockers<'a>(item: &Data, items: &'a [Data]) -> Vec<&'a str> {
+    let Some(names) = item.fields.get(BLOCKED_BY_FIELD) else {
+        return Vec::new();
+    };
+    let names: Vec<String> = names
+        .split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect();
+    items
+        .iter()
+        .filter(|other| other.created != item.created && other.progress != Progress::Done)
+        .filter(|other| names.contains(&other.name.trim().to_lowercase()))
+        .map(|other| other.name.as_str())
+        .collect()
+}
+
+/// Whether `item` is open and waits on an open todo.
+pub fn is_blocked(item: &Data, items: &[Data]) -> bool {
+    item.progress != Progress::Done && !blockers(item, items).is_empty()
+}
@@ -0,0 +1,147 @@
+use crate::{Data, Progress};
+
+/// Lowercases the name, drops punctuation and collapses whitespace so that
+/// "Buy milk!" and "buy  milk" compare equal.
+pub fn normalize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Two names are similar when they normalize to the same words (in any order)
+/// or are only a couple of typos apart.
+pub fn is_similar(a: &str, b: &str) -> bool {
+    let a = normalize(a);
+    let b = normalize(b);
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    if a == b {
+        return true;
+    }
+
+    let mut a_words: Vec<&str> = a.split(' ').collect();
+    let mut b_words: Vec<&str> = b.split(' ').collect();
+    a_words.sort_unstable();
+    b_words.sort_unstable();
+    if a_words == b_words {
+        return true;
+    }
+
+    // Allow roughly one typo per five characters, but never on very short names
+    let shortest = a.chars().count().min(b.chars().count());
+    shortest >= 4 && edit_distance(&a, &b) <= (shortest / 5).max(1)
+}
+
+/// Returns the index of an open task whose name is similar to `name`,
+/// ignoring the task at `skip` (the one currently being edited). A task with
+/// exactly the same name is preferred over one that is merely similar.
+pub fn find_duplicate(items: &[Data], name: &str, skip: Option<usize>) -> Option<usize> {
+    let open = || {
+        items
+            .iter()
+            .enumerate()
+            .filter(move |&(index, item)| Some(index) != skip && item.progress != Progress::Done)
+    };
+    open()
+        .find(|(_, item)| is_exact(&item.name, name))
+        .or_else(|| open().find(|(_, item)| is_similar(&item.name, name)))
+        .map(|(index, _)| index)
+}
+
+/// Whether the names are the same apart from surrounding whitespace.
+pub fn is_exact(a: &str, b: &str) -> bool {
+    !a.trim().is_empty() && a.trim() == b.trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(name: &str, progress: Progress) -> Data {
+        Data {
+            name: name.to_string(),
+            progress,
+            ..Data::default()
+        }
+    }
+
+    #[test]
+    fn names_normalize_to_lowercase_words() {
+        assert_eq!(normalize("  Buy MILK!! "), "buy milk");
+        assert_eq!(normalize("call\tmum -- today"), "call mum today");
+        assert_eq!(normalize("Café au lait"), "café au lait");
+        assert_eq!(normalize("?!"), "");
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("milk", "milk"), 0);
+        assert_eq!(edit_distance("milk", "mlik"), 2);
+        assert_eq!(edit_distance("milk", "silk"), 1);
+        assert_eq!(edit_distance("", "milk"), 4);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn similar_names_differ_in_case_order_punctuation_or_a_typo() {
+        assert!(is_similar("Buy milk", "buy  milk!"));
+        assert!(is_similar("Buy milk", "milk buy"));
+        assert!(is_similar("Renew passport", "Renew pasport"));
+        assert!(!is_similar("Buy milk", "Buy bread"));
+        // Short names need to match exactly, blank ones never do
+        assert!(!is_similar("Cat", "Car"));
+        assert!(!is_similar("!!", "??"));
+        // About one typo per five characters
+        assert!(is_similar(
+            "Write the quarterly report",
+            "Wrte the quartrly report"
+        ));
+        assert!(!is_similar("Write report", "Wrt rprt"));
+    }
+
+    #[test]
+    fn exact_names_ignore_only_surrounding_whitespace() {
+        assert!(is_exact("Buy milk", " Buy milk "));
+        assert!(!is_exact("Buy milk", "buy milk"));
+        assert!(!is_exact(" ", " "));
+    }
+
+    #[test]
+    fn duplicates_are_open_todos_other_than_the_one_edited() {
+        let items = [
+            task("Buy milk!", Progress::Waiting),
+            task("Renew passport", Progress::Done),
+            task("Buy milk", Progress::InProgress),
+        ];
+        // An exact match wins over an earlier similar one
+        assert_eq!(find_duplicate(&items, "Buy milk", None), Some(2));
+        assert_eq!(find_duplicate(&items, "buy milk", None), Some(0));
+        assert_eq!(find_duplicate(&items, "Buy milk", Some(2)), Some(0));
+        // Done todos don't count
+        assert_eq!(find_duplicate(&items, "Renew passport", None), None);
+        assert_eq!(find_duplicate(&items, "Water plants", None), None);
+    }
+}
@@ -0,0 +1,42 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::{Color, Line, Style, Stylize};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
+
+/// Shows the oldest unhandled error, shown instead of printing to the
+/// terminal while it is in raw mode.
+pub struct ErrorPopup<'a> {
+    pub message: &'a str,
+    pub can_retry: bool,
+    pub remaining: usize,
+    pub style: Style,
+}
+
+impl ErrorPopup<'_> {
+    pub fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let title = if self.remaining > 1 {
+            format!("Error (1 of {})", self.remaining)
+        } else {
+            "Error".to_string()
+        };
+        let keys = if self.can_retry {
+            " (R) retry | (Esc) ignore "
+        } else {
+            " (Esc) dismiss "
+        };
+
+        Paragraph::new(self.message)
+            .wrap(Wrap { trim: true })
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title(title)
+                    .title_bottom(Line::from(keys).italic())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Red)),
+            )
+            .render(area, buf);
+    }
+}
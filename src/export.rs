@@ -0,0 +1,121 @@
+use crate::preferences::Preferences;
+use crate::{html, org, read_list, Data, Progress};
+use chrono::Local;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "usage: todo-tui export [--format json|csv|md|org|html] [--output FILE]";
+
+/// `todo-tui export`: writes the main list to a file, or to stdout without
+/// `--output`, e.g. `todo-tui export --format html --output report.html`. The
+/// format defaults to the output file's extension.
+pub fn run(mut args: impl Iterator<Item = String>) -> io::Result<()> {
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+    let mut format = None;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--format" | "--output" => args
+                .next()
+                .ok_or_else(|| invalid(format!("{arg} needs a value\n{USAGE}")))?,
+            _ => return Err(invalid(format!("unknown argument {arg:?}\n{USAGE}"))),
+        };
+        if arg == "--format" {
+            format = Some(value);
+        } else {
+            output = Some(PathBuf::from(value));
+        }
+    }
+    let format = format
+        .or_else(|| {
+            let ext = output.as_ref()?.extension()?;
+            Some(ext.to_string_lossy().into_owned())
+        })
+        .ok_or_else(|| invalid(format!("pass --format or an --output file\n{USAGE}")))?;
+
+    let preferences = Preferences::load().unwrap_or_else(|e| {
+        tracing::warn!("{e}");
+        Preferences::default()
+    });
+    let items = read_list(&preferences.data_path())?;
+    let contents = render(&format, &items.iter().collect::<Vec<_>>())?;
+    match &output {
+        Some(path) => {
+            fs::write(path, contents)?;
+            tracing::info!(path = %path.display(), todos = items.len(), "exported");
+        }
+        None => io::stdout().write_all(contents.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Writes `items` to `path` as JSON, CSV, a Markdown checklist, an Org file or
+/// an HTML report, picked by the file extension.
+pub fn write(path: &Path, items: &[&Data]) -> io::Result<()> {
+    let format = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    fs::write(path, render(format, items)?)
+}
+
+fn render(format: &str, items: &[&Data]) -> io::Result<String> {
+    Ok(match format {
+        "json" => serde_json::to_string_pretty(items)?,
+        "csv" => to_csv(items),
+        "md" => to_markdown(items),
+        "org" => org::to_org(items),
+        "html" => html::to_html(items, Local::now().date_naive()),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Export to a .json, .csv, .md, .org or .html file",
+            ))
+        }
+    })
+}
+
+fn to_csv(items: &[&Data]) -> String {
+    let mut csv = String::from("name,description,progress,created,starred,percent\n");
+    for item in items {
+        let fields = [
+            csv_field(&item.name),
+            csv_field(&item.description),
+            item.progress.display().1,
+            item.created.clone(),
+            item.starred.to_string(),
+            item.percent
+                .map_or_else(String::new, |percent| percent.to_string()),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_markdown(items: &[&Data]) -> String {
+    let mut markdown = String::new();
+    for item in items {
+        let checkbox = if item.progress == Progress::Done {
+            "x"
+        } else {
+            " "
+        };
+        match item.percent {
+            Some(percent) => {
+                markdown.push_str(&format!("- [{checkbox}] {} ({percent}%)\n", item.name));
+            }
+            None => markdown.push_str(&format!("- [{checkbox}] {}\n", item.name)),
+        }
+        for line in item.description.lines() {
+            markdown.push_str(&format!("  {line}\n"));
+        }
+    }
+    markdown
+}
@@ -0,0 +1,39 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::process::Command;
+
+/// The user's editor from `$VISUAL` or `$EDITOR`, falling back to `vi`.
+pub fn editor() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Opens `text` in the editor and returns it as saved, without the trailing
+/// newline most editors add. Blocks until the editor exits, so the terminal
+/// has to be handed over first.
+pub fn edit(text: &str) -> io::Result<String> {
+    let path = env::temp_dir().join(format!("todo-tui-{}.md", std::process::id()));
+    fs::write(&path, text)?;
+
+    // Allow arguments, e.g. `code --wait`
+    let editor = editor();
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program).args(words).arg(&path).status();
+
+    let edited = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path),
+        Ok(status) => Err(io::Error::other(format!("{program} exited with {status}"))),
+        Err(e) => Err(io::Error::new(
+            e.kind(),
+            format!("couldn't run {program}: {e}"),
+        )),
+    };
+    let _ = fs::remove_file(&path);
+
+    Ok(edited?.trim_end_matches(['\n', '\r']).to_string())
+}
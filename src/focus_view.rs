@@ -0,0 +1,69 @@
+use crate::Data;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Style, Stylize, Text};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap};
+use std::time::Duration;
+use tui_big_text::{BigText, PixelSize};
+
+const ACTIONS_TEXT: &str = "(C) complete and pull next | (Esc) leave focus";
+
+pub struct FocusView<'a> {
+    pub item: &'a Data,
+    pub elapsed: Duration,
+    pub style: Style,
+}
+
+impl FocusView<'_> {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        Clear.render(area, buf);
+
+        let block = Block::new()
+            .title(" Focus ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .border_style(Style::default().fg(selected_style_fg))
+            .style(self.style);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [_, title_area, description_area, timer_area, actions_area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(4),
+            Constraint::Max(10),
+            Constraint::Length(8),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        BigText::builder()
+            .pixel_size(PixelSize::HalfHeight)
+            .style(Style::new().fg(selected_style_fg))
+            .lines(vec![Line::from(self.item.name.clone())])
+            .centered()
+            .build()
+            .render(title_area, buf);
+
+        Paragraph::new(Text::from(self.item.description.clone()))
+            .wrap(Wrap { trim: true })
+            .centered()
+            .style(self.style)
+            .render(description_area, buf);
+
+        let seconds = self.elapsed.as_secs();
+        let timer = format!("{:02}:{:02}", seconds / 60, seconds % 60);
+        BigText::builder()
+            .pixel_size(PixelSize::Full)
+            .style(self.style)
+            .lines(vec![Line::from(timer)])
+            .centered()
+            .build()
+            .render(timer_area, buf);
+
+        Paragraph::new(Line::from(ACTIONS_TEXT).italic())
+            .centered()
+            .style(self.style)
+            .render(actions_area, buf);
+    }
+}
@@ -0,0 +1,180 @@
+//! `todo-tui generate`: writes made-up todos to a file, for trying out themes,
+//! checking how the app copes with long lists and taking screenshots, e.g.
+//! `todo-tui generate --count 500 --seed 7 --output big.json`.
+
+use crate::{export, Data, Priority, Progress, DESCRIPTION_LIMIT, DUE_FORMAT, NAME_LIMIT};
+use chrono::{Duration, Local, SecondsFormat, Utc};
+use fastrand::Rng;
+use std::io;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: todo-tui generate [--count N] [--seed N] [--output FILE]";
+
+const VERBS: [&str; 12] = [
+    "Write", "Review", "Fix", "Plan", "Call", "Buy", "Clean", "Book", "Update", "Read", "Refactor",
+    "Send",
+];
+const OBJECTS: [&str; 16] = [
+    "the quarterly report",
+    "grandma about Sunday",
+    "milk",
+    "the flaky login test",
+    "flights to Lisbon",
+    "the garage",
+    "café receipts",
+    "Straße 12 lease renewal",
+    "日本語 lesson notes",
+    "the onboarding docs",
+    "dentist appointment",
+    "birthday present 🎁",
+    "server certificates",
+    "Dvořák symphony tickets",
+    "the README",
+    "invoice #2024-117",
+];
+const SENTENCES: [&str; 10] = [
+    "Needs to happen before the end of the week.",
+    "Ask **Sam** first, they know the details.",
+    "See the notes from the last meeting.",
+    "Keep it *short*.",
+    "Check `config.toml` before starting.",
+    "Blocked until the budget is approved.",
+    "Remember the 10% discount code.",
+    "Très important — ne pas oublier!",
+    "Zwei Exemplare, bitte.",
+    "Follow up if there is no answer by Friday.",
+];
+const TAGS: [&str; 6] = [
+    "#work",
+    "#home",
+    "#errands",
+    "#project-apollo",
+    "#health",
+    "#reading",
+];
+
+struct Options {
+    count: usize,
+    seed: Option<u64>,
+    output: PathBuf,
+}
+
+fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        count: 100,
+        seed: None,
+        output: PathBuf::from("sample.json"),
+    };
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "--count" | "--seed" | "--output" => args
+                .next()
+                .ok_or_else(|| format!("{arg} needs a value\n{USAGE}"))?,
+            _ => return Err(format!("unknown argument {arg:?}\n{USAGE}")),
+        };
+        match arg.as_str() {
+            "--count" => {
+                options.count = value
+                    .parse()
+                    .map_err(|_| format!("--count must be a number, not {value:?}"))?;
+            }
+            "--seed" => {
+                options.seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("--seed must be a number, not {value:?}"))?,
+                );
+            }
+            _ => options.output = PathBuf::from(value),
+        }
+    }
+    Ok(options)
+}
+
+/// Writes the todos as JSON, CSV or Markdown by the file extension. Refuses to
+/// overwrite a file, so a list can't be replaced by accident.
+pub fn run(args: impl Iterator<Item = String>) -> io::Result<()> {
+    let options = parse(args).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    if options.output.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", options.output.display()),
+        ));
+    }
+
+    // Printed so a run can be repeated
+    let seed = options.seed.unwrap_or_else(|| fastrand::u64(..));
+    let items = tasks(options.count, &mut Rng::with_seed(seed));
+    export::write(&options.output, &items.iter().collect::<Vec<_>>())?;
+    tracing::info!(count = items.len(), seed, path = %options.output.display(), "generated todos");
+    println!(
+        "Wrote {} todos to {} (seed {seed})",
+        items.len(),
+        options.output.display()
+    );
+    Ok(())
+}
+
+/// `count` todos with a spread of statuses, priorities, lengths and dates. The
+/// same seed gives the same todos, apart from the dates moving along with today.
+pub fn tasks(count: usize, rng: &mut Rng) -> Vec<Data> {
+    (0..count).map(|_| task(rng)).collect()
+}
+
+fn task(rng: &mut Rng) -> Data {
+    let mut name = format!("{} {}", pick(rng, &VERBS), pick(rng, &OBJECTS));
+    if rng.u8(..10) == 0 {
+        name.push(' ');
+        name.push_str(pick(rng, &TAGS));
+    }
+
+    let mut sentences = SENTENCES.to_vec();
+    rng.shuffle(&mut sentences);
+    sentences.truncate(rng.usize(..5));
+    if rng.u8(..4) == 0 {
+        sentences.push(pick(rng, &TAGS));
+    }
+    let description = sentences.join(" ");
+
+    let progress = match rng.u8(..10) {
+        0..=4 => Progress::Waiting,
+        5..=7 => Progress::InProgress,
+        _ => Progress::Done,
+    };
+    let created = Utc::now() - Duration::minutes(rng.i64(0..60 * 24 * 120));
+    let due = (rng.u8(..3) == 0).then(|| {
+        (Local::now().date_naive() + Duration::days(rng.i64(-10..30)))
+            .format(DUE_FORMAT)
+            .to_string()
+    });
+    let priority = match rng.u8(..6) {
+        0 => Some(Priority::High),
+        1 => Some(Priority::Medium),
+        2 => Some(Priority::Low),
+        _ => None,
+    };
+
+    Data {
+        name: truncate(name, NAME_LIMIT),
+        description: truncate(description, DESCRIPTION_LIMIT),
+        progress,
+        created: created.to_rfc3339_opts(SecondsFormat::Secs, true),
+        starred: rng.u8(..20) == 0,
+        percent: (rng.u8(..6) == 0).then(|| rng.u8(..=10) * 10),
+        due,
+        priority,
+        ..Data::default()
+    }
+}
+
+fn pick<'a>(rng: &mut Rng, words: &[&'a str]) -> &'a str {
+    words[rng.usize(..words.len())]
+}
+
+/// Cuts `text` to at most `limit` bytes, the way the inputs limit typing.
+fn truncate(mut text: String, limit: usize) -> String {
+    while text.len() > limit {
+        text.pop();
+    }
+    text
+}
@@ -0,0 +1,84 @@
+//! Recent search queries, recalled with Up and Down like shell history and kept
+//! in `search_history.json` next to the main list.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const HISTORY_FILE: &str = "search_history.json";
+const MAX_ENTRIES: usize = 50;
+
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<String>,    // Oldest first
+    position: Option<usize>, // The recalled entry while browsing
+    draft: String,           // What was typed before browsing started
+}
+
+impl History {
+    pub fn path(main_list: &Path) -> PathBuf {
+        main_list.with_file_name(HISTORY_FILE)
+    }
+
+    /// Empty if the file is missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        let entries = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            entries,
+            ..Self::default()
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(&self.entries)?)
+    }
+
+    /// Adds `query` as the newest entry, moving it there if it was used before.
+    pub fn push(&mut self, query: &str) {
+        let query = query.trim();
+        self.reset();
+        if query.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != query);
+        self.entries.push(query.to_string());
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// The entry before the recalled one, or the newest one, remembering
+    /// `typed` to come back to. Stays on the oldest entry.
+    pub fn older(&mut self, typed: &str) -> Option<&str> {
+        let position = match self.position {
+            Some(position) => position.saturating_sub(1),
+            None => {
+                self.draft = typed.to_string();
+                self.entries.len().checked_sub(1)?
+            }
+        };
+        self.position = Some(position);
+        Some(&self.entries[position])
+    }
+
+    /// The entry after the recalled one, or what was typed past the newest.
+    pub fn newer(&mut self) -> Option<&str> {
+        let position = self.position? + 1;
+        if position < self.entries.len() {
+            self.position = Some(position);
+            Some(&self.entries[position])
+        } else {
+            self.position = None;
+            Some(&self.draft)
+        }
+    }
+
+    /// Stops browsing, e.g. once the recalled query is edited.
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+}
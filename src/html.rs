@@ -0,0 +1,122 @@
+//! A standalone HTML report of the todos, grouped by status, with the styles
+//! inlined so the file can be mailed around or printed as it is.
+
+use crate::{Data, Progress};
+use chrono::NaiveDate;
+
+const STYLE: &str = "
+body { font: 15px/1.5 system-ui, sans-serif; color: #1e293b; max-width: 52rem; margin: 2rem auto; padding: 0 1rem; }
+h1 { margin-bottom: 0; }
+.generated { color: #64748b; margin-top: 0; }
+h2 { border-bottom: 2px solid; padding-bottom: .2rem; margin-top: 2rem; }
+h2.waiting { border-color: #ef4444; }
+h2.in-progress { border-color: #eab308; }
+h2.done { border-color: #22c55e; }
+ul { list-style: none; padding: 0; }
+li { padding: .6rem .8rem; margin: .4rem 0; border-radius: 6px; background: #f1f5f9; break-inside: avoid; }
+li.overdue { background: #fee2e2; border-left: 4px solid #dc2626; }
+li.done .name { text-decoration: line-through; color: #64748b; }
+.name { font-weight: 600; }
+.meta { font-size: .85rem; color: #475569; }
+.meta span { margin-right: 1rem; }
+.overdue .due { color: #b91c1c; font-weight: 600; }
+.priority-high { color: #dc2626; }
+.priority-medium { color: #ca8a04; }
+.priority-low { color: #2563eb; }
+.tag { background: #e2e8f0; border-radius: 4px; padding: 0 .3rem; }
+.description { white-space: pre-wrap; margin: .3rem 0 0; }
+@media print { body { margin: 0; } li { background: none; border: 1px solid #cbd5e1; } }
+";
+
+/// Open todos due before `today` are highlighted as overdue.
+pub fn to_html(items: &[&Data], today: NaiveDate) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Todos</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>Todos</h1>\n<p class=\"generated\">{} todos, {}</p>\n",
+        items.len(),
+        today.format("%A %-d %B %Y")
+    );
+
+    for (progress, class) in [
+        (Progress::InProgress, "in-progress"),
+        (Progress::Waiting, "waiting"),
+        (Progress::Done, "done"),
+    ] {
+        let group: Vec<&&Data> = items
+            .iter()
+            .filter(|item| item.progress == progress)
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        html.push_str(&format!(
+            "<h2 class=\"{class}\">{} ({})</h2>\n<ul>\n",
+            progress.display().1,
+            group.len()
+        ));
+        for item in group {
+            html.push_str(&entry(item, class, today));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn entry(item: &Data, class: &str, today: NaiveDate) -> String {
+    let due = item.due_date();
+    let overdue = item.progress != Progress::Done && due.is_some_and(|due| due < today);
+    let mut entry = format!(
+        "<li class=\"{class}{}\">\n<div class=\"name\">{}{}</div>\n",
+        if overdue { " overdue" } else { "" },
+        if item.starred { "★ " } else { "" },
+        escape(&item.name)
+    );
+
+    let mut meta = Vec::new();
+    if let Some(priority) = item.priority {
+        let name = priority.display().1;
+        meta.push(format!(
+            "<span class=\"priority-{}\">{name} priority</span>",
+            name.to_lowercase()
+        ));
+    }
+    if let Some(due) = due {
+        let label = if overdue { "Overdue" } else { "Due" };
+        meta.push(format!(
+            "<span class=\"due\">{label} {}</span>",
+            due.format("%a %-d %b %Y")
+        ));
+    }
+    if let Some(percent) = item.percent {
+        meta.push(format!("<span>{percent}% complete</span>"));
+    }
+    let tags = item.tags();
+    if !tags.is_empty() {
+        let tags: Vec<String> = tags
+            .iter()
+            .map(|tag| format!("<span class=\"tag\">#{}</span>", escape(tag)))
+            .collect();
+        meta.push(tags.join(" "));
+    }
+    if !meta.is_empty() {
+        entry.push_str(&format!("<div class=\"meta\">{}</div>\n", meta.join("")));
+    }
+
+    if !item.description.trim().is_empty() {
+        entry.push_str(&format!(
+            "<p class=\"description\">{}</p>\n",
+            escape(item.description.trim())
+        ));
+    }
+    entry.push_str("</li>\n");
+    entry
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,98 @@
+use crate::Progress;
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+/// Built-in glyph sets. `Ascii` is the fallback for terminals or fonts that
+/// render the others as mojibake.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IconSet {
+    #[default]
+    Unicode,
+    NerdFont,
+    Ascii,
+}
+
+/// Per-glyph overrides from the preferences file, applied on top of the set.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct IconOverrides {
+    pub waiting: Option<String>,
+    pub in_progress: Option<String>,
+    pub done: Option<String>,
+    pub checked: Option<String>,
+    pub unchecked: Option<String>,
+    pub star: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Icons {
+    pub waiting: String,
+    pub in_progress: String,
+    pub done: String,
+    pub checked: String,
+    pub unchecked: String,
+    pub star: String,
+    pub gauge_filled: String,
+    pub gauge_empty: String,
+}
+
+impl Icons {
+    pub fn new(set: IconSet, overrides: &IconOverrides) -> Self {
+        let (waiting, in_progress, done, checked, unchecked, star) = match set {
+            IconSet::Unicode => ("○", "◐", "✔", "[✔]", "[ ]", "★"),
+            IconSet::NerdFont => (
+                "\u{f017}", "\u{f110}", "\u{f00c}", "\u{f046}", "\u{f096}", "\u{f005}",
+            ),
+            IconSet::Ascii => ("-", "~", "x", "[x]", "[ ]", "*"),
+        };
+        let pick = |custom: &Option<String>, default: &str| {
+            custom.clone().unwrap_or_else(|| default.to_string())
+        };
+
+        Self {
+            waiting: pick(&overrides.waiting, waiting),
+            in_progress: pick(&overrides.in_progress, in_progress),
+            done: pick(&overrides.done, done),
+            checked: pick(&overrides.checked, checked),
+            unchecked: pick(&overrides.unchecked, unchecked),
+            star: pick(&overrides.star, star),
+            gauge_filled: if set == IconSet::Ascii { "#" } else { "█" }.to_string(),
+            gauge_empty: if set == IconSet::Ascii { "-" } else { "░" }.to_string(),
+        }
+    }
+
+    pub fn progress(&self, progress: &Progress) -> &str {
+        match progress {
+            Progress::Waiting => &self.waiting,
+            Progress::InProgress => &self.in_progress,
+            Progress::Done => &self.done,
+        }
+    }
+
+    /// A bar `width` glyphs wide, filled to `percent`.
+    pub fn gauge(&self, percent: u8, width: usize) -> String {
+        let filled = (usize::from(percent.min(100)) * width + 50) / 100;
+        format!(
+            "{}{}",
+            self.gauge_filled.repeat(filled),
+            self.gauge_empty.repeat(width - filled)
+        )
+    }
+
+    pub fn checkbox(&self, checked: bool) -> &str {
+        if checked {
+            &self.checked
+        } else {
+            &self.unchecked
+        }
+    }
+
+    /// Display width of the widest status glyph, used to size the icon column.
+    pub fn column_width(&self) -> u16 {
+        [&self.waiting, &self.in_progress, &self.done]
+            .iter()
+            .map(|icon| u16::try_from(icon.width()).unwrap_or(0))
+            .max()
+            .unwrap_or(1)
+    }
+}
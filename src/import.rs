@@ -0,0 +1,218 @@
+use crate::command::Change;
+use crate::trello::Board;
+use crate::{org, timestamps, Data, Progress};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The outcome of merging imported todos into a list.
+pub struct Merge {
+    pub updated: Vec<Change>,
+    pub added: Vec<Data>,
+    pub skipped: usize,
+}
+
+/// Reads todos from a `.json` file in the `data.json` format, a single todo as
+/// shared, a `.csv` file with a `name,description,progress,created,starred,percent`
+/// header, as exported, an Org file or a Trello board exported as JSON.
+pub fn read(path: &Path) -> io::Result<Vec<Data>> {
+    let contents = fs::read_to_string(path)?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => Ok(serde_json::from_str(&contents).or_else(|e| {
+            serde_json::from_str::<Board>(&contents)
+                .map(Board::into_items)
+                .or_else(|_| serde_json::from_str::<Data>(&contents).map(|item| vec![item]))
+                .map_err(|_| e)
+        })?),
+        Some("csv") => Ok(from_csv(&contents)),
+        Some("org") => Ok(org::from_org(&contents)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Import a .json, .csv or .org file",
+        )),
+    }
+}
+
+/// Matches imported todos to existing ones by name and creation time. Exact
+/// duplicates are skipped, matches with a different description or status are
+/// updated and everything else is added.
+pub fn merge(items: &[Data], imported: Vec<Data>) -> Merge {
+    let mut merge = Merge {
+        updated: Vec::new(),
+        added: Vec::new(),
+        skipped: 0,
+    };
+
+    for new in imported {
+        let same = |item: &Data| item.name == new.name && item.created == new.created;
+        let Some(index) = items.iter().position(same) else {
+            if merge.added.iter().any(same) {
+                merge.skipped += 1;
+            } else {
+                merge.added.push(new);
+            }
+            continue;
+        };
+
+        let item = &items[index];
+        let change = Change {
+            index,
+            name: None,
+            description: (item.description != new.description).then_some(new.description),
+            progress: (item.progress != new.progress).then_some(new.progress),
+            remove: false,
+        };
+        if change.description.is_none() && change.progress.is_none()
+            || merge.updated.iter().any(|update| update.index == index)
+        {
+            merge.skipped += 1;
+        } else {
+            merge.updated.push(change);
+        }
+    }
+    merge
+}
+
+fn from_csv(contents: &str) -> Vec<Data> {
+    let mut rows = parse_csv(contents).into_iter();
+    let Some(header) = rows.next() else {
+        return Vec::new();
+    };
+    let column = |name: &str| header.iter().position(|field| field.trim() == name);
+    let (name, description, progress, created, starred, percent) = (
+        column("name"),
+        column("description"),
+        column("progress"),
+        column("created"),
+        column("starred"),
+        column("percent"),
+    );
+
+    rows.filter_map(|row| {
+        let field = |column: Option<usize>| column.and_then(|i| row.get(i)).cloned();
+        let name = field(name).filter(|name| !name.is_empty())?;
+        Some(Data {
+            name,
+            description: field(description).unwrap_or_default(),
+            progress: field(progress)
+                .and_then(|progress| Progress::parse(&progress))
+                .unwrap_or_default(),
+            created: field(created)
+                .filter(|created| !created.is_empty())
+                .unwrap_or_else(timestamps::now),
+            starred: field(starred).is_some_and(|starred| starred == "true"),
+            percent: field(percent).and_then(|percent| percent.parse().ok()),
+            ..Data::default()
+        })
+    })
+    .collect()
+}
+
+/// Splits CSV into rows of fields, honouring quoted fields with `""` escapes
+/// and embedded newlines.
+pub fn parse_csv(contents: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            '\r' if !quoted => {}
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(name: &str, created: &str) -> Data {
+        Data {
+            name: name.to_string(),
+            created: created.to_string(),
+            ..Data::default()
+        }
+    }
+
+    #[test]
+    fn csv_fields_can_be_quoted_with_commas_quotes_and_newlines() {
+        let rows = parse_csv("a,\"b, c\",\"say \"\"hi\"\"\"\r\n\"two\nlines\",,last");
+        assert_eq!(
+            rows,
+            [
+                vec!["a", "b, c", "say \"hi\""],
+                vec!["two\nlines", "", "last"],
+            ]
+        );
+        assert!(parse_csv("").is_empty());
+        // A trailing newline doesn't add an empty row
+        assert_eq!(parse_csv("a,b\n"), [vec!["a", "b"]]);
+    }
+
+    #[test]
+    fn csv_columns_are_found_by_their_header() {
+        let csv = "percent, starred ,name,progress,created,description\n\
+                   40,true,Buy milk,in progress,2024-01-01T12:00:00Z,\"Two, liters\"\n\
+                   ,,,done,,Nameless\n\
+                   ,false,Call mum,bogus,,\n";
+        let items = from_csv(csv);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].name, "Buy milk");
+        assert_eq!(items[0].description, "Two, liters");
+        assert_eq!(items[0].progress, Progress::InProgress);
+        assert_eq!(items[0].created, "2024-01-01T12:00:00Z");
+        assert!(items[0].starred);
+        assert_eq!(items[0].percent, Some(40));
+        // Unknown statuses fall back to the default, a missing creation time to now
+        assert_eq!(items[1].progress, Progress::Waiting);
+        assert!(!items[1].created.is_empty());
+        assert!(!items[1].starred);
+        assert_eq!(items[1].percent, None);
+    }
+
+    #[test]
+    fn imported_todos_are_added_updated_or_skipped() {
+        let items = [task("Buy milk", "1"), task("Call mum", "2")];
+        let mut done = task("Call mum", "2");
+        done.progress = Progress::Done;
+        let imported = vec![
+            task("Buy milk", "1"),     // Exact duplicate
+            done.clone(),              // Changed status
+            done,                      // The same update twice
+            task("Book dentist", "3"), // New
+            task("Book dentist", "3"), // New, twice in the file
+            task("Buy milk", "4"),     // Same name, another todo
+        ];
+        let merge = merge(&items, imported);
+
+        let added: Vec<&str> = merge
+            .added
+            .iter()
+            .map(|item| item.created.as_str())
+            .collect();
+        assert_eq!(added, ["3", "4"]);
+        assert_eq!(merge.updated.len(), 1);
+        assert_eq!(merge.updated[0].index, 1);
+        assert_eq!(merge.updated[0].progress, Some(Progress::Done));
+        assert_eq!(merge.updated[0].description, None);
+        assert_eq!(merge.skipped, 3);
+    }
+}
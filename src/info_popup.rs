@@ -3,17 +3,10 @@ use ratatui::layout::Rect;
 use ratatui::prelude::{Color, Line, Style, Text};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
 use tui_big_text::{BigText, PixelSize};
-#[derive(Debug, Default)]
-pub struct Checkbox {
-    pub checked: bool,
-    pub label: String,
-}
 #[derive(Default)]
 pub struct InfoPopup<'a> {
     pub title: Line<'a>,
     pub information: Line<'a>,
-    pub checkboxes: Vec<Checkbox>,
-    pub selected_checkbox: usize,
     pub style: Style,
 }
 impl InfoPopup<'_> {
@@ -35,52 +28,11 @@ impl InfoPopup<'_> {
         // Render the title
         big_text.render(name_area, buf);
 
-        let columns: usize = 3;
-        let rows = (self.checkboxes.len() + columns - 1) / columns; // Calculate rows dynamically
-        #[allow(clippy::cast_possible_truncation)]
-        let checkbox_width = area.width / columns as u16;
-        let checkbox_height: u16 = 1;
-
-        // Render the checkboxes
-        for (i, checkbox) in self.checkboxes.iter().enumerate() {
-            let row: usize = i / columns;
-            let col: usize = i % columns;
-
-            #[allow(clippy::cast_possible_truncation)]
-            let checkbox_area = Rect::new(
-                area.x + (col as u16) * checkbox_width,
-                area.y + top_offset + 5 + row as u16 * (checkbox_height + 1),
-                checkbox_width,
-                checkbox_height,
-            );
-
-            let checkbox_label = if checkbox.checked {
-                format!("[✔] {}", checkbox.label)
-            } else {
-                format!("[ ] {}", checkbox.label)
-            };
-
-            let style = if i == self.selected_checkbox {
-                Style::new().fg(selected_style_fg)
-            } else {
-                self.style
-            };
-
-            // Render the checkbox label
-            buf.set_string(
-                checkbox_area.x + 1,
-                checkbox_area.y + 1,
-                checkbox_label,
-                style,
-            );
-        }
-
-        #[allow(clippy::cast_possible_truncation)]
         let description_area = Rect::new(
             area.x,
-            area.y + top_offset + 5 + (rows as u16 * (checkbox_height + 1)) + 1,
+            area.y + top_offset + 5,
             area.width,
-            area.height - (top_offset + 5 + (rows as u16 * (checkbox_height + 1)) + 1),
+            area.height.saturating_sub(top_offset + 5),
         );
 
         // Split the information into spans, each span in information will be on a new line
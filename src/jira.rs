@@ -0,0 +1,316 @@
+//! `todo-tui jira`: imports Jira issues into their own list, `lists/jira.json`,
+//! either from the REST API with the `jira` preference or from a CSV export
+//! with `todo-tui jira --csv issues.csv`. Running it again updates the status
+//! and description of issues already in the list.
+
+use crate::import::{self, parse_csv};
+use crate::preferences::{Jira, Preferences};
+use crate::{read_list, save_list, timestamps, Data, Priority, Progress, DUE_FORMAT};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+use ureq::AgentBuilder;
+
+const USAGE: &str = "usage: todo-tui jira [--csv FILE]";
+const LIST_PATH: &str = "lists/jira.json";
+const TIMEOUT: Duration = Duration::from_secs(30);
+const PAGE_SIZE: usize = 100;
+
+/// Open issues assigned to you, and ones finished lately so they get marked done.
+const DEFAULT_JQL: &str =
+    "assignee = currentUser() AND (statusCategory != Done OR updated >= -30d) ORDER BY updated DESC";
+
+pub fn run(mut args: impl Iterator<Item = String>) -> io::Result<()> {
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+    let preferences = Preferences::load().unwrap_or_else(|e| {
+        tracing::warn!("{e}");
+        Preferences::default()
+    });
+
+    let issues = match args.next().as_deref() {
+        Some("--csv") => {
+            let path = args
+                .next()
+                .ok_or_else(|| invalid(format!("--csv needs a file\n{USAGE}")))?;
+            from_csv(&fs::read_to_string(path)?)
+        }
+        Some(arg) => return Err(invalid(format!("unknown argument {arg:?}\n{USAGE}"))),
+        None => {
+            let jira = preferences.jira.as_ref().ok_or_else(|| {
+                invalid(format!(
+                    "set `jira` in preferences.json to fetch issues, or pass --csv\n{USAGE}"
+                ))
+            })?;
+            fetch(jira)?
+        }
+    };
+
+    let path = preferences.data_dir().join(LIST_PATH);
+    let (added, updated, skipped) = store(&path, issues)?;
+    tracing::info!(added, updated, skipped, path = %path.display(), "imported jira issues");
+    println!(
+        "Jira: {added} added, {updated} updated, {skipped} unchanged in {}",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Merges the issues into the list at `path`, creating it if needed. Returns
+/// how many were added, updated and left as they were.
+pub fn store(path: &Path, issues: Vec<Data>) -> io::Result<(usize, usize, usize)> {
+    let mut items = if path.exists() {
+        read_list(path)?
+    } else {
+        Vec::new()
+    };
+    let merge = import::merge(&items, issues);
+    let counts = (merge.added.len(), merge.updated.len(), merge.skipped);
+    for change in merge.updated {
+        let item = &mut items[change.index];
+        if let Some(description) = change.description {
+            item.description = description;
+        }
+        if let Some(progress) = change.progress {
+            item.progress = progress;
+        }
+    }
+    items.extend(merge.added);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    save_list(path, &items)?;
+    Ok(counts)
+}
+
+/// Issues from a CSV export (Filters > Export > CSV), by the column names Jira
+/// uses. Status categories need the "Status Category" column, otherwise the
+/// status name is used.
+pub fn from_csv(contents: &str) -> Vec<Data> {
+    let mut rows = parse_csv(contents.trim_start_matches('\u{feff}')).into_iter();
+    let Some(header) = rows.next() else {
+        return Vec::new();
+    };
+    let column = |name: &str| header.iter().position(|field| field.trim() == name);
+    let (key, summary, description, status, category, created, due, priority) = (
+        column("Issue key"),
+        column("Summary"),
+        column("Description"),
+        column("Status"),
+        column("Status Category"),
+        column("Created"),
+        column("Due Date").or_else(|| column("Due date")),
+        column("Priority"),
+    );
+    // Labels repeat the column once per label
+    let labels: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| field.trim() == "Labels")
+        .map(|(i, _)| i)
+        .collect();
+
+    rows.filter_map(|row| {
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|i| row.get(i))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+        };
+        let issue = Issue {
+            key: field(key)?.to_string(),
+            summary: field(summary)?.to_string(),
+            description: field(description).unwrap_or_default().to_string(),
+            progress: progress(field(category).or(field(status)).unwrap_or_default()),
+            created: field(created).and_then(parse_created),
+            due: field(due).and_then(parse_due),
+            priority: field(priority).and_then(parse_priority),
+            labels: labels
+                .iter()
+                .filter_map(|&i| field(Some(i)))
+                .map(str::to_string)
+                .collect(),
+        };
+        Some(issue.into_data())
+    })
+    .collect()
+}
+
+#[derive(Deserialize)]
+struct SearchPage {
+    issues: Vec<ApiIssue>,
+    total: usize,
+}
+
+#[derive(Deserialize)]
+struct ApiIssue {
+    key: String,
+    fields: Fields,
+}
+
+#[derive(Deserialize)]
+struct Fields {
+    summary: String,
+    description: Option<String>,
+    status: Status,
+    created: Option<String>,
+    duedate: Option<String>,
+    priority: Option<Named>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Status {
+    name: String,
+    status_category: Option<Named>,
+}
+
+#[derive(Deserialize)]
+struct Named {
+    #[serde(default)]
+    key: String,
+    #[serde(default)]
+    name: String,
+}
+
+/// Every issue matching the JQL, a page at a time, through the v2 search API,
+/// which still returns descriptions as plain text.
+pub fn fetch(jira: &Jira) -> io::Result<Vec<Data>> {
+    let agent = AgentBuilder::new().timeout(TIMEOUT).build();
+    let url = format!("{}/rest/api/2/search", jira.url.trim_end_matches('/'));
+    let authorization = match &jira.email {
+        Some(email) => format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{email}:{}", jira.token))
+        ),
+        None => format!("Bearer {}", jira.token),
+    };
+
+    let mut issues = Vec::new();
+    loop {
+        let response = agent
+            .get(&url)
+            .set("Authorization", &authorization)
+            .query("jql", jira.jql.as_deref().unwrap_or(DEFAULT_JQL))
+            .query(
+                "fields",
+                "summary,description,status,created,duedate,priority,labels",
+            )
+            .query("startAt", &issues.len().to_string())
+            .query("maxResults", &PAGE_SIZE.to_string())
+            .call()
+            .map_err(io::Error::other)?;
+        let page: SearchPage = serde_json::from_reader(response.into_reader())?;
+        let done = page.issues.is_empty();
+        issues.extend(page.issues.into_iter().map(|issue| {
+            let fields = issue.fields;
+            Issue {
+                key: issue.key,
+                summary: fields.summary,
+                description: fields.description.unwrap_or_default(),
+                progress: match &fields.status.status_category {
+                    Some(category) => progress(&category.key),
+                    None => progress(&fields.status.name),
+                },
+                created: fields.created.as_deref().and_then(parse_created),
+                due: fields.duedate.as_deref().and_then(parse_due),
+                priority: fields
+                    .priority
+                    .as_ref()
+                    .and_then(|priority| parse_priority(&priority.name)),
+                labels: fields.labels,
+            }
+            .into_data()
+        }));
+        if done || issues.len() >= page.total {
+            return Ok(issues);
+        }
+    }
+}
+
+/// What the CSV export and the API have in common.
+struct Issue {
+    key: String,
+    summary: String,
+    description: String,
+    progress: Progress,
+    created: Option<String>,
+    due: Option<String>,
+    priority: Option<Priority>,
+    labels: Vec<String>,
+}
+
+impl Issue {
+    fn into_data(self) -> Data {
+        let mut description = self.description.trim().replace("\r\n", "\n");
+        let tags: Vec<String> = self
+            .labels
+            .iter()
+            .map(|label| format!("#{}", label.to_lowercase()))
+            .collect();
+        if !tags.is_empty() {
+            if !description.is_empty() {
+                description.push_str("\n\n");
+            }
+            description.push_str(&tags.join(" "));
+        }
+        Data {
+            // The key keeps issues apart that share a summary
+            name: format!("{} {}", self.key, self.summary.trim()),
+            description,
+            progress: self.progress,
+            // Matching on re-import needs a stable creation time
+            created: self.created.unwrap_or_else(timestamps::now),
+            due: self.due,
+            priority: self.priority,
+            ..Data::default()
+        }
+    }
+}
+
+/// A status category, by its API key or CSV name, or a status name.
+fn progress(status: &str) -> Progress {
+    match status.trim().to_lowercase().as_str() {
+        "done" | "closed" | "resolved" | "complete" | "completed" => Progress::Done,
+        "indeterminate" | "in progress" | "in review" | "in development" => Progress::InProgress,
+        _ => Progress::Waiting,
+    }
+}
+
+/// The API's `2024-01-31T10:00:00.000+0000`, or the CSV's `31/Jan/24 10:00 AM`
+/// as in the default date format.
+fn parse_created(created: &str) -> Option<String> {
+    let utc = DateTime::parse_from_str(created, "%Y-%m-%dT%H:%M:%S%.f%z")
+        .map(|created| created.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(created, "%d/%b/%y %I:%M %p").map(|naive| naive.and_utc())
+        })
+        .ok()?;
+    Some(utc.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+fn parse_due(due: &str) -> Option<String> {
+    let date = NaiveDate::parse_from_str(due, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(due, "%d/%b/%y"))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(due, "%d/%b/%y %I:%M %p").map(|naive| naive.date())
+        })
+        .ok()?;
+    Some(date.format(DUE_FORMAT).to_string())
+}
+
+/// Jira's default priorities, Highest and Lowest folded into High and Low.
+fn parse_priority(priority: &str) -> Option<Priority> {
+    match priority.to_lowercase().as_str() {
+        "highest" | "high" | "blocker" | "critical" => Some(Priority::High),
+        "medium" | "major" => Some(Priority::Medium),
+        "low" | "lowest" | "minor" | "trivial" => Some(Priority::Low),
+        _ => None,
+    }
+}
@@ -0,0 +1,179 @@
+use crate::icons::Icons;
+use crate::{Data, Progress};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Span, Style, Stylize};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Widget};
+
+const COLUMNS: [Progress; 3] = [Progress::Waiting, Progress::InProgress, Progress::Done];
+
+/// One horizontal swimlane, holding its cards (with their index into the
+/// items) in the order they appear in the table.
+pub struct Lane<'a> {
+    pub title: String,
+    pub collapsed: bool,
+    pub cards: Vec<(usize, &'a Data)>,
+}
+
+/// Where a card, or a column of an expanded lane, was drawn, so the mouse can
+/// drag cards between columns.
+pub struct Target {
+    pub area: Rect,
+    pub progress: Progress,
+    pub card: Option<usize>,
+}
+
+impl Lane<'_> {
+    fn column<'b>(
+        &'b self,
+        progress: &'b Progress,
+    ) -> impl Iterator<Item = (usize, &'b Data)> + 'b {
+        self.cards
+            .iter()
+            .copied()
+            .filter(move |(_, card)| card.progress == *progress)
+    }
+
+    /// Rows needed for the header plus the tallest column.
+    fn height(&self) -> u16 {
+        if self.collapsed {
+            return 1;
+        }
+        let tallest = COLUMNS
+            .iter()
+            .map(|progress| self.column(progress).count())
+            .max()
+            .unwrap_or(0);
+        1 + u16::try_from(tallest.max(1)).unwrap_or(u16::MAX)
+    }
+}
+
+/// A board with a column per status, split into swimlanes.
+pub struct KanbanBoard<'a> {
+    pub lanes: Vec<Lane<'a>>,
+    pub selected: Option<&'a Data>,
+    pub selected_lane: usize,
+    pub icons: &'a Icons,
+    pub style: Style,
+}
+
+impl KanbanBoard<'_> {
+    /// Draws the board, returning where every card and column ended up,
+    /// cards first.
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) -> Vec<Target> {
+        let mut cards = Vec::new();
+        let mut columns = Vec::new();
+        let block = Block::new()
+            .title("Board")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Color::White));
+        let inner = block.inner(area);
+        block.style(self.style).render(area, buf);
+
+        let [header_area, lanes_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+        let column_layout = Layout::horizontal([Constraint::Ratio(1, 3); 3]).spacing(1);
+        for (progress, column_area) in COLUMNS.iter().zip(column_layout.split(header_area).iter()) {
+            let (color, title) = progress.display();
+            Paragraph::new(title)
+                .style(Style::new().fg(color).add_modifier(Modifier::BOLD))
+                .render(*column_area, buf);
+        }
+
+        // Skip lanes from the top until the selected one fits
+        let heights: Vec<u16> = self.lanes.iter().map(Lane::height).collect();
+        let mut first = 0;
+        while first < self.selected_lane
+            && heights[first..=self.selected_lane].iter().sum::<u16>() > lanes_area.height
+        {
+            first += 1;
+        }
+
+        let mut y = lanes_area.y;
+        for (i, lane) in self.lanes.iter().enumerate().skip(first) {
+            if y >= lanes_area.bottom() {
+                break;
+            }
+            let height = heights[i].min(lanes_area.bottom() - y);
+            let lane_area = Rect::new(lanes_area.x, y, lanes_area.width, height);
+            y += height;
+
+            let marker = if lane.collapsed { "▸" } else { "▾" };
+            let mut title_style = Style::new().add_modifier(Modifier::BOLD);
+            if i == self.selected_lane {
+                title_style = title_style.fg(selected_style_fg);
+            }
+            Line::from(vec![
+                Span::styled(format!("{marker} {}", lane.title), title_style),
+                Span::from(format!(" ({})", lane.cards.len())).dark_gray(),
+            ])
+            .render(
+                Rect {
+                    height: 1,
+                    ..lane_area
+                },
+                buf,
+            );
+
+            if lane.collapsed || lane_area.height < 2 {
+                continue;
+            }
+            let cards_area = Rect {
+                y: lane_area.y + 1,
+                height: lane_area.height - 1,
+                ..lane_area
+            };
+            for (progress, column_area) in
+                COLUMNS.iter().zip(column_layout.split(cards_area).iter())
+            {
+                columns.push(Target {
+                    area: *column_area,
+                    progress: progress.clone(),
+                    card: None,
+                });
+                for (y, (index, _)) in
+                    (column_area.y..column_area.bottom()).zip(lane.column(progress))
+                {
+                    cards.push(Target {
+                        area: Rect {
+                            y,
+                            height: 1,
+                            ..*column_area
+                        },
+                        progress: progress.clone(),
+                        card: Some(index),
+                    });
+                }
+                let lines: Vec<Line> = lane
+                    .column(progress)
+                    .map(|(_, card)| {
+                        let line = Line::from(format!(
+                            "{} {}",
+                            self.icons.progress(&card.progress),
+                            card.name
+                        ));
+                        if self
+                            .selected
+                            .is_some_and(|selected| std::ptr::eq(selected, card))
+                        {
+                            line.style(
+                                Style::new()
+                                    .fg(selected_style_fg)
+                                    .add_modifier(Modifier::REVERSED),
+                            )
+                        } else {
+                            line
+                        }
+                    })
+                    .collect();
+                Paragraph::new(lines)
+                    .style(self.style)
+                    .render(*column_area, buf);
+            }
+        }
+
+        cards.extend(columns);
+        cards
+    }
+}
@@ -0,0 +1,89 @@
+use crate::preferences::Preferences;
+use crate::{read_list, save_json, Data};
+use ratatui::widgets::TableState;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const LISTS_DIR: &str = "lists";
+const MAIN_LIST_NAME: &str = "Todo";
+
+/// A todo list backed by its own JSON file. Inactive lists keep their items and
+/// selection here; the active list's live copy is held by `App`.
+#[derive(Default)]
+pub struct TodoList {
+    pub name: String,
+    pub path: PathBuf,
+    pub items: Vec<Data>,
+    pub state: TableState,
+    pub load_error: Option<String>, // Set when the file exists but couldn't be read
+}
+
+impl TodoList {
+    fn load(name: String, path: PathBuf) -> Self {
+        let (items, load_error) = match read_list(&path) {
+            Ok(items) => (items, None),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (Vec::new(), None),
+            Err(e) => (
+                Vec::new(),
+                Some(format!("Couldn't read {}: {e}", path.display())),
+            ),
+        };
+        Self {
+            name,
+            path,
+            items,
+            state: TableState::default().with_selected(0),
+            load_error,
+        }
+    }
+}
+
+/// The main list followed by every `lists/*.json` or `lists/*.md` file in the
+/// data folder, sorted by name.
+pub fn discover(preferences: &Preferences) -> Vec<TodoList> {
+    let mut paths = paths(preferences).into_iter();
+    let mut lists = vec![TodoList::load(
+        MAIN_LIST_NAME.to_string(),
+        paths.next().unwrap_or_default(),
+    )];
+
+    lists.extend(paths.filter_map(|path| {
+        let name = path.file_stem()?.to_string_lossy().into_owned();
+        Some(TodoList::load(name, path))
+    }));
+    lists
+}
+
+/// The paths of every list file, in the order `discover` loads them.
+pub fn paths(preferences: &Preferences) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(preferences.data_dir().join(LISTS_DIR))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|ext| ext == "json" || ext == "md")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths.insert(0, preferences.data_path());
+    paths
+}
+
+/// Creates an empty `lists/<name>.json` file in `dir` and returns the new list.
+pub fn create(dir: &Path, name: &str) -> io::Result<TodoList> {
+    let lists_dir = dir.join(LISTS_DIR);
+    fs::create_dir_all(&lists_dir)?;
+    let path = lists_dir.join(format!("{name}.json"));
+    if path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("A list named \"{name}\" already exists"),
+        ));
+    }
+    save_json(&path, &[])?;
+    Ok(TodoList::load(name.to_string(), path))
+}
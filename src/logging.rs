@@ -0,0 +1,19 @@
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE_PREFIX: &str = "todo-tui.log";
+
+/// Logs to a file in `logs/`, rolled over daily. Changes to todos and file IO
+/// are always logged; `verbose` adds every key press. Keep the returned guard
+/// alive until exit so buffered lines get written.
+pub fn init(verbose: bool) -> WorkerGuard {
+    let appender = tracing_appender::rolling::daily(LOG_DIR, LOG_FILE_PREFIX);
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_max_level(if verbose { Level::DEBUG } else { Level::INFO })
+        .init();
+    guard
+}
@@ -1,51 +1,371 @@
+mod actions;
+mod ascii;
+mod capture;
+mod checklist;
+mod command;
+mod command_popup;
+mod conflict_popup;
 mod create_popup;
+mod daemon;
+mod date_picker;
+mod date_range;
+mod density;
+mod dependencies;
+mod duplicates;
+mod error_popup;
+mod export;
+mod external_editor;
+mod focus_view;
+mod generate;
+mod history;
+mod html;
+mod icons;
+mod import;
 mod info_popup;
+mod jira;
+mod kanban;
+mod lists;
+mod logging;
+mod maintenance;
+mod markdown;
+mod onboarding;
+mod org;
+mod palette_popup;
+mod pick;
+mod preferences;
+mod preview_pane;
+mod prompt_popup;
+mod quick_actions;
+mod recovery;
+mod reminders;
+mod rules;
+mod scan;
+mod search;
+mod search_popup;
+mod serde_compat;
+mod settings;
+mod settings_popup;
+mod share;
+mod stats;
+mod stats_popup;
+mod storage;
+mod substitute;
+mod sync;
+mod tag_popup;
+mod tag_sidebar;
+mod task_reader;
+mod timestamps;
+mod toast;
+mod trello;
+#[cfg(test)]
+mod ui_tests;
+mod view_model;
+mod view_state;
+mod workdays;
 
-use crate::info_popup::{Checkbox, InfoPopup};
-use chrono::Local;
+use crate::actions::AppAction;
+use crate::command::{Action, Change, Command};
+use crate::command_popup::CommandPopup;
+use crate::conflict_popup::ConflictPopup;
+use crate::date_picker::DatePicker;
+use crate::date_range::DateFilter;
+use crate::density::Density;
+use crate::error_popup::ErrorPopup;
+use crate::focus_view::FocusView;
+use crate::history::History;
+use crate::icons::{IconSet, Icons};
+use crate::info_popup::InfoPopup;
+use crate::kanban::{KanbanBoard, Lane, Target};
+use crate::lists::TodoList;
+use crate::onboarding::Onboarding;
+use crate::palette_popup::PalettePopup;
+use crate::preferences::{
+    ColumnWidths, Preferences, RandomPick, SortMode, StaleDays, Swimlanes, ViewLayout,
+};
+use crate::preview_pane::{PreviewField, PreviewPane};
+use crate::prompt_popup::PromptPopup;
+use crate::recovery::Recovery;
+use crate::rules::Rule;
+use crate::search::Query;
+use crate::search_popup::{SearchHit, SearchPopup};
+use crate::settings::{Setting, Value};
+use crate::settings_popup::SettingsPopup;
+use crate::stats::{Completions, Session, TimeLog};
+use crate::stats_popup::{StatsPage, StatsPopup};
+use crate::storage::Uploader;
+use crate::sync::Conflict;
+use crate::tag_popup::TagPopup;
+use crate::tag_sidebar::TagSidebar;
+use crate::task_reader::TaskReader;
+use crate::timestamps::{Clock, Zone};
+use crate::toast::{Toast, ToastKind, ToastStack};
+use crate::view_model::{Entry, FollowedTask, ViewModel};
+use crate::view_state::{ViewState, ViewStates};
+use crate::workdays::Workdays;
+use chrono::{Datelike, Days, Local, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
 use color_eyre::Result;
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+    MouseButton, MouseEvent, MouseEventKind,
+};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+};
 use ratatui::text::Span;
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode, KeyEventKind},
-    layout::{Constraint, Layout, Margin, Rect},
+    backend::{Backend, CrosstermBackend},
+    buffer::Buffer,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Margin, Position, Rect},
     style::{self, Color, Modifier, Style, Stylize},
     text::{Line, Text},
     widgets::{
-        Block, BorderType, Cell, HighlightSpacing, Paragraph, Row, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, Table, TableState,
+        Block, BorderType, Cell, HighlightSpacing, LineGauge, Paragraph, Row, Scrollbar,
+        ScrollbarOrientation, Table, TableState, Tabs,
     },
-    DefaultTerminal, Frame,
+    Frame, Terminal, TerminalOptions, Viewport,
 };
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::{self, BufReader};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, BufReader, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
 use style::palette::tailwind;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-const PALETTES: [tailwind::Palette; 4] = [
-    tailwind::BLUE,
-    tailwind::EMERALD,
-    tailwind::INDIGO,
-    tailwind::RED,
+/// The color themes in the order `H` and `L` cycle through them, picked by
+/// the `theme` preference.
+const THEMES: [TableColors; 5] = [
+    TableColors::new(&tailwind::BLUE),
+    TableColors::new(&tailwind::EMERALD),
+    TableColors::new(&tailwind::INDIGO),
+    TableColors::new(&tailwind::RED),
+    TableColors::HIGH_CONTRAST,
 ];
+const USAGE: &str =
+    "usage: todo-tui [--inline N] [--capture [--template T]] [--no-color] [--verbose]
+       todo-tui daemon|export|generate|jira|maintenance|pick|scan [ARGS]";
 const INFO_TEXT: &str = "(I) Info | (Esc) quit";
-const ITEM_HEIGHT: usize = 4;
+const PICK_INFO_TEXT: &str = "(Enter) pick | (Esc) cancel";
 const JSON_FILE_PATH: &str = "data.json";
+const MIN_INLINE_HEIGHT: u16 = 6;
+const TICK_RATE: Duration = Duration::from_millis(250);
+const COMPLETION_FLASH: Duration = Duration::from_millis(600); // How long a todo just marked done lights up
+const CREATED_FORMAT: &str = "%Y-%m-%d %H:%M:%S"; // The default way timestamps are shown, see `timestamps`
+const DUE_FORMAT: &str = "%Y-%m-%d";
+const DUE_TIME_FORMAT: &str = "%H:%M"; // After the date, for todos due at a time of day
+/// Saves of the active list that can be undone.
+const MAX_UNDO: usize = 100;
+const NAME_LIMIT: usize = 50;
+const DESCRIPTION_LIMIT: usize = 255;
 
 fn main() -> Result<()> {
+    let _log_guard = logging::init(std::env::args().any(|arg| arg == "--verbose"));
+    let args = std::env::args().skip(2);
+    match std::env::args().nth(1).as_deref() {
+        Some("daemon") => {
+            tracing::info!("starting daemon");
+            return Ok(daemon::run()?);
+        }
+        Some("export") => return Ok(export::run(args)?),
+        Some("generate") => return Ok(generate::run(args)?),
+        Some("jira") => return Ok(jira::run(args)?),
+        Some("maintenance") => return Ok(maintenance::run(args)?),
+        Some("scan") => return Ok(scan::run(args)?),
+        Some("pick") => return pick::run(args),
+        // Anything else is a flag for the app itself
+        Some(command) if !command.starts_with('-') => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown command {command:?}\n{USAGE}"),
+            )
+            .into());
+        }
+        _ => {}
+    }
+    let inline = inline_height()?;
+    // Read before the terminal is taken over, keys come from the tty then
+    let captured = capture::read()?;
+    tracing::info!(?inline, "starting");
+    install_crash_handlers();
+
     // Enable raw mode to capture all key-presses
     enable_raw_mode()?;
     color_eyre::install()?;
+    if let Some(height) = inline {
+        return run_inline(height, captured);
+    }
+
+    let mut terminal = ratatui::init();
+    // Pastes arrive as one event instead of a key press per character
+    execute!(io::stdout(), EnableBracketedPaste, EnableMouseCapture)?;
+    let setup_error = if Onboarding::needed() {
+        Onboarding::run(&mut terminal, next_terminal_event).err()
+    } else {
+        None
+    };
+    let mut app = App::new();
+    app.no_color = no_color_requested();
+    if let Some(todos) = captured {
+        app.capture(todos);
+    }
+    if let Some(e) = setup_error {
+        app.show_error(format!("Couldn't finish the setup: {e}"), None);
+    }
+    let app_result = app.run(&mut terminal, next_terminal_event);
+
+    // Leaves raw mode however the app ended, before any error is printed
+    restore_terminal();
+    app_result
+}
+
+/// The N of `--inline N`, if given.
+fn inline_height() -> io::Result<Option<u16>> {
+    let Some(position) = std::env::args().position(|arg| arg == "--inline") else {
+        return Ok(None);
+    };
+    match std::env::args()
+        .nth(position + 1)
+        .and_then(|lines| lines.parse().ok())
+    {
+        Some(height) if height >= MIN_INLINE_HEIGHT => Ok(Some(height)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("--inline takes a number of lines, at least {MIN_INLINE_HEIGHT}"),
+        )),
+    }
+}
 
-    let terminal = ratatui::init();
-    let app_result = App::new().run(terminal);
+/// `--inline N`: the list in an N-line viewport below the prompt instead of
+/// taking over the screen, left in place on quitting.
+fn run_inline(height: u16, captured: Option<Vec<(String, String)>>) -> Result<()> {
+    INLINE_VIEWPORT.store(true, Ordering::Relaxed);
+    let mut terminal = Terminal::with_options(
+        CrosstermBackend::new(io::stdout()),
+        TerminalOptions {
+            viewport: Viewport::Inline(height),
+        },
+    )?;
+    execute!(io::stdout(), EnableBracketedPaste)?;
+    let mut app = App::new();
+    app.inline = true;
+    app.no_color = no_color_requested();
+    if let Some(todos) = captured {
+        app.capture(todos);
+    }
+    let app_result = app.run(&mut terminal, next_terminal_event);
 
-    // Disable raw mode when the program exits
-    disable_raw_mode()?;
-    ratatui::restore();
+    restore_terminal();
+    // The prompt comes back below the last frame
+    let area = terminal.get_frame().area();
+    terminal.set_cursor_position(Position::new(0, area.bottom().saturating_sub(1)))?;
+    println!();
     app_result
 }
 
+/// `--no-color`, or a non-empty `NO_COLOR` as <https://no-color.org> asks.
+fn no_color_requested() -> bool {
+    std::env::args().any(|arg| arg == "--no-color")
+        || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// Set by `todo-tui pick`, which keeps stdout for the picked todo.
+static UI_ON_STDERR: AtomicBool = AtomicBool::new(false);
+/// Set by `--inline`, which never enters the alternate screen.
+static INLINE_VIEWPORT: AtomicBool = AtomicBool::new(false);
+/// Set on SIGCONT, after the app was suspended and the terminal restored.
+static RESUMED: AtomicBool = AtomicBool::new(false);
+/// Set once the terminal's own window title was saved, to be put back on exit.
+static TITLE_SAVED: AtomicBool = AtomicBool::new(false);
+
+/// Stops the app like Ctrl+Z does outside of raw mode; the SIGTSTP handler in
+/// `install_crash_handlers` restores the terminal first.
+fn suspend() {
+    #[cfg(unix)]
+    if let Err(e) = signal_hook::low_level::raise(signal_hook::consts::SIGTSTP) {
+        tracing::warn!("couldn't suspend: {e}");
+    }
+}
+
+/// Where the UI is drawn.
+fn terminal_output() -> Box<dyn Write> {
+    if UI_ON_STDERR.load(Ordering::Relaxed) {
+        Box::new(io::stderr())
+    } else {
+        Box::new(io::stdout())
+    }
+}
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        terminal_output(),
+        DisableBracketedPaste,
+        DisableMouseCapture
+    );
+    if !INLINE_VIEWPORT.load(Ordering::Relaxed) {
+        let _ = execute!(terminal_output(), LeaveAlternateScreen);
+    }
+    if TITLE_SAVED.swap(false, Ordering::Relaxed) {
+        // Pops the title pushed in `update_window_title`, where supported
+        let _ = write!(terminal_output(), "\x1b[23;0t");
+    }
+}
+
+/// Restores the terminal before a panic message is printed, or when the app is
+/// killed, so a crash doesn't leave it in raw mode on the alternate screen.
+/// Unsaved state is already in `recovery.json` by then.
+fn install_crash_handlers() {
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        tracing::error!(%info, "panicked");
+        previous_hook(info);
+    }));
+
+    #[cfg(unix)]
+    {
+        use signal_hook::consts::{SIGCONT, SIGHUP, SIGINT, SIGTERM, SIGTSTP};
+        use signal_hook::iterator::Signals;
+        use signal_hook::low_level::emulate_default_handler;
+
+        match Signals::new([SIGHUP, SIGINT, SIGTERM, SIGTSTP, SIGCONT]) {
+            Ok(mut signals) => {
+                std::thread::spawn(move || {
+                    for signal in signals.forever() {
+                        match signal {
+                            // Ctrl+Z, or job control: hand the terminal back
+                            // before stopping, and take it again once resumed
+                            SIGTSTP => {
+                                tracing::info!("suspended");
+                                restore_terminal();
+                                let _ = emulate_default_handler(SIGTSTP);
+                            }
+                            SIGCONT => {
+                                tracing::info!("resumed");
+                                RESUMED.store(true, Ordering::Relaxed);
+                            }
+                            _ => {
+                                restore_terminal();
+                                tracing::warn!(signal, "terminated by signal");
+                                std::process::exit(128 + signal);
+                            }
+                        }
+                    }
+                });
+            }
+            Err(e) => tracing::warn!("couldn't install signal handlers: {e}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 struct TableColors {
     buffer_bg: Color,
     header_bg: Color,
@@ -55,9 +375,23 @@ struct TableColors {
     normal_row_color: Color,
     alt_row_color: Color,
     footer_border_color: Color,
+    bold_selection: bool,
 }
 
 impl TableColors {
+    /// Pure black, white and yellow, well past the contrast of the palettes.
+    const HIGH_CONTRAST: Self = Self {
+        buffer_bg: Color::Black,
+        header_bg: Color::Yellow,
+        header_fg: Color::Black,
+        row_fg: Color::White,
+        selected_style_fg: Color::Yellow,
+        normal_row_color: Color::Black,
+        alt_row_color: Color::Black,
+        footer_border_color: Color::Yellow,
+        bold_selection: true,
+    };
+
     const fn new(color: &tailwind::Palette) -> Self {
         Self {
             buffer_bg: tailwind::SLATE.c950,
@@ -68,25 +402,79 @@ impl TableColors {
             normal_row_color: tailwind::SLATE.c950,
             alt_row_color: tailwind::SLATE.c900,
             footer_border_color: color.c400,
+            bold_selection: false,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Every field has a default and unknown fields are kept as they are, so files
+/// written by older and newer versions both load and round-trip.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
 struct Data {
     name: String,
     description: String,
+    #[serde(deserialize_with = "serde_compat::or_default")]
     progress: Progress,
-    created: String,
+    created: String, // UTC in RFC 3339, or local time in `CREATED_FORMAT` in older files
+    starred: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    percent: Option<u8>, // How far along the task is, shown as a gauge when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due: Option<String>, // Due date in `DUE_FORMAT`, see `parse_due`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority: Option<Priority>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed: Option<String>, // When it was last marked done, like `created`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    updated: Option<String>, // When it was last changed in the edit popup, like `created`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimate: Option<String>, // How long the task should take, like `2h 00m`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remaining: Option<String>, // How much of it is left, when it's not the estimate minus the time logged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    remind: Vec<String>, // Its own reminders, apart from the due date, in `reminders::REMIND_FORMAT`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<Note>, // Its log of notes, oldest first
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    fields: BTreeMap<String, String>, // User-defined metadata, like `ticket: ABC-123`
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>, // Fields from newer versions
 }
+
+/// An entry of a todo's notes log, e.g. `waiting on vendor reply`, kept apart
+/// from its description.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+struct Note {
+    at: String, // When it was added, like `created`
+    text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+#[allow(clippy::enum_variant_names)]
 enum Progress {
     InProgress,
+    #[default]
     Waiting,
     Done,
 }
 
 impl Progress {
+    /// Parses `waiting`, `in_progress` (or `in-progress`, `In Progress`, `inprogress`) and `done`.
+    fn parse(value: &str) -> Option<Self> {
+        match value
+            .trim()
+            .to_lowercase()
+            .replace(['-', ' '], "_")
+            .as_str()
+        {
+            "waiting" => Some(Self::Waiting),
+            "in_progress" | "inprogress" => Some(Self::InProgress),
+            "done" => Some(Self::Done),
+            _ => None,
+        }
+    }
+
     fn display(&self) -> (Color, String) {
         match self {
             Self::Waiting => (Color::Red, "Waiting".to_string()),
@@ -94,11 +482,195 @@ impl Progress {
             Self::Done => (Color::Green, "Done".to_string()),
         }
     }
+
+    /// The next status in Waiting -> In Progress -> Done order, wrapping around.
+    fn cycle(&self, forward: bool) -> Self {
+        match (self, forward) {
+            (Self::Waiting, true) | (Self::Done, false) => Self::InProgress,
+            (Self::InProgress, true) | (Self::Waiting, false) => Self::Done,
+            (Self::Done, true) | (Self::InProgress, false) => Self::Waiting,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    fn display(self) -> (Color, String) {
+        match self {
+            Self::Low => (Color::Blue, "Low".to_string()),
+            Self::Medium => (Color::Yellow, "Medium".to_string()),
+            Self::High => (Color::Red, "High".to_string()),
+        }
+    }
+
+    /// The marker shown before the name in the table.
+    const fn marker(self) -> &'static str {
+        match self {
+            Self::Low => "!",
+            Self::Medium => "!!",
+            Self::High => "!!!",
+        }
+    }
+
+    /// Cycles None -> Low -> Medium -> High, wrapping around.
+    fn cycle(current: Option<Self>, forward: bool) -> Option<Self> {
+        let order = [None, Some(Self::Low), Some(Self::Medium), Some(Self::High)];
+        let position = order.iter().position(|p| *p == current).unwrap_or(0);
+        let next = if forward {
+            (position + 1) % order.len()
+        } else {
+            (position + order.len() - 1) % order.len()
+        };
+        order[next]
+    }
 }
 
-impl Default for Progress {
-    fn default() -> Self {
-        Self::Waiting
+impl Data {
+    /// Whole days since the task was created, if the timestamp parses.
+    fn age_days(&self) -> Option<i64> {
+        let created = timestamps::parse(&self.created)?;
+        Some((Utc::now() - created).num_days())
+    }
+
+    /// How pressing the todo is, for the urgency sort: points for its
+    /// priority, a due date that's close or past, being in progress and age.
+    fn urgency(&self, today: NaiveDate) -> i64 {
+        if self.progress == Progress::Done {
+            return 0;
+        }
+        let priority = match self.priority {
+            Some(Priority::High) => 60,
+            Some(Priority::Medium) => 40,
+            Some(Priority::Low) => 20,
+            None => 0,
+        };
+        // Full points once overdue, none two weeks or more ahead
+        let due = self.due_date().map_or(0, |due| {
+            let days = (due - today).num_days().clamp(0, 14);
+            120 * (14 - days) / 14
+        });
+        let in_progress = if self.progress == Progress::InProgress {
+            40
+        } else {
+            0
+        };
+        let age = self.age_days().unwrap_or(0).clamp(0, 365) * 20 / 365;
+        priority + due + in_progress + age
+    }
+
+    /// Sets a user-defined field from `key: value`, removing it when the value
+    /// is empty. `estimate` and `remaining` take a duration and are kept apart.
+    fn set_field(&mut self, input: &str) -> Result<(), String> {
+        let (key, value) = input
+            .split_once(':')
+            .ok_or("Type the field as key: value, e.g. ticket: ABC-123")?;
+        let (key, value) = (key.trim(), value.trim());
+        if key.is_empty() {
+            return Err("The field needs a name before the colon".to_string());
+        }
+        let effort = match key.to_lowercase().as_str() {
+            "estimate" => Some(&mut self.estimate),
+            "remaining" => Some(&mut self.remaining),
+            _ => None,
+        };
+        if let Some(effort) = effort {
+            *effort = if value.is_empty() {
+                None
+            } else {
+                let seconds = stats::parse_duration(value)
+                    .ok_or("Type the time as a duration, e.g. 2h, 45m or 1h 30m")?;
+                Some(stats::duration(seconds))
+            };
+            return Ok(());
+        }
+        if value.is_empty() {
+            self.fields.remove(key);
+        } else {
+            self.fields.insert(key.to_string(), value.to_string());
+        }
+        Ok(())
+    }
+
+    /// Like `2h 00m est / 45m logged`, with the time left when it's set; `None`
+    /// without an estimate or remaining time.
+    fn effort(&self, logged: u64) -> Option<String> {
+        let estimate = self.estimate.as_deref().and_then(stats::parse_duration);
+        let remaining = self.remaining.as_deref().and_then(stats::parse_duration);
+        if estimate.is_none() && remaining.is_none() {
+            return None;
+        }
+        let logged = format!("{} logged", stats::duration(logged));
+        let mut effort = match estimate {
+            Some(estimate) => format!("{} est / {logged}", stats::duration(estimate)),
+            None => logged,
+        };
+        if let Some(remaining) = remaining {
+            effort.push_str(&format!(", {} left", stats::duration(remaining)));
+        }
+        Some(effort)
+    }
+
+    /// The remaining time if it's set, otherwise what's left of the estimate.
+    fn remaining_seconds(&self, logged: u64) -> Option<u64> {
+        let remaining = self.remaining.as_deref().and_then(stats::parse_duration);
+        remaining.or_else(|| {
+            let estimate = self.estimate.as_deref().and_then(stats::parse_duration)?;
+            Some(estimate.saturating_sub(logged))
+        })
+    }
+
+    fn due_date(&self) -> Option<NaiveDate> {
+        parse_due(self.due.as_deref()?).map(|(date, _)| date)
+    }
+
+    fn due_time(&self) -> Option<NaiveTime> {
+        parse_due(self.due.as_deref()?).and_then(|(_, time)| time)
+    }
+
+    /// Lowercased `#hashtags` found in the name and description.
+    fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .name
+            .split_whitespace()
+            .chain(self.description.split_whitespace())
+            .filter_map(|word| word.strip_prefix('#'))
+            .map(|tag| {
+                tag.trim_end_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase()
+            })
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Makes `tags` the todo's tags: the `#hashtag`s of dropped ones are taken
+    /// out of the name and description, and new ones added to the description.
+    fn set_tags(&mut self, tags: &[String]) {
+        let today = Local::now().date_naive();
+        let current = self.tags();
+        for tag in current.iter().filter(|tag| !tags.contains(tag)) {
+            quick_actions::Step::Untag(tag.clone()).apply(self, today, &Workdays::default());
+        }
+        for tag in tags.iter().filter(|tag| !current.contains(tag)) {
+            quick_actions::Step::Tag(tag.clone()).apply(self, today, &Workdays::default());
+        }
     }
 }
 
@@ -109,50 +681,270 @@ impl Clone for Data {
             description: self.description.clone(),
             progress: self.progress.clone(),
             created: self.created.clone(),
+            starred: self.starred,
+            percent: self.percent,
+            due: self.due.clone(),
+            priority: self.priority,
+            completed: self.completed.clone(),
+            updated: self.updated.clone(),
+            estimate: self.estimate.clone(),
+            remaining: self.remaining.clone(),
+            remind: self.remind.clone(),
+            notes: self.notes.clone(),
+            fields: self.fields.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
 
 struct App<'a> {
     state: TableState,
-    items: Vec<Data>, // Original items loaded from JSON
-    filtered_items: Vec<Data>,
+    selected_task: Option<FollowedTask>, // The selection stays on it as rows move
+    items: Vec<Data>,                    // Original items loaded from JSON
     longest_item_lens: (u16, u16, u16, u16), // (name, information, progress, created)
-    scroll_state: ScrollbarState,
     colors: TableColors,
     color_index: usize,
     show_create: bool,
     show_info: bool,
+    show_settings: bool,
+    settings_selected: usize,
+    settings_input: Option<String>, // Text typed into the selected text setting
+    show_reader: bool,
+    reader_scroll: u16,
     input_name: String,
     input_description: String,
     input_focus: InputFocus,
+    input_progress: Progress,
+    input_priority: Option<Priority>,
+    input_due: Option<NaiveDate>,
+    input_due_time: String,      // Typed on the due date field as HH:MM
+    due_time_invalid: bool,      // Saving was refused because the time isn't HH:MM
+    invalid_due: Option<String>, // An unparsable due date from the file, kept until replaced
+    name_missing: bool,          // Saving was refused because the name is empty
+    open_editor: bool,           // Edit the description in $EDITOR after this key press
+    due_cursor: NaiveDate,       // The day highlighted in the date picker
     editing_index: Option<usize>,
     info_popup: InfoPopup<'a>,
     hide_completed: bool,
+    icons: Icons,
+    rules: Vec<Rule>,
+    preferences: Preferences,
+    column_focus: ColumnFocus,
+    cell_scroll: usize, // Horizontal offset (in chars) of the focused cell
+    pane_focus: PaneFocus,
+    preview_scroll: u16,
+    preview_field: PreviewField,
+    preview_input: Option<String>, // Text typed into the preview pane's field
+    lists: Vec<TodoList>,          // The entry at `active_list` is a placeholder while it's active
+    active_list: usize,
+    show_new_list: bool,
+    input_list_name: String,
+    show_quick_add: bool,
+    input_quick_add: String,
+    show_tags: bool,
+    tag_choices: Vec<(String, bool)>, // Every tag and whether the selected todo gets it
+    tag_selected: usize,
+    input_tag: String,
+    focus_index: Option<usize>, // Index into `items` of the task shown in focus mode
+    focus_started: Instant,
+    tag_filter: Option<String>,
+    sidebar_selected: usize, // 0 is "All", then one entry per tag
+    show_search: bool,
+    search_query: String,
+    search_selected: usize,
+    search_history: History,
+    view_states: ViewStates, // The sort and filters each list and layout were left with
+    show_command: bool,
+    input_command: String,
+    import_preview: Option<(PathBuf, Result<Vec<Data>, String>)>, // The file to import, as read
+    disk_items: Vec<Data>, // The active list as last read from disk, the base for merging
+    disk_modified: Option<SystemTime>,
+    disk_hash: Option<u64>, // The file's contents as last read or written, to catch edits before saving
+    window_title: String,   // As last set, to only write it when it changes
+    conflicts: Vec<Conflict>,
+    conflict_field: usize,
+    uploader: Option<Uploader>, // Uploads changed list files when remote storage is set
+    errors: Vec<AppError>,      // Shown one at a time in the error popup
+    toasts: VecDeque<Toast>,
+    collapsed_lanes: Vec<String>, // Kanban swimlanes folded down to their title
+    reminded: Vec<String>,        // `reminders::key` of every reminder shown this session
+    clock: Clock, // How timestamps are shown, from the `timezone` and `date_format` preferences
+    date_filters: Vec<DateFilter>, // Set with `:filter`, the table only shows todos in every range
+    table_area: Rect, // Where the table was last drawn, for the mouse
+    kanban_targets: Vec<Target>, // Where the kanban cards and columns were last drawn
+    dragging: Option<usize>, // Index into `items` of the row or card held by the mouse
+    unsaved: bool, // The last save of the active list failed
+    interrupted: bool, // Ctrl+C was pressed once with something unsaved, and again quits
+    delete_pending: bool, // Delete was pressed once with Confirm Delete on, and again deletes
+    recovery_written: Option<String>, // What recovery.json currently holds
+    completions: Completions,
+    done_before: Vec<String>, // `created` of the active list's done todos as last saved or read
+    flashing: Vec<(String, Instant)>, // `created` of todos just marked done, and since when
+    show_stats: bool,
+    stats_page: StatsPage,
+    show_palette: bool,
+    palette_query: String,
+    palette_selected: usize,
+    time_log: TimeLog,                // Time spent in focus mode per todo
+    undo: Vec<Vec<Data>>,             // Earlier versions of the active list, one per save
+    undo_base: Vec<Data>, // The active list as last saved or read, pushed onto `undo` on the next save
+    inline: bool,         // Drawn in a few lines below the prompt with `--inline`, one line per row
+    no_color: bool,       // Monochrome, with text markers where colors would tell things apart
+    picking: bool,        // Run as `todo-tui pick`, where Enter picks the selected todo
+    picked: Option<Data>, // What `todo-tui pick` prints once the app quits
+    recording: Option<Vec<KeyEvent>>, // The keys pressed since recording started
+    macro_keys: Vec<KeyEvent>, // The last recorded macro
+    replaying: bool,
+    show_replay: bool, // Asking how many times to replay the macro
+    input_replay_count: String,
+    show_set_field: bool, // Asking for a `key: value` field of the todo in the reader
+    input_field: String,
+    show_add_note: bool, // Asking for a note to add to the selected todo's log
+    input_note: String,
+}
+
+/// A row of the command palette.
+struct PaletteEntry {
+    name: String,
+    hint: String, // The keys that do the same
+    target: PaletteTarget,
+}
+
+#[derive(Clone, Copy)]
+enum PaletteTarget {
+    Action(AppAction),
+    List(usize),
+    QuickAction(usize), // Index into the `quick_actions` preference
+}
+
+/// An error waiting to be acknowledged in the error popup.
+struct AppError {
+    message: String,
+    retry: Option<Retry>,
+}
+
+/// What the error popup's retry runs again.
+#[derive(Clone, Copy)]
+enum Retry {
+    SaveItems,
+    SavePreferences,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum InputFocus {
+    Name,
+    Status,
+    Priority,
+    Description,
+    Due,
+}
+
+/// Which pane receives navigation keys when the sidebar or preview is shown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PaneFocus {
+    Sidebar,
+    Table,
+    Preview,
+}
+
+/// The table column that `<` / `>` resize.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ColumnFocus {
     Name,
     Description,
 }
 
 impl App<'_> {
     fn new() -> Self {
-        let data_vec = read_json().unwrap_or_default();
-        Self {
+        let mut startup_errors = Vec::new();
+        let preferences = Preferences::load().unwrap_or_else(|e| {
+            startup_errors.push(e);
+            Preferences::default()
+        });
+        let uploader = preferences.remote.as_ref().map(|remote| {
+            let storage = storage::open(remote);
+            let dir = preferences.data_dir();
+            startup_errors.extend(storage::pull(
+                storage.as_ref(),
+                dir,
+                &lists::paths(&preferences),
+            ));
+            Uploader::spawn(storage, dir.to_path_buf())
+        });
+
+        let lists = lists::discover(&preferences);
+        for list in &lists {
+            tracing::info!(list = %list.name, path = %list.path.display(), todos = list.items.len(), "loaded list");
+        }
+        let mut app = Self::with_lists(lists, preferences, uploader);
+        for message in startup_errors {
+            app.show_error(message, None);
+        }
+        if let Some(recovery) = recovery::load() {
+            app.restore(recovery);
+        }
+        app
+    }
+
+    /// Builds the app around already loaded lists, with the first one active.
+    fn with_lists(
+        mut lists: Vec<TodoList>,
+        preferences: Preferences,
+        uploader: Option<Uploader>,
+    ) -> Self {
+        let mut errors: Vec<AppError> = lists
+            .iter_mut()
+            .filter_map(|list| list.load_error.take())
+            .map(|message| AppError {
+                message,
+                retry: None,
+            })
+            .collect();
+        let zone = Zone::parse(preferences.timezone.as_deref()).unwrap_or_else(|message| {
+            errors.push(AppError {
+                message,
+                retry: None,
+            });
+            Zone::Local
+        });
+        let data_vec = mem::take(&mut lists[0].items);
+        let disk_modified = modified_time(&lists[0].path);
+        let disk_hash = file_hash(&lists[0].path);
+        let completions = Completions::load(&Completions::path(&lists[0].path));
+        let time_log = TimeLog::load(&TimeLog::path(&lists[0].path));
+        let search_history = History::load(&History::path(&lists[0].path));
+        let view_states = ViewStates::load(&ViewStates::path(&lists[0].path));
+        let color_index = preferences.theme % THEMES.len();
+        let clock = Clock {
+            zone,
+            format: preferences.date_format,
+        };
+        let mut app = Self {
             state: TableState::default().with_selected(0),
+            selected_task: None,
             longest_item_lens: constraint_len_calculator(&data_vec),
-            scroll_state: ScrollbarState::new(data_vec.len().saturating_sub(1) * ITEM_HEIGHT),
-            colors: TableColors::new(&PALETTES[0]),
-            color_index: 0,
-            items: data_vec.clone(),  // Original items
-            filtered_items: data_vec, // Initially display all items
+            colors: THEMES[color_index],
+            color_index,
+            items: data_vec.clone(),
             show_create: false,
             show_info: false,
+            show_settings: false,
+            settings_selected: 0,
+            settings_input: None,
+            show_reader: false,
+            reader_scroll: 0,
             input_name: String::new(),
             input_description: String::new(),
             input_focus: InputFocus::Name,
+            input_progress: preferences.new_status.clone(),
+            input_priority: None,
+            input_due: None,
+            input_due_time: String::new(),
+            due_time_invalid: false,
+            invalid_due: None,
+            name_missing: false,
+            open_editor: false,
+            due_cursor: Local::now().date_naive(),
             editing_index: None,
             info_popup: InfoPopup {
                 title: Line::from("Todo-TUI"),
@@ -168,472 +960,3250 @@ impl App<'_> {
                             .fg(Color::Cyan)
                             .add_modifier(Modifier::BOLD),
                     ),
-                    Span::from("(I) info | (Esc) quit"),
-                    Span::from("(A) create new todo | (X) delete todo | (R) edit todo"),
+                    Span::from("(I) info | (,) settings | (Esc) quit"),
+                    Span::from(
+                        "(A) create new todo | (O) quick add | (X) delete todo | (R) edit todo",
+                    ),
+                    Span::from("(Enter) read todo | (N) next progress | (D) row density"),
+                    Span::from("(U) undo the last change | (Shift+J) add a note"),
+                    Span::from("(Shift+Q) start/stop recording a macro | (@) replay it"),
+                    Span::from("(Tab) focus column/preview | (<) shrink column | (>) grow column"),
+                    Span::from("(P) cycle table/preview/kanban | (F) focus on task in progress"),
+                    Span::from("(W) pick a random open todo | (S) star todo"),
+                    Span::from("(Shift+S) completion and time tracking stats"),
+                    Span::from("(Ctrl+P) command palette with every action | (Ctrl+Z) suspend"),
+                    Span::from("(B) toggle tag sidebar | (#) edit tags | (/) search all lists"),
+                    Span::from("(:) command, e.g. substitute old/new or status done"),
+                    Span::from("(Shift+C) clear completed todos | (:) export/import todos.csv"),
+                    Span::from("(Shift+N) new list | (Alt+1-9) switch list | (Ctrl+Tab) next list"),
+                    Span::from("(+/-) adjust percent complete | (G) group by due date"),
+                    Span::from("(Shift+O) cycle sort order | (E) postpone a day"),
+                    Span::from("(Shift+E) postpone a week | (M) postpone to next Monday"),
+                    Span::from("([/]) previous/next kanban lane | (Z) collapse lane"),
+                    Span::from("(Shift+←) scroll cell left | (Shift+→) scroll cell right"),
                     Span::from("(↑) move up | (↓) move down | (→) next color | (←) previous color"),
                 ]),
-                checkboxes: vec![
-                    Checkbox {
-                        label: "Hide Completed".to_string(),
-                        checked: false,
-                    },
-                    Checkbox {
-                        label: "Lock Color".to_string(),
-                        checked: false,
-                    },
-                    Checkbox {
-                        label: "....".to_string(),
-                        checked: false,
-                    },
-                ],
                 style: Style::default().fg(Color::White),
-                selected_checkbox: 0,
             },
             hide_completed: false,
+            icons: Icons::new(
+                if preferences.ascii_only {
+                    IconSet::Ascii
+                } else {
+                    preferences.icon_set
+                },
+                &preferences.icons,
+            ),
+            rules: rules::parse_rules(&preferences.rules),
+            preferences,
+            column_focus: ColumnFocus::Name,
+            cell_scroll: 0,
+            pane_focus: PaneFocus::Table,
+            preview_scroll: 0,
+            preview_field: PreviewField::Status,
+            preview_input: None,
+            lists,
+            active_list: 0,
+            show_new_list: false,
+            input_list_name: String::new(),
+            show_quick_add: false,
+            input_quick_add: String::new(),
+            show_tags: false,
+            tag_choices: Vec::new(),
+            tag_selected: 0,
+            input_tag: String::new(),
+            focus_index: None,
+            focus_started: Instant::now(),
+            tag_filter: None,
+            sidebar_selected: 0,
+            show_search: false,
+            search_query: String::new(),
+            search_selected: 0,
+            search_history,
+            view_states,
+            show_command: false,
+            input_command: String::new(),
+            import_preview: None,
+            disk_modified,
+            disk_hash,
+            window_title: String::new(),
+            disk_items: data_vec.clone(),
+            conflicts: Vec::new(),
+            conflict_field: 0,
+            uploader,
+            errors,
+            toasts: VecDeque::new(),
+            collapsed_lanes: Vec::new(),
+            reminded: Vec::new(),
+            clock,
+            date_filters: Vec::new(),
+            table_area: Rect::default(),
+            kanban_targets: Vec::new(),
+            dragging: None,
+            unsaved: false,
+            interrupted: false,
+            delete_pending: false,
+            recovery_written: None,
+            completions,
+            done_before: done_keys(&data_vec),
+            flashing: Vec::new(),
+            show_stats: false,
+            stats_page: StatsPage::default(),
+            show_palette: false,
+            palette_query: String::new(),
+            palette_selected: 0,
+            time_log,
+            undo: Vec::new(),
+            undo_base: data_vec.clone(),
+            inline: false,
+            no_color: false,
+            picking: false,
+            picked: None,
+            recording: None,
+            macro_keys: Vec::new(),
+            replaying: false,
+            show_replay: false,
+            input_replay_count: String::new(),
+            show_set_field: false,
+            input_field: String::new(),
+            show_add_note: false,
+            input_note: String::new(),
+        };
+        // Until a view was left somewhere, the preferences' sort applies
+        if app.view_states.get(&app.view_key()).is_some() {
+            app.restore_view();
         }
+        app
     }
 
-    fn get_filtered_items(&self) -> Vec<&Data> {
-        if self.hide_completed {
-            self.items
-                .iter()
-                .filter(|item| item.progress != Progress::Done)
-                .collect() // Filter out completed items
-        } else {
-            self.items.iter().collect() // Keep all items if not hiding
+    /// Picks up the draft and unsaved todos of a session that crashed or was killed.
+    fn restore(&mut self, recovery: Recovery) {
+        if let Some(index) = self
+            .lists
+            .iter()
+            .position(|list| list.path == recovery.list)
+        {
+            self.switch_list(index);
         }
-    }
-    fn item_matches(item: &Data, selected_item: &Data) -> bool {
-        item.name == selected_item.name
-            && item.description == selected_item.description
-            && item.progress == selected_item.progress
-            && item.created == selected_item.created
-    }
-    fn toggle_info(&mut self) {
-        self.show_info = !self.show_info;
+        if let Some(items) = recovery.unsaved_items {
+            self.items = items;
+            self.longest_item_lens = constraint_len_calculator(&self.items);
+            self.save_items();
+        }
+        if !recovery.draft_name.is_empty() || !recovery.draft_description.is_empty() {
+            self.editing_index = recovery
+                .editing_index
+                .filter(|&index| index < self.items.len());
+            self.toggle_create();
+            self.input_name = recovery.draft_name;
+            self.input_description = recovery.draft_description;
+        }
+        tracing::info!("restored the previous session from recovery.json");
+        self.notify("Recovered unsaved changes from the last session");
     }
 
-    fn handle_info_input(&mut self, key: KeyCode) {
-        let checkboxes = &mut self.info_popup.checkboxes; // Access checkboxes from the info_popup
+    /// Keeps recovery.json in line with the open draft and unsaved todos,
+    /// removing it when there is nothing to lose.
+    fn autosave_recovery(&mut self) {
+        let has_draft = self.has_draft();
+        let recovery = (has_draft || self.unsaved).then(|| Recovery {
+            list: self.data_path().to_path_buf(),
+            draft_name: if has_draft {
+                self.input_name.clone()
+            } else {
+                String::new()
+            },
+            draft_description: if has_draft {
+                self.input_description.clone()
+            } else {
+                String::new()
+            },
+            editing_index: self.editing_index.filter(|_| has_draft),
+            unsaved_items: self.unsaved.then(|| self.items.clone()),
+        });
+        let contents = recovery.and_then(|recovery| serde_json::to_string(&recovery).ok());
+        if contents == self.recovery_written {
+            return;
+        }
 
-        match key {
-            KeyCode::Down => {
-                if !checkboxes.is_empty() {
-                    self.info_popup.selected_checkbox =
-                        (self.info_popup.selected_checkbox + 1) % checkboxes.len();
-                }
-            }
-            KeyCode::Up => {
-                if !checkboxes.is_empty() {
-                    self.info_popup.selected_checkbox =
-                        (self.info_popup.selected_checkbox + checkboxes.len() - 1)
-                            % checkboxes.len();
-                }
-            }
-            KeyCode::Left => {
-                // Logic for left arrow key
-                if self.info_popup.selected_checkbox > 0 {
-                    self.info_popup.selected_checkbox -= 1;
-                } else {
-                    self.info_popup.selected_checkbox = checkboxes.len() - 1; // Wrap around to the last checkbox
-                }
-            }
-            KeyCode::Right => {
-                // Logic for right arrow key
-                if self.info_popup.selected_checkbox < checkboxes.len() - 1 {
-                    self.info_popup.selected_checkbox += 1;
-                } else {
-                    self.info_popup.selected_checkbox = 0; // Wrap around to the first checkbox
-                }
-            }
-            KeyCode::Enter => {
-                if let Some(checkbox) = checkboxes.get_mut(self.info_popup.selected_checkbox) {
-                    checkbox.checked = !checkbox.checked;
-
-                    if checkbox.label == "Hide Completed" {
-                        self.hide_completed = checkbox.checked;
-
-                        // Update filtered items
-                        if self.hide_completed {
-                            self.filtered_items = self
-                                .items
-                                .iter()
-                                .filter(|item| item.progress != Progress::Done)
-                                .cloned()
-                                .collect();
-                        } else {
-                            self.filtered_items = self.items.clone();
-                        }
+        match recovery::write(contents.as_deref()) {
+            Ok(()) => self.recovery_written = contents,
+            Err(e) => tracing::warn!("couldn't write recovery.json: {e}"),
+        }
+    }
 
-                        // Recalculate longest item lengths
-                        self.longest_item_lens = constraint_len_calculator(&self.filtered_items);
+    /// Whether the create or edit popup holds typed text.
+    fn has_draft(&self) -> bool {
+        self.show_create && !(self.input_name.is_empty() && self.input_description.is_empty())
+    }
 
-                        // Ensure the selection is valid
-                        self.update_selected_index();
-                    }
-                }
-            }
-            _ => {}
+    /// Ctrl+C quits from anywhere. A failed save is tried once more first, and
+    /// if that fails too or a draft is open, a second Ctrl+C is needed; what's
+    /// left unsaved is kept in recovery.json either way.
+    fn interrupt(&mut self) -> bool {
+        if self.unsaved {
+            self.save_items();
         }
+        if (self.unsaved || self.has_draft()) && !self.interrupted {
+            self.interrupted = true;
+            self.warn("There are unsaved changes, press Ctrl+C again to quit anyway");
+            return true;
+        }
+        tracing::info!("quit with Ctrl+C");
+        false
     }
 
-    fn create_item(&self) -> Data {
-        Data {
-            name: self.input_name.clone(),
-            description: self.input_description.clone(),
-            progress: Progress::InProgress,
-            created: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-        }
+    fn notify(&mut self, message: impl Into<String>) {
+        self.toasts
+            .push_back(Toast::new(message.into(), ToastKind::Info));
     }
 
-    fn toggle_create(&mut self) {
-        self.show_create = !self.show_create;
+    fn warn(&mut self, message: impl Into<String>) {
+        self.toasts
+            .push_back(Toast::new(message.into(), ToastKind::Warning));
+    }
 
-        if self.show_create {
-            // Clear fields every time the popup is opened
-            self.input_name.clear();
-            self.input_description.clear();
+    /// Queues an error for the error popup, unless the same one is already waiting.
+    fn show_error(&mut self, message: String, retry: Option<Retry>) {
+        tracing::error!("{message}");
+        if self.errors.iter().all(|error| error.message != message) {
+            self.errors.push(AppError { message, retry });
+        }
+    }
 
-            if let Some(index) = self.editing_index {
-                // Load the existing item's data if editing
-                self.input_name = self.items[index].name.clone();
-                self.input_description = self.items[index].description.clone();
+    fn save_items(&mut self) {
+        self.record_completions();
+        self.record_undo();
+        // Another program wrote the file since we last read it, so merge its
+        // edits in rather than overwriting them; the merge saves the result
+        if file_hash(self.data_path()) != self.disk_hash {
+            tracing::warn!(path = %self.data_path().display(), "file changed before saving");
+            if self.merge_from_disk() {
+                return;
             }
-
-            // Set focus to the name field by default
-            self.input_focus = InputFocus::Name;
-        } else {
-            // When closing the popup, reset the editing index and clear the input fields
-            self.editing_index = None; // Reset the editing index when closing
-            self.input_name.clear();
-            self.input_description.clear();
         }
+        tracing::info!(path = %self.data_path().display(), todos = self.items.len(), "saving");
+        self.unsaved = save_list(self.data_path(), &self.items).is_err_and(|e| {
+            let message = format!("Couldn't save {}: {e}", self.data_path().display());
+            self.show_error(message, Some(Retry::SaveItems));
+            true
+        });
+        self.disk_hash = file_hash(self.data_path());
     }
 
-    fn add_item(&mut self) {
-        if self.input_name.trim().is_empty() {
-            // If name is empty don't add
+    /// Keeps the list as it was before this save for `undo`, unless nothing changed.
+    fn record_undo(&mut self) {
+        if sync::same_items(&self.undo_base, &self.items) {
             return;
         }
+        let before = mem::replace(&mut self.undo_base, self.items.clone());
+        self.undo.push(before);
+        if self.undo.len() > MAX_UNDO {
+            self.undo.remove(0);
+        }
+    }
 
-        let new_item = self.create_item();
-        self.items.push(new_item);
+    /// Puts the active list back as it was before the last save.
+    fn undo(&mut self) {
+        let Some(items) = self.undo.pop() else {
+            self.notify("Nothing to undo");
+            return;
+        };
+        tracing::info!(path = %self.data_path().display(), "undo");
+        self.undo_base = items.clone();
+        self.items = items;
+        self.save_items();
+        self.longest_item_lens = constraint_len_calculator(&self.items);
+        self.update_selected_index();
+        self.notify("Undone");
+    }
 
-        if let Err(e) = save_json(&self.items) {
-            panic!("Error saving JSON: {e}")
+    /// Counts todos done or reopened since the last save towards today.
+    fn record_completions(&mut self) {
+        // Stamped here as every way of changing the status ends in a save
+        for item in &mut self.items {
+            if item.progress != Progress::Done {
+                item.completed = None;
+            } else if !self.done_before.contains(&item.created) {
+                item.completed = Some(timestamps::now());
+            }
+        }
+        let done = done_keys(&self.items);
+        let newly_done: Vec<&String> = done
+            .iter()
+            .filter(|key| !self.done_before.contains(key))
+            .collect();
+        let completed = newly_done.len();
+        let now = Instant::now();
+        self.flashing
+            .extend(newly_done.into_iter().map(|key| (key.clone(), now)));
+        // Deleted todos stay counted, only reopened ones are taken back
+        let reopened = self
+            .items
+            .iter()
+            .filter(|item| item.progress != Progress::Done)
+            .filter(|item| self.done_before.contains(&item.created))
+            .count();
+        self.done_before = done;
+        if completed == 0 && reopened == 0 {
+            return;
         }
 
-        self.toggle_create();
+        let today = Local::now().date_naive();
+        self.completions.record(
+            today,
+            u32::try_from(completed).unwrap_or(u32::MAX),
+            u32::try_from(reopened).unwrap_or(u32::MAX),
+        );
+        let path = Completions::path(&self.lists[0].path);
+        if let Err(e) = self.completions.save(&path) {
+            tracing::warn!(path = %path.display(), "couldn't save completions: {e}");
+        }
     }
 
-    fn handle_popup_input(&mut self, key: KeyCode) {
+    fn save_preferences(&mut self) {
+        tracing::info!("saving preferences");
+        if let Err(e) = self.preferences.save() {
+            self.show_error(
+                format!("Couldn't save preferences: {e}"),
+                Some(Retry::SavePreferences),
+            );
+        }
+    }
+
+    fn handle_error_input(&mut self, key: KeyCode) {
         match key {
-            KeyCode::Char(c)
-                if self.input_focus == InputFocus::Name && self.input_name.len() < 50 =>
-            {
-                self.input_name.push(c);
-            }
-            KeyCode::Char(c)
-                if self.input_focus == InputFocus::Description
-                    && self.input_description.len() < 255 =>
-            {
-                self.input_description.push(c);
-            }
-            KeyCode::Backspace => match self.input_focus {
-                InputFocus::Name => {
-                    if !self.input_name.is_empty() {
-                        self.input_name.pop();
+            KeyCode::Char('r') => {
+                if let Some(retry) = self.errors[0].retry {
+                    self.errors.remove(0);
+                    match retry {
+                        Retry::SaveItems => self.save_items(),
+                        Retry::SavePreferences => self.save_preferences(),
                     }
                 }
-                InputFocus::Description => {
-                    if !self.input_description.is_empty() {
-                        self.input_description.pop();
-                    }
-                }
-            },
-            KeyCode::Enter => {
-                if self.input_focus == InputFocus::Description {
-                    self.add_item(); // Save and close the popup
-                } else {
-                    self.input_focus = InputFocus::Description;
-                }
             }
-            KeyCode::Tab if !self.show_info => {
-                self.input_focus = match self.input_focus {
-                    InputFocus::Name => InputFocus::Description,
-                    InputFocus::Description => InputFocus::Name,
-                };
+            KeyCode::Esc | KeyCode::Enter => {
+                self.errors.remove(0);
             }
             _ => {}
         }
     }
 
-    pub fn next(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i >= self.items.len() - 1 {
-                    0
-                } else {
-                    i + 1
+    fn data_path(&self) -> &Path {
+        &self.lists[self.active_list].path
+    }
+
+    /// Parks the active list's items and view state and restores those of `index`.
+    fn switch_list(&mut self, index: usize) {
+        if index == self.active_list || index >= self.lists.len() {
+            return;
+        }
+
+        self.remember_view();
+        let current = &mut self.lists[self.active_list];
+        current.items = mem::take(&mut self.items);
+        current.state = mem::take(&mut self.state);
+
+        let next = &mut self.lists[index];
+        self.items = mem::take(&mut next.items);
+        self.state = mem::take(&mut next.state);
+        self.selected_task = None; // Picked up again from the list's own selection
+        self.active_list = index;
+
+        self.longest_item_lens = constraint_len_calculator(&self.items);
+        self.cell_scroll = 0;
+        self.preview_scroll = 0;
+        self.restore_view();
+
+        self.disk_items = self.items.clone();
+        self.disk_modified = modified_time(self.data_path());
+        self.disk_hash = file_hash(self.data_path());
+        self.done_before = done_keys(&self.items);
+        self.flashing.clear();
+        self.undo.clear();
+        self.undo_base = self.items.clone();
+    }
+
+    /// Shows a toast, and a desktop notification when enabled, for every open
+    /// task in any list whose reminder is due and hasn't been shown yet.
+    fn check_reminders(&mut self) {
+        let settings = self.preferences.reminders;
+        if !settings.enabled {
+            return;
+        }
+        let now = Local::now().naive_local();
+        let mut messages = Vec::new();
+        for list in 0..self.lists.len() {
+            let items = if list == self.active_list {
+                &self.items
+            } else {
+                &self.lists[list].items
+            };
+            for item in items {
+                for at in reminders::own_reminders(item, now) {
+                    let key = reminders::own_key(item, at);
+                    if !self.reminded.contains(&key) {
+                        self.reminded.push(key);
+                        messages.push(format!("Reminder: {}", item.name));
+                    }
+                }
+                let key = reminders::key(item);
+                if self.reminded.contains(&key) {
+                    continue;
+                }
+                if let Some(message) = reminders::reminder(item, now, &settings) {
+                    self.reminded.push(key);
+                    messages.push(message);
                 }
             }
-            None => 0,
+        }
+        self.drop_shown_reminders(now);
+
+        // Don't bury the screen in toasts, e.g. at startup
+        if messages.len() > 3 {
+            self.warn(format!("{} todos are due soon or overdue", messages.len()));
+        } else {
+            for message in &messages {
+                self.warn(message.clone());
+            }
+        }
+        for message in messages {
+            tracing::info!(%message, "reminder");
+            if settings.desktop {
+                reminders::notify_desktop(message);
+            }
+        }
+    }
+
+    /// Takes the todos' own reminders that were shown off the active list's
+    /// todos, so they don't come back the next time the app starts. Other
+    /// lists' are taken off once they're switched to.
+    fn drop_shown_reminders(&mut self, now: NaiveDateTime) {
+        let mut changed = false;
+        for item in &mut self.items {
+            let shown: Vec<String> = reminders::own_reminders(item, now)
+                .into_iter()
+                .filter(|at| self.reminded.contains(&reminders::own_key(item, at)))
+                .map(str::to_string)
+                .collect();
+            if !shown.is_empty() {
+                item.remind.retain(|at| !shown.contains(at));
+                changed = true;
+            }
+        }
+        if changed {
+            self.save_items();
+        }
+    }
+
+    /// Merges in changes another program made to the active list's file since
+    /// it was last read. Conflicting edits are queued for the conflict popup.
+    fn check_disk(&mut self) {
+        let modified = modified_time(self.data_path());
+        if modified == self.disk_modified {
+            return;
+        }
+        self.disk_modified = modified;
+        if let Some(uploader) = &self.uploader {
+            // Both our own saves and other programs' edits end up here
+            uploader.upload(self.data_path().to_path_buf());
+        }
+        self.merge_from_disk();
+    }
+
+    /// Reads the active list's file and merges it with the list in memory,
+    /// saving the result when it has edits the file doesn't. `false` if the
+    /// file couldn't be read.
+    fn merge_from_disk(&mut self) -> bool {
+        let Ok(remote) = read_list(self.data_path()) else {
+            return false;
         };
-        self.state.select(Some(i));
-        self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
+        self.disk_hash = file_hash(self.data_path());
+
+        // Our own saves show up here too, with nothing new to merge
+        if !sync::same_items(&self.items, &remote) {
+            tracing::info!(path = %self.data_path().display(), "file changed on disk, merging");
+            self.notify("Reloaded changes from disk");
+            // Undoing past another program's edit would throw it away
+            self.undo.clear();
+        }
+        let (merged, conflicts) = sync::merge(&self.disk_items, &self.items, &remote);
+        if !conflicts.is_empty() {
+            tracing::warn!(conflicts = conflicts.len(), "sync conflicts");
+        }
+        let has_local_changes = !sync::same_items(&merged, &remote);
+        self.disk_items = remote;
+        self.items = merged;
+        // Only count what's completed here, the daemon counts its own
+        self.done_before = done_keys(&self.items);
+        self.undo_base = self.items.clone();
+        self.conflicts.extend(conflicts);
+        if has_local_changes {
+            self.save_items();
+        }
+        self.longest_item_lens = constraint_len_calculator(&self.items);
+        self.update_selected_index();
+        true
     }
 
-    pub fn previous(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.items.len().saturating_sub(1)
-                } else {
-                    i.saturating_sub(1)
-                }
+    /// Resolves the first conflict with the in-memory, disk or merged version.
+    fn resolve_conflict(&mut self, pick: fn(&Conflict) -> Data) {
+        if self.conflicts.is_empty() {
+            return;
+        }
+        let conflict = self.conflicts.remove(0);
+        if let Some(item) = self
+            .items
+            .iter_mut()
+            .find(|item| item.created == conflict.local.created)
+        {
+            *item = pick(&conflict);
+        }
+        self.save_items();
+        self.conflict_field = 0;
+    }
+
+    fn handle_conflict_input(&mut self, key: KeyCode) {
+        let Some(conflict) = self.conflicts.first_mut() else {
+            return;
+        };
+        match key {
+            KeyCode::Char('l') => self.resolve_conflict(|conflict| conflict.local.clone()),
+            KeyCode::Char('r') => self.resolve_conflict(|conflict| conflict.remote.clone()),
+            KeyCode::Enter => self.resolve_conflict(Conflict::merged),
+            KeyCode::Up => self.conflict_field = self.conflict_field.saturating_sub(1),
+            KeyCode::Down => {
+                self.conflict_field = (self.conflict_field + 1).min(conflict.fields.len() - 1);
             }
-            None => 0,
+            KeyCode::Left | KeyCode::Right | KeyCode::Char(' ') => {
+                let use_remote = &mut conflict.use_remote[self.conflict_field];
+                *use_remote = !*use_remote;
+            }
+            _ => {}
+        }
+    }
+
+    fn view_key(&self) -> String {
+        ViewStates::key(self.data_path(), self.preferences.layout)
+    }
+
+    /// Keeps the active list and layout's sort and filters, writing them out
+    /// when they changed.
+    fn remember_view(&mut self) {
+        let state = ViewState {
+            sort: self.preferences.sort,
+            hide_completed: self.hide_completed,
+            tag_filter: self.tag_filter.clone(),
+            date_filters: self.date_filters.iter().map(DateFilter::term).collect(),
+        };
+        if !self.view_states.set(self.view_key(), state) {
+            return;
+        }
+        let path = ViewStates::path(&self.lists[0].path);
+        if let Err(e) = self.view_states.save(&path) {
+            tracing::warn!(path = %path.display(), "couldn't save the view state: {e}");
+        }
+    }
+
+    /// Brings back the sort and filters the active list and layout were left
+    /// with, or none for one that wasn't used yet.
+    fn restore_view(&mut self) {
+        let state = self
+            .view_states
+            .get(&self.view_key())
+            .cloned()
+            .unwrap_or_default();
+        self.preferences.sort = state.sort;
+        self.tag_filter = state.tag_filter;
+        self.date_filters = state
+            .date_filters
+            .iter()
+            .filter_map(|term| DateFilter::parse(term)?.ok())
+            .collect();
+        self.set_hide_completed(state.hide_completed);
+    }
+
+    fn set_hide_completed(&mut self, hide_completed: bool) {
+        self.hide_completed = hide_completed;
+        // Hidden right away, without waiting for the flash of todos just done
+        self.flashing.clear();
+        self.update_selected_index();
+    }
+
+    fn setting(&self, setting: Setting) -> Value {
+        setting.value(&self.preferences, self.hide_completed)
+    }
+
+    /// Changes a toggle or choice without saving it, see `change_setting`.
+    fn step_setting(&mut self, setting: Setting, forward: bool) {
+        let mut hide_completed = self.hide_completed;
+        setting.step(&mut self.preferences, &mut hide_completed, forward);
+        self.set_hide_completed(hide_completed);
+        // These are copied out of the preferences at start
+        match setting {
+            Setting::Theme => {
+                self.color_index = self.preferences.theme % THEMES.len();
+                self.set_colors();
+            }
+            Setting::DateFormat => self.clock.format = self.preferences.date_format,
+            _ => {}
+        }
+    }
+
+    /// Changes a setting from the settings screen, saving it right away.
+    fn change_setting(&mut self, setting: Setting, forward: bool) {
+        self.step_setting(setting, forward);
+        tracing::info!(setting = setting.label(), value = ?self.setting(setting), "changed setting");
+        if setting.is_preference() {
+            self.save_preferences();
+        }
+    }
+
+    /// Matches in every list as (list index, item index) pairs, active list first.
+    fn search_results(&self) -> Vec<(usize, usize)> {
+        let Ok(query) = Query::parse(&self.search_query) else {
+            return Vec::new();
         };
-        self.state.select(Some(i));
-        self.scroll_state = self.scroll_state.position(i.saturating_mul(ITEM_HEIGHT));
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let active = std::iter::once((self.active_list, self.items.as_slice()));
+        let others = self
+            .lists
+            .iter()
+            .enumerate()
+            .filter(|(list, _)| *list != self.active_list)
+            .map(|(list, todo_list)| (list, todo_list.items.as_slice()));
+
+        active
+            .chain(others)
+            .flat_map(|(list, items)| {
+                items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, item)| {
+                        query.matches(item, self.clock, self.preferences.week_start)
+                    })
+                    .map(move |(index, _)| (list, index))
+            })
+            .collect()
     }
 
-    pub fn next_color(&mut self) {
-        self.color_index = (self.color_index + 1) % PALETTES.len();
+    fn item_in_list(&self, list: usize, index: usize) -> &Data {
+        if list == self.active_list {
+            &self.items[index]
+        } else {
+            &self.lists[list].items[index]
+        }
     }
 
-    pub fn previous_color(&mut self) {
-        let count = PALETTES.len();
-        self.color_index = (self.color_index + count - 1) % count;
+    fn toggle_search(&mut self) {
+        self.show_search = !self.show_search;
+        self.search_query.clear();
+        self.search_selected = 0;
+        self.search_history.reset();
     }
 
-    pub fn set_colors(&mut self) {
-        self.colors = TableColors::new(&PALETTES[self.color_index]);
+    /// Switches to the hit's list and selects it, clearing filters that would hide it.
+    fn open_search_result(&mut self) {
+        let Some(&(list, index)) = self.search_results().get(self.search_selected) else {
+            return;
+        };
+        self.toggle_search();
+        self.switch_list(list);
+
+        self.tag_filter = None;
+        if self.items[index].progress == Progress::Done && self.hide_completed {
+            self.set_hide_completed(false);
+        }
+        self.select_item(index);
     }
 
-    fn delete(&mut self) {
-        if let Some(selected) = self.state.selected() {
-            if !self.items.is_empty() {
-                let filtered_items = self.get_filtered_items();
-
-                // Ensure the selected index is valid for the filtered items
-                if selected < filtered_items.len() {
-                    let selected_item = filtered_items[selected];
-
-                    if let Some(index) = self.items.iter().position(|item| {
-                        item.name == selected_item.name
-                            && item.description == selected_item.description
-                            && item.progress == selected_item.progress
-                            && item.created == selected_item.created
-                    }) {
-                        self.items.remove(index);
-
-                        let new_index = if index >= self.items.len() {
-                            self.items.len().saturating_sub(1)
-                        } else {
-                            index
-                        };
-                        self.state.select(Some(new_index));
-                        self.scroll_state = self.scroll_state.position(new_index * ITEM_HEIGHT);
+    fn toggle_command(&mut self) {
+        self.show_command = !self.show_command;
+        self.input_command.clear();
+        self.import_preview = None;
+    }
 
-                        if let Err(e) = save_json(&self.items) {
-                            eprintln!("Error saving JSON: {e}");
-                        }
-                    }
-                }
+    /// Every change the typed command would make, used for both the preview and applying it.
+    fn command_changes(&self) -> Result<(Command, Vec<Change>), String> {
+        let command = Command::parse(&self.input_command)?;
+        let targets = if command.covers_all() {
+            (0..self.items.len()).collect()
+        } else {
+            self.filtered_indices()
+        };
+        let changes = command.changes(&self.items, targets);
+        Ok((command, changes))
+    }
+
+    /// The typed command's error, or the preview of what it would do.
+    fn command_preview(&mut self) -> (Option<String>, Vec<Line<'static>>) {
+        if self.input_command.trim().is_empty() {
+            return (None, vec![Line::from(command::USAGE).italic()]);
+        }
+
+        let (command, changes) = match self.command_changes() {
+            Ok(command_changes) => command_changes,
+            Err(error) => return (Some(error), Vec::new()),
+        };
+
+        if let Action::Filter(filters) = &command.action {
+            let shown: Vec<&Data> = self
+                .items
+                .iter()
+                .filter(|item| {
+                    filters
+                        .iter()
+                        .all(|filter| filter.matches(item, self.clock, self.preferences.week_start))
+                })
+                .collect();
+            let mut preview =
+                vec![Line::from(format!("{} todo(s) will be shown", shown.len())).bold()];
+            preview.extend(shown.into_iter().map(|item| Line::from(item.name.clone())));
+            return (None, preview);
+        }
+
+        if let Action::Share(_) = &command.action {
+            let Some(item) = self.selected_item() else {
+                return (Some("Select a todo to share".to_string()), Vec::new());
+            };
+            let preview = vec![
+                Line::from(command.summary(1)).bold(),
+                Line::from(item.name.clone()),
+            ];
+            return (None, preview);
+        }
+
+        if let Action::Import(path) = &command.action {
+            // Drawn on every tick, so the file is only read again once another is typed
+            let imported = match self.import_preview.take() {
+                Some((previewed, imported)) if previewed == *path => imported,
+                _ => import::read(path).map_err(|e| e.to_string()),
+            };
+            self.import_preview = Some((path.clone(), imported.clone()));
+            let merge = match imported {
+                Ok(imported) => import::merge(&self.items, imported),
+                Err(e) => return (Some(e), Vec::new()),
+            };
+            let mut preview = vec![Line::from(format!(
+                "{} added, {} updated, {} skipped as duplicates",
+                merge.added.len(),
+                merge.updated.len(),
+                merge.skipped
+            ))
+            .bold()];
+            preview.extend(merge.added.into_iter().map(|item| {
+                Line::from(vec![
+                    Span::styled(item.name, Style::new().fg(Color::Green)),
+                    Span::from("  added").italic(),
+                ])
+            }));
+            for change in merge.updated {
+                preview.extend(change_lines(&self.items[change.index], change));
             }
+            return (None, preview);
+        }
+
+        let mut preview = vec![Line::from(command.summary(changes.len())).bold()];
+        for change in changes {
+            preview.extend(change_lines(&self.items[change.index], change));
         }
+        (None, preview)
     }
 
-    fn run(&mut self, mut terminal: DefaultTerminal) -> Result<()> {
-        loop {
-            terminal.draw(|frame| self.draw(frame))?;
+    fn apply_command(&mut self) {
+        let Ok((command, changes)) = self.command_changes() else {
+            return;
+        };
 
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    if self.show_create {
-                        match key.code {
-                            KeyCode::Esc => self.show_create = false,
-                            KeyCode::Enter => {
-                                if self.input_focus == InputFocus::Description {
-                                    self.save_item(); // Save and close the popup
-                                } else {
-                                    self.input_focus = InputFocus::Description;
-                                }
-                            }
-                            _ => {
-                                self.handle_popup_input(key.code);
-                            }
-                        }
-                    } else if self.show_info {
-                        match key.code {
-                            KeyCode::Esc | KeyCode::Char('i') => self.show_info = false,
-                            _ => self.handle_info_input(key.code), // Handle input for the info popup
-                        }
-                    } else {
-                        match key.code {
-                            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
-                            KeyCode::Char('j') | KeyCode::Down => self.next(),
-                            KeyCode::Char('k') | KeyCode::Up => self.previous(),
-                            KeyCode::Char('l') | KeyCode::Right => {
-                                let lock_color_checked = Option::unwrap_or(
-                                    self.info_popup
-                                        .checkboxes
-                                        .iter()
-                                        .find(|checkbox| checkbox.label == "Lock Color")
-                                        .map(|checkbox| checkbox.checked),
-                                    false,
-                                );
-                                if !lock_color_checked {
-                                    self.next_color();
-                                }
-                            }
-                            KeyCode::Char('h') | KeyCode::Left => {
-                                let lock_color_checked = Option::unwrap_or(
-                                    self.info_popup
-                                        .checkboxes
-                                        .iter()
-                                        .find(|checkbox| checkbox.label == "Lock Color")
-                                        .map(|checkbox| checkbox.checked),
-                                    false,
-                                );
-                                if !lock_color_checked {
-                                    self.previous_color();
-                                }
-                            }
-                            KeyCode::Char('x') | KeyCode::Delete => self.delete(),
-                            KeyCode::Char('i') => self.toggle_info(),
-                            KeyCode::Char('r') => {
-                                self.edit_item(); // Call edit item logic
-                            }
-                            KeyCode::Char('a') => {
-                                self.editing_index = None;
-                                self.toggle_create(); // Toggle create popup
-                            }
-                            KeyCode::Char('n') => self.next_progress(),
-                            KeyCode::Char('t') => {
-                                self.hide_completed = !self.hide_completed; // Toggle hiding
-                                self.update_selected_index(); // Ensure the selection is valid
-                            }
-                            _ => {}
-                        }
-                    }
+        if let Action::Import(path) = &command.action {
+            let imported = match import::read(path) {
+                Ok(imported) => imported,
+                Err(e) => {
+                    self.show_error(format!("Couldn't import {}: {e}", path.display()), None);
+                    return;
+                }
+            };
+            let merge = import::merge(&self.items, imported);
+            tracing::info!(
+                path = %path.display(),
+                added = merge.added.len(),
+                updated = merge.updated.len(),
+                skipped = merge.skipped,
+                "imported"
+            );
+            self.notify(format!(
+                "Imported: {} added, {} updated, {} skipped",
+                merge.added.len(),
+                merge.updated.len(),
+                merge.skipped
+            ));
+            self.apply_changes(merge.updated);
+            self.items.extend(merge.added);
+            self.save_after_command();
+            return;
+        }
+
+        if let Action::Share(target) = &command.action {
+            let Some(item) = self.selected_item() else {
+                return;
+            };
+            let result = match target {
+                Some(path) => share::write(path, item),
+                None => share::copy(item),
+            };
+            match result {
+                Ok(()) => {
+                    tracing::info!(name = %item.name, "shared todo");
+                    self.notify(command.outcome(1));
                 }
+                Err(e) => self.show_error(format!("Couldn't share the todo: {e}"), None),
             }
+            self.toggle_command();
+            return;
+        }
+
+        if let Action::Filter(filters) = command.action {
+            let terms: Vec<String> = filters.iter().map(DateFilter::term).collect();
+            tracing::info!(filters = %terms.join(" "), "filtered by date");
+            self.date_filters = filters;
+            self.toggle_command();
+            self.update_selected_index();
+            return;
+        }
+
+        if let Action::Export(path) = &command.action {
+            let items: Vec<&Data> = changes
+                .iter()
+                .map(|change| &self.items[change.index])
+                .collect();
+            let result = export::write(path, &items);
+            let count = items.len();
+            match result {
+                Ok(()) => {
+                    tracing::info!(path = %path.display(), todos = count, "exported");
+                    self.notify(command.outcome(count));
+                }
+                Err(e) => {
+                    self.show_error(format!("Couldn't export to {}: {e}", path.display()), None);
+                }
+            }
+            self.toggle_command();
+            return;
         }
+
+        tracing::info!(command = %self.input_command, changes = changes.len(), "applied command");
+        self.notify(command.outcome(changes.len()));
+        self.apply_changes(changes);
+        self.save_after_command();
     }
 
-    fn update_selected_index(&mut self) {
-        let filtered_items = self.get_filtered_items();
+    fn apply_changes(&mut self, changes: Vec<Change>) {
+        let mut removed = Vec::new();
+        for change in changes {
+            if change.remove {
+                removed.push(change.index);
+                continue;
+            }
+            let item = &mut self.items[change.index];
+            if let Some(name) = change.name {
+                item.name = name;
+            }
+            if let Some(description) = change.description {
+                item.description = description;
+            }
+            if let Some(progress) = change.progress {
+                item.progress = progress;
+            }
+        }
 
-        // Check if the currently selected index is still valid
-        if let Some(selected) = self.state.selected() {
-            if selected >= filtered_items.len() {
-                // Reset selection to the first visible item if out of bounds
-                self.state.select(Some(0));
+        if !removed.is_empty() {
+            // Back to front so the remaining indices stay valid
+            for index in removed.into_iter().rev() {
+                self.items.remove(index);
             }
-        } else {
-            // If nothing is selected, default to the first item
-            self.state.select(Some(0));
+            self.select_row(0);
         }
     }
-    fn edit_item(&mut self) {
-        if let Some(selected) = self.state.selected() {
-            let filtered_items = self.get_filtered_items();
 
-            // Ensure the selected index is valid for the filtered items
-            if selected < filtered_items.len() {
-                let selected_item = filtered_items[selected];
+    fn save_after_command(&mut self) {
+        self.save_items();
+        self.longest_item_lens = constraint_len_calculator(&self.items);
+        self.toggle_command();
+    }
 
-                if let Some(index) = self
-                    .items
-                    .iter()
-                    .position(|item| App::<'_>::item_matches(item, selected_item))
-                {
-                    self.editing_index = Some(index);
-                    self.toggle_create(); // Open the popup
+    fn handle_command_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => self.toggle_command(),
+            KeyCode::Enter => self.apply_command(),
+            KeyCode::Backspace => {
+                self.input_command.pop();
+            }
+            KeyCode::Char(c) if has_room(&self.input_command, 255) => self.input_command.push(c),
+            _ => {}
+        }
+    }
+
+    fn handle_search_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => self.toggle_search(),
+            KeyCode::Enter => {
+                self.search_history.push(&self.search_query);
+                let path = History::path(&self.lists[0].path);
+                if let Err(e) = self.search_history.save(&path) {
+                    tracing::warn!(path = %path.display(), "couldn't save the search history: {e}");
                 }
+                self.open_search_result();
             }
+            KeyCode::Tab => {
+                let count = self.search_results().len();
+                self.search_selected = (self.search_selected + 1).min(count.saturating_sub(1));
+            }
+            KeyCode::BackTab => self.search_selected = self.search_selected.saturating_sub(1),
+            KeyCode::Up => {
+                if let Some(query) = self.search_history.older(&self.search_query) {
+                    self.search_query = query.to_string();
+                    self.search_selected = 0;
+                }
+            }
+            KeyCode::Down => {
+                if let Some(query) = self.search_history.newer() {
+                    self.search_query = query.to_string();
+                    self.search_selected = 0;
+                }
+            }
+            KeyCode::Backspace => {
+                self.search_query.pop();
+                self.search_selected = 0;
+                self.search_history.reset();
+            }
+            KeyCode::Char(c) if has_room(&self.search_query, 100) => {
+                self.search_query.push(c);
+                self.search_selected = 0;
+                self.search_history.reset();
+            }
+            _ => {}
         }
     }
 
-    fn save_item(&mut self) {
-        if self.input_name.trim().is_empty() {
-            return; // Don't save if the name is empty
+    fn cycle_list(&mut self, forward: bool) {
+        let count = self.lists.len();
+        let index = if forward {
+            (self.active_list + 1) % count
+        } else {
+            (self.active_list + count - 1) % count
+        };
+        self.switch_list(index);
+    }
+
+    fn toggle_new_list(&mut self) {
+        self.show_new_list = !self.show_new_list;
+        self.input_list_name.clear();
+    }
+
+    fn add_list(&mut self) {
+        let name = self.input_list_name.trim().to_string();
+        if name.is_empty() {
+            return;
         }
 
-        let item = self.create_item();
+        match lists::create(self.preferences.data_dir(), &name) {
+            Ok(list) => {
+                self.lists.push(list);
+                self.toggle_new_list();
+                self.switch_list(self.lists.len() - 1);
+                tracing::info!(list = %name, "created list");
+                self.notify(format!("List \"{name}\" created"));
+            }
+            Err(e) => self.show_error(format!("Couldn't create the list: {e}"), None),
+        }
+    }
 
-        if let Some(index) = self.editing_index {
-            // If editing an existing item, update it
-            self.items[index] = item;
+    fn handle_new_list_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => self.toggle_new_list(),
+            KeyCode::Enter => self.add_list(),
+            KeyCode::Backspace => {
+                self.input_list_name.pop();
+            }
+            // Only allow characters that are safe in a file name
+            KeyCode::Char(c)
+                if (c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+                    && has_room(&self.input_list_name, 30) =>
+            {
+                self.input_list_name.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Starts recording every key pressed, or stops and keeps them as the macro.
+    fn toggle_recording(&mut self) {
+        match self.recording.take() {
+            Some(mut keys) => {
+                keys.pop(); // The Shift+Q that stopped it
+                tracing::info!(keys = keys.len(), "recorded macro");
+                self.notify(format!(
+                    "Recorded a macro of {} keys, @ replays it",
+                    keys.len()
+                ));
+                self.macro_keys = keys;
+            }
+            None => self.recording = Some(Vec::new()),
+        }
+    }
+
+    fn toggle_replay(&mut self) {
+        if self.show_replay {
+            self.show_replay = false;
+        } else if self.replaying {
+            // A macro replaying itself would never end
+        } else if self.recording.is_some() {
+            if let Some(keys) = &mut self.recording {
+                keys.pop();
+            }
+            self.warn("Stop recording with Shift+Q before replaying");
+        } else if self.macro_keys.is_empty() {
+            self.notify("No macro yet, Shift+Q starts recording one");
         } else {
-            // Otherwise, add a new item
-            self.items.push(item);
+            self.show_replay = true;
+            self.input_replay_count.clear();
+        }
+    }
+
+    fn handle_replay_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => self.toggle_replay(),
+            KeyCode::Enter => {
+                let count = self.input_replay_count.parse().unwrap_or(1);
+                self.toggle_replay();
+                self.replay_macro(count);
+            }
+            KeyCode::Backspace => {
+                self.input_replay_count.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() && self.input_replay_count.len() < 3 => {
+                self.input_replay_count.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn toggle_set_field(&mut self) {
+        self.show_set_field = !self.show_set_field;
+        self.input_field.clear();
+    }
+
+    fn handle_set_field_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => self.toggle_set_field(),
+            KeyCode::Enter => self.set_field(),
+            KeyCode::Backspace => {
+                self.input_field.pop();
+            }
+            KeyCode::Char(c) if has_room(&self.input_field, NAME_LIMIT) => self.input_field.push(c),
+            _ => {}
+        }
+    }
+
+    /// Sets or removes the field typed in the prompt on the selected todo.
+    fn set_field(&mut self) {
+        let Some(index) = self.selected_index() else {
+            self.toggle_set_field();
+            return;
+        };
+        let input = mem::take(&mut self.input_field);
+        if let Err(message) = self.items[index].set_field(&input) {
+            self.input_field = input;
+            self.show_error(message, None);
+            return;
+        }
+        tracing::info!(name = %self.items[index].name, field = %input, "set field");
+        self.toggle_set_field();
+        self.save_items();
+    }
+
+    fn toggle_add_note(&mut self) {
+        self.show_add_note = self.selected_index().is_some() && !self.show_add_note;
+        self.input_note.clear();
+    }
+
+    fn handle_add_note_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => self.toggle_add_note(),
+            KeyCode::Enter => self.add_note(),
+            KeyCode::Backspace => {
+                self.input_note.pop();
+            }
+            KeyCode::Char(c) if has_room(&self.input_note, DESCRIPTION_LIMIT) => {
+                self.input_note.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds the note typed in the prompt to the end of the selected todo's log.
+    fn add_note(&mut self) {
+        let text = self.input_note.trim().to_string();
+        let Some(index) = self.selected_index().filter(|_| !text.is_empty()) else {
+            self.toggle_add_note();
+            return;
+        };
+        self.items[index].notes.push(Note {
+            at: timestamps::now(),
+            text,
+        });
+        tracing::info!(name = %self.items[index].name, "added note");
+        self.toggle_add_note();
+        self.save_items();
+    }
+
+    /// Presses the macro's keys `count` times over, stopping at the first
+    /// error. Keys that would quit only stop the replay.
+    fn replay_macro(&mut self, count: usize) {
+        tracing::info!(keys = self.macro_keys.len(), count, "replaying macro");
+        let keys = self.macro_keys.clone();
+        self.replaying = true;
+        'replay: for _ in 0..count {
+            for &key in &keys {
+                if !self.handle_key(key) || !self.errors.is_empty() {
+                    break 'replay;
+                }
+            }
+        }
+        self.replaying = false;
+    }
+
+    /// The table as it's shown right now.
+    fn view(&self) -> ViewModel {
+        let indices = self.filtered_indices();
+        let rows = if self.preferences.group_by_date {
+            view_model::group_by_date(
+                indices
+                    .into_iter()
+                    .map(|index| {
+                        let item = &self.items[index];
+                        (index, item.due_date(), item.due_time())
+                    })
+                    .collect(),
+                Local::now().date_naive(),
+                self.preferences.week_start,
+            )
+        } else {
+            indices.into_iter().map(Entry::Task).collect()
+        };
+        ViewModel::new(rows, self.state.selected(), self.row_height())
+    }
+
+    /// The selected task, if any row is selected.
+    fn selected_item(&self) -> Option<&Data> {
+        self.view().selected_index().map(|index| &self.items[index])
+    }
+
+    /// Indices into `items` of the visible rows, in display order.
+    fn filtered_indices(&self) -> Vec<usize> {
+        let mut filtered_indices: Vec<usize> = (0..self.items.len())
+            .filter(|&index| {
+                let item = &self.items[index];
+                // Filter out completed items if hiding them
+                // A todo just marked done stays until its flash is over
+                !(self.hide_completed && item.progress == Progress::Done && !self.is_flashing(item))
+                    && self
+                        .tag_filter
+                        .as_ref()
+                        .is_none_or(|tag| item.tags().contains(tag))
+                    && self
+                        .date_filters
+                        .iter()
+                        .all(|filter| filter.matches(item, self.clock, self.preferences.week_start))
+            })
+            .collect();
+
+        let items = &self.items;
+        match self.preferences.sort {
+            SortMode::Manual => {}
+            SortMode::Created => filtered_indices
+                .sort_by_key(|&index| Reverse(timestamps::parse(&items[index].created))),
+            // Within a day, todos due at a time come first
+            SortMode::Due => filtered_indices.sort_by_key(|&index| {
+                let due = items[index].due_date();
+                let time = items[index].due_time();
+                (due.is_none(), due, time.is_none(), time)
+            }),
+            SortMode::Priority => {
+                filtered_indices.sort_by_key(|&index| Reverse(items[index].priority));
+            }
+            SortMode::Urgency => {
+                let today = self.clock.date(Utc::now());
+                filtered_indices.sort_by_key(|&index| Reverse(items[index].urgency(today)));
+            }
+            SortMode::Name => {
+                filtered_indices.sort_by_key(|&index| items[index].name.to_lowercase());
+            }
+        }
+
+        // Starred items float to the top, otherwise keeping their order
+        filtered_indices.sort_by_key(|&index| !self.items[index].starred);
+        filtered_indices
+    }
+    fn is_flashing(&self, item: &Data) -> bool {
+        self.flashing
+            .iter()
+            .any(|(created, _)| created == &item.created)
+    }
+
+    /// Lets todos marked done a while ago go, hiding them now if completed
+    /// todos are hidden.
+    fn end_flashes(&mut self) {
+        let count = self.flashing.len();
+        self.flashing
+            .retain(|(_, since)| since.elapsed() < COMPLETION_FLASH);
+        if self.flashing.len() != count {
+            self.update_selected_index();
+        }
+    }
+
+    fn toggle_info(&mut self) {
+        self.show_info = !self.show_info;
+    }
+
+    fn toggle_settings(&mut self) {
+        self.show_settings = !self.show_settings;
+        self.settings_input = None;
+    }
+
+    fn handle_settings_input(&mut self, key: KeyCode) {
+        let count = Setting::ALL.len();
+        let setting = Setting::ALL[self.settings_selected % count];
+        if let Some(input) = &mut self.settings_input {
+            match key {
+                KeyCode::Esc => self.settings_input = None,
+                KeyCode::Enter => {
+                    let text = self.settings_input.take().unwrap_or_default();
+                    setting.set_text(&mut self.preferences, &text);
+                    tracing::info!(setting = setting.label(), text, "changed setting");
+                    self.save_preferences();
+                    self.notify("Takes effect the next time todo-tui starts");
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) if has_room(input, 200) => input.push(c),
+                _ => {}
+            }
+            return;
+        }
+
+        let selected = &mut self.settings_selected;
+        match key {
+            KeyCode::Esc | KeyCode::Char(',' | 'q') => self.toggle_settings(),
+            // Both wrap around
+            KeyCode::Down | KeyCode::Char('j') => *selected = (*selected + 1) % count,
+            KeyCode::Up | KeyCode::Char('k') => *selected = (*selected + count - 1) % count,
+            KeyCode::Enter | KeyCode::Right | KeyCode::Left => match self.setting(setting) {
+                Value::Text(text) if key == KeyCode::Enter => self.settings_input = Some(text),
+                Value::Text(_) => {}
+                Value::Toggle(_) | Value::Choice(_) => {
+                    self.change_setting(setting, key != KeyCode::Left);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn create_item(&self) -> Data {
+        Data {
+            name: self.input_name.clone(),
+            description: self.input_description.clone(),
+            progress: self.input_progress.clone(),
+            priority: self.input_priority,
+            created: timestamps::now(),
+            due: self
+                .input_due
+                .map(|date| {
+                    let time = NaiveTime::parse_from_str(&self.input_due_time, DUE_TIME_FORMAT);
+                    format_due(date, time.ok())
+                })
+                .or_else(|| self.invalid_due.clone()),
+            ..Data::default()
+        }
+    }
+
+    fn toggle_quick_add(&mut self) {
+        self.show_quick_add = !self.show_quick_add;
+        self.input_quick_add.clear();
+    }
+
+    /// Adds a todo with just the typed name, leaving the prompt open for the
+    /// next one. `Enter` with nothing typed closes it.
+    fn quick_add(&mut self) {
+        let name = self.input_quick_add.trim().to_string();
+        if name.is_empty() {
+            self.toggle_quick_add();
+            return;
+        }
+
+        tracing::info!(%name, "quick added todo");
+        let item = Data {
+            name,
+            progress: self.preferences.new_status.clone(),
+            created: self.unused_created(),
+            ..Data::default()
+        };
+        self.items.push(item);
+        self.save_items();
+        self.input_quick_add.clear();
+        self.notify("Todo added");
+    }
+
+    /// Adds the todos piped in with `--capture` to the list, as (name,
+    /// description) cut to the fields' limits.
+    fn capture(&mut self, todos: Vec<(String, String)>) {
+        let count = todos.len();
+        for (name, description) in todos {
+            let item = Data {
+                name: name.chars().take(NAME_LIMIT).collect(),
+                description: description.chars().take(DESCRIPTION_LIMIT).collect(),
+                progress: self.preferences.new_status.clone(),
+                created: self.unused_created(),
+                ..Data::default()
+            };
+            self.items.push(item);
+        }
+        tracing::info!(todos = count, "captured todos");
+        if count > 0 {
+            self.save_items();
+            self.longest_item_lens = constraint_len_calculator(&self.items);
+            self.update_selected_index();
+        }
+        self.notify(format!("Captured {count} todo(s)"));
+    }
+
+    /// The current time as a creation time no todo in the list has yet, a
+    /// second later for every one that does, as it's what identifies them.
+    fn unused_created(&self) -> String {
+        let mut at = Utc::now();
+        loop {
+            let created = at.to_rfc3339_opts(SecondsFormat::Secs, true);
+            if !self.items.iter().any(|item| item.created == created) {
+                return created;
+            }
+            at += chrono::Duration::seconds(1);
+        }
+    }
+
+    fn handle_quick_add_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Esc => self.toggle_quick_add(),
+            KeyCode::Enter => self.quick_add(),
+            KeyCode::Backspace => {
+                self.input_quick_add.pop();
+            }
+            KeyCode::Char(c) if has_room(&self.input_quick_add, NAME_LIMIT) => {
+                self.input_quick_add.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn toggle_create(&mut self) {
+        self.show_create = !self.show_create;
+
+        if self.show_create {
+            // Clear fields every time the popup is opened
+            self.input_name.clear();
+            self.input_description.clear();
+            self.input_progress = self.preferences.new_status.clone();
+            self.input_priority = None;
+            self.input_due = None;
+            self.input_due_time.clear();
+            self.invalid_due = None;
+            self.name_missing = false;
+            self.due_time_invalid = false;
+
+            if let Some(index) = self.editing_index {
+                // Load the existing item's data if editing
+                self.input_name = self.items[index].name.clone();
+                self.input_description = self.items[index].description.clone();
+                self.input_progress = self.items[index].progress.clone();
+                self.input_priority = self.items[index].priority;
+                self.input_due = self.items[index].due_date();
+                self.input_due_time = self.items[index]
+                    .due_time()
+                    .map(|time| time.format(DUE_TIME_FORMAT).to_string())
+                    .unwrap_or_default();
+                if self.input_due.is_none() {
+                    self.invalid_due = self.items[index].due.clone();
+                }
+            }
+            self.due_cursor = self.input_due.unwrap_or_else(|| Local::now().date_naive());
+
+            // Set focus to the name field by default
+            self.input_focus = InputFocus::Name;
+        } else {
+            // When closing the popup, reset the editing index and clear the input fields
+            self.editing_index = None; // Reset the editing index when closing
+            self.input_name.clear();
+            self.input_description.clear();
+        }
+    }
+
+    fn add_item(&mut self) {
+        if self.refuse_empty_name() || self.refuse_invalid_time() {
+            return;
+        }
+
+        let new_item = self.create_item();
+        self.items.push(new_item);
+
+        self.save_items();
+
+        self.toggle_create();
+    }
+
+    fn handle_popup_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char(c)
+                if self.input_focus == InputFocus::Name
+                    && has_room(&self.input_name, NAME_LIMIT) =>
+            {
+                self.input_name.push(c);
+            }
+            KeyCode::Char(c)
+                if self.input_focus == InputFocus::Description
+                    && has_room(&self.input_description, DESCRIPTION_LIMIT) =>
+            {
+                self.input_description.push(c);
+            }
+            KeyCode::Backspace => match self.input_focus {
+                InputFocus::Name => {
+                    if !self.input_name.is_empty() {
+                        self.input_name.pop();
+                    }
+                }
+                InputFocus::Description => {
+                    if !self.input_description.is_empty() {
+                        self.input_description.pop();
+                    }
+                }
+                InputFocus::Due if !self.input_due_time.is_empty() => {
+                    self.input_due_time.pop();
+                }
+                InputFocus::Due => self.clear_due(),
+                InputFocus::Status | InputFocus::Priority => {}
+            },
+            KeyCode::Left | KeyCode::Right if self.input_focus == InputFocus::Status => {
+                self.input_progress = self.input_progress.cycle(key == KeyCode::Right);
+            }
+            KeyCode::Left | KeyCode::Right if self.input_focus == InputFocus::Priority => {
+                self.input_priority = Priority::cycle(self.input_priority, key == KeyCode::Right);
+            }
+            KeyCode::Delete if self.input_focus == InputFocus::Due => self.clear_due(),
+            KeyCode::Char(' ') if self.input_focus == InputFocus::Due => {
+                self.input_due = Some(self.due_cursor);
+            }
+            // A time of day, on the highlighted day if none is picked yet
+            KeyCode::Char(c @ ('0'..='9' | ':'))
+                if self.input_focus == InputFocus::Due && self.input_due_time.len() < 5 =>
+            {
+                self.input_due_time.push(c);
+                self.input_due.get_or_insert(self.due_cursor);
+                self.due_time_invalid = false;
+            }
+            KeyCode::Left if self.input_focus == InputFocus::Due => self.move_due(-1),
+            KeyCode::Right if self.input_focus == InputFocus::Due => self.move_due(1),
+            KeyCode::Up if self.input_focus == InputFocus::Due => self.move_due(-7),
+            KeyCode::Down if self.input_focus == InputFocus::Due => self.move_due(7),
+            KeyCode::PageUp if self.input_focus == InputFocus::Due => {
+                self.due_cursor = date_picker::shift_months(self.due_cursor, -1);
+                self.input_due = Some(self.due_cursor);
+            }
+            KeyCode::PageDown if self.input_focus == InputFocus::Due => {
+                self.due_cursor = date_picker::shift_months(self.due_cursor, 1);
+                self.input_due = Some(self.due_cursor);
+            }
+            KeyCode::Enter => {
+                if self.input_focus == InputFocus::Name {
+                    self.input_focus = InputFocus::Description;
+                } else {
+                    self.add_item(); // Save and close the popup
+                }
+            }
+            KeyCode::Tab if !self.show_info => {
+                self.input_focus = match self.input_focus {
+                    InputFocus::Name => InputFocus::Status,
+                    InputFocus::Status => InputFocus::Priority,
+                    InputFocus::Priority => InputFocus::Description,
+                    InputFocus::Description => InputFocus::Due,
+                    InputFocus::Due => InputFocus::Name,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn clear_due(&mut self) {
+        self.input_due = None;
+        self.input_due_time.clear();
+        self.invalid_due = None;
+    }
+
+    /// Keeps the popup open on the due date when the typed time isn't HH:MM.
+    fn refuse_invalid_time(&mut self) -> bool {
+        self.due_time_invalid = !self.input_due_time.is_empty()
+            && NaiveTime::parse_from_str(&self.input_due_time, DUE_TIME_FORMAT).is_err();
+        if self.due_time_invalid {
+            self.input_focus = InputFocus::Due;
+        }
+        self.due_time_invalid
+    }
+
+    /// Keeps the popup open with a message on the name field when the name is empty.
+    fn refuse_empty_name(&mut self) -> bool {
+        self.name_missing = self.input_name.trim().is_empty();
+        if self.name_missing {
+            self.input_focus = InputFocus::Name;
+        }
+        self.name_missing
+    }
+
+    /// Moves the date picker by `days` and picks the day it lands on.
+    fn move_due(&mut self, days: i64) {
+        if let Some(date) = self
+            .due_cursor
+            .checked_add_signed(chrono::Duration::days(days))
+        {
+            self.due_cursor = date;
+        }
+        self.input_due = Some(self.due_cursor);
+    }
+
+    pub fn next(&mut self) {
+        if let Some(row) = self.view().next_row() {
+            self.select_row(row);
+            self.cell_scroll = 0;
+            self.preview_scroll = 0;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if let Some(row) = self.view().previous_row() {
+            self.select_row(row);
+            self.cell_scroll = 0;
+            self.preview_scroll = 0;
+        }
+    }
+
+    pub fn next_color(&mut self) {
+        self.color_index = (self.color_index + 1) % THEMES.len();
+    }
+
+    pub fn previous_color(&mut self) {
+        let count = THEMES.len();
+        self.color_index = (self.color_index + count - 1) % count;
+    }
+
+    const fn row_height(&self) -> usize {
+        self.density().row_height()
+    }
+
+    /// Always compact in the few lines of `--inline`.
+    const fn density(&self) -> Density {
+        if self.inline {
+            Density::Compact
+        } else {
+            self.preferences.density
+        }
+    }
+
+    fn cycle_density(&mut self) {
+        self.preferences.density = self.preferences.density.next();
+        self.save_preferences();
+    }
+
+    /// Tab cycles Sidebar (when shown) -> Name -> Description -> Preview (split layout only).
+    fn cycle_focus(&mut self) {
+        let mut stops = Vec::new();
+        if self.preferences.show_sidebar {
+            stops.push((PaneFocus::Sidebar, ColumnFocus::Name));
+        }
+        stops.push((PaneFocus::Table, ColumnFocus::Name));
+        stops.push((PaneFocus::Table, ColumnFocus::Description));
+        if self.preferences.layout == ViewLayout::Split {
+            stops.push((PaneFocus::Preview, ColumnFocus::Description));
+        }
+
+        let current = stops
+            .iter()
+            .position(|&stop| stop == (self.pane_focus, self.column_focus))
+            .unwrap_or(0);
+        (self.pane_focus, self.column_focus) = stops[(current + 1) % stops.len()];
+        self.cell_scroll = 0;
+    }
+
+    fn toggle_group_by_date(&mut self) {
+        // Stay on the same task, now in another row
+        let selected = self.selected_index();
+        self.preferences.group_by_date = !self.preferences.group_by_date;
+        if let Some(index) = selected {
+            self.select_item(index);
+        }
+        self.save_preferences();
+    }
+
+    fn cycle_sort(&mut self) {
+        // Stay on the same task, now in another row
+        let selected = self.selected_index();
+        self.preferences.sort = self.preferences.sort.next();
+        tracing::info!(sort = self.preferences.sort.label(), "sorting");
+        if let Some(index) = selected {
+            self.select_item(index);
+        }
+        self.save_preferences();
+    }
+
+    fn toggle_sidebar(&mut self) {
+        self.preferences.show_sidebar = !self.preferences.show_sidebar;
+        if self.preferences.show_sidebar {
+            self.pane_focus = PaneFocus::Sidebar;
+        } else {
+            // Don't keep filtering by a tag that's no longer visible
+            self.pane_focus = PaneFocus::Table;
+            self.tag_filter = None;
+            self.update_selected_index();
+        }
+
+        self.save_preferences();
+    }
+
+    /// Every tag in the list with the number of open tasks carrying it.
+    fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for item in &self.items {
+            let open = usize::from(item.progress != Progress::Done);
+            for tag in item.tags() {
+                match counts.binary_search_by(|(existing, _)| existing.cmp(&tag)) {
+                    Ok(index) => counts[index].1 += open,
+                    Err(index) => counts.insert(index, (tag, open)),
+                }
+            }
+        }
+        counts
+    }
+
+    /// Opens the tag popup with every tag in the list, the selected todo's
+    /// ones checked.
+    fn toggle_tags(&mut self) {
+        if self.show_tags {
+            self.show_tags = false;
+            return;
+        }
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+        let tags = item.tags();
+        self.tag_choices = self
+            .tag_counts()
+            .into_iter()
+            .map(|(tag, _)| {
+                let checked = tags.contains(&tag);
+                (tag, checked)
+            })
+            .collect();
+        self.tag_selected = 0;
+        self.input_tag.clear();
+        self.show_tags = true;
+    }
+
+    fn handle_tags_input(&mut self, key: KeyCode) {
+        let count = self.tag_choices.len();
+        match key {
+            KeyCode::Esc => self.toggle_tags(),
+            KeyCode::Down => {
+                self.tag_selected = (self.tag_selected + 1).min(count.saturating_sub(1))
+            }
+            KeyCode::Up => self.tag_selected = self.tag_selected.saturating_sub(1),
+            KeyCode::Char(' ') => {
+                if let Some((_, checked)) = self.tag_choices.get_mut(self.tag_selected) {
+                    *checked = !*checked;
+                }
+            }
+            // A typed tag is added to the choices, checked
+            KeyCode::Enter if !self.input_tag.is_empty() => {
+                let tag = self.input_tag.trim_start_matches('#').to_lowercase();
+                self.input_tag.clear();
+                if tag.is_empty() {
+                    return;
+                }
+                let index = match self
+                    .tag_choices
+                    .binary_search_by(|(choice, _)| choice.cmp(&tag))
+                {
+                    Ok(index) => index,
+                    Err(index) => {
+                        self.tag_choices.insert(index, (tag, false));
+                        index
+                    }
+                };
+                self.tag_choices[index].1 = true;
+                self.tag_selected = index;
+            }
+            KeyCode::Enter => {
+                let tags: Vec<String> = self
+                    .tag_choices
+                    .iter()
+                    .filter(|(_, checked)| *checked)
+                    .map(|(tag, _)| tag.clone())
+                    .collect();
+                self.edit_selected(|item| {
+                    let changed = item.tags() != tags;
+                    item.set_tags(&tags);
+                    changed
+                });
+                self.toggle_tags();
+            }
+            KeyCode::Backspace => {
+                self.input_tag.pop();
+            }
+            KeyCode::Char(c) if !c.is_whitespace() && has_room(&self.input_tag, 30) => {
+                self.input_tag.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves the sidebar selection and filters the table by the selected tag.
+    fn move_sidebar(&mut self, delta: isize) {
+        let tags = self.tag_counts();
+        self.sidebar_selected = self
+            .sidebar_selected
+            .saturating_add_signed(delta)
+            .min(tags.len());
+        self.tag_filter = self
+            .sidebar_selected
+            .checked_sub(1)
+            .map(|index| tags[index].0.clone());
+        self.update_selected_index();
+    }
+
+    fn toggle_layout(&mut self) {
+        self.set_layout(match self.preferences.layout {
+            ViewLayout::Table => ViewLayout::Split,
+            ViewLayout::Split => ViewLayout::Kanban,
+            ViewLayout::Kanban => ViewLayout::Table,
+        });
+        self.save_preferences();
+    }
+
+    /// Switches the layout, bringing along the sort and filters it was left with.
+    fn set_layout(&mut self, layout: ViewLayout) {
+        self.remember_view();
+        self.preferences.layout = layout;
+        self.restore_view();
+        if self.pane_focus == PaneFocus::Preview {
+            self.pane_focus = PaneFocus::Table;
+        }
+    }
+
+    fn scroll_cell(&mut self, delta: isize) {
+        let text_len = self
+            .selected_item()
+            .map_or(0, |item| match self.column_focus {
+                ColumnFocus::Name => item.name.chars().count(),
+                ColumnFocus::Description => item.description.chars().count(),
+            });
+
+        self.cell_scroll = self
+            .cell_scroll
+            .saturating_add_signed(delta)
+            .min(text_len.saturating_sub(1));
+    }
+
+    fn resize_column(&mut self, delta: i16) {
+        self.step_column_width(delta);
+        self.save_preferences();
+    }
+
+    /// Grows or shrinks the focused column, within the limits, without saving.
+    fn step_column_width(&mut self, delta: i16) {
+        let widths = &mut self.preferences.column_widths;
+        let width = match self.column_focus {
+            ColumnFocus::Name => &mut widths.name,
+            ColumnFocus::Description => &mut widths.description,
+        };
+        *width = width
+            .saturating_add_signed(delta)
+            .clamp(ColumnWidths::MIN, ColumnWidths::MAX);
+    }
+
+    pub fn set_colors(&mut self) {
+        self.colors = THEMES[self.color_index];
+    }
+
+    /// Deletes the selected todo, or with Confirm Delete on, asks to press the
+    /// key again first.
+    fn delete(&mut self) {
+        let view = self.view();
+        if let (Some(row), Some(index)) = (view.selected(), view.selected_index()) {
+            if self.preferences.confirm_delete && !self.delete_pending {
+                self.delete_pending = true;
+                let name = self.items[index].name.clone();
+                self.warn(format!("Press X again to delete \"{name}\""));
+                return;
+            }
+            self.delete_pending = false;
+
+            let removed = self.items.remove(index);
+            tracing::info!(index, name = %removed.name, "deleted todo");
+
+            // Keep the selection on the row that took its place
+            let remaining = view.len() - 1;
+            self.select_row(row.min(remaining.saturating_sub(1)));
+
+            self.save_items();
+            self.notify("Todo deleted");
+        }
+    }
+
+    /// Draws and handles events until the user quits. `next_event` waits up to
+    /// the given timeout for an event, so tests can drive the app with scripted input.
+    fn run<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        mut next_event: impl FnMut(Duration) -> io::Result<Option<Event>>,
+    ) -> Result<()> {
+        loop {
+            // Open popups may hold indices into the items, so wait until they close
+            if !self.show_create && !self.show_command && self.focus_index.is_none() {
+                self.check_disk();
+            }
+            self.autosave_recovery();
+            self.check_reminders();
+            while self.toasts.front().is_some_and(Toast::is_expired) {
+                self.toasts.pop_front();
+            }
+            let upload_errors: Vec<String> = self
+                .uploader
+                .as_ref()
+                .map(|uploader| uploader.errors().collect())
+                .unwrap_or_default();
+            for message in upload_errors {
+                self.show_error(message, None);
+            }
+            if RESUMED.swap(false, Ordering::Relaxed) {
+                self.reenter_terminal(terminal)?;
+            }
+            self.update_window_title();
+            self.end_flashes();
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // Redraw on every tick so timers keep running without input
+            let Some(event) = next_event(TICK_RATE)? else {
+                continue;
+            };
+
+            match event {
+                Event::Key(key) if key.kind == KeyEventKind::Press && !self.handle_key(key) => {
+                    // Leaves recovery.json behind only if the todos couldn't be saved
+                    self.autosave_recovery();
+                    return Ok(());
+                }
+                Event::Paste(text) => self.handle_paste(&text),
+                Event::Mouse(mouse) => self.handle_mouse(mouse),
+                _ => {}
+            }
+            if mem::take(&mut self.open_editor) {
+                self.edit_description_externally(terminal)?;
+            }
+        }
+    }
+
+    /// Inserts pasted text into the open text input at once, with line breaks
+    /// turned into spaces as every input is a single line.
+    fn handle_paste(&mut self, text: &str) {
+        tracing::debug!(chars = text.chars().count(), "paste");
+        let text = single_line(text);
+        if !self.errors.is_empty() || !self.conflicts.is_empty() {
+            return;
+        }
+        if self.show_create {
+            match self.input_focus {
+                InputFocus::Name => paste_into(&mut self.input_name, &text, NAME_LIMIT),
+                InputFocus::Description => {
+                    paste_into(&mut self.input_description, &text, DESCRIPTION_LIMIT);
+                }
+                InputFocus::Status | InputFocus::Priority | InputFocus::Due => {}
+            }
+        } else if self.show_new_list {
+            // Only keep characters that are safe in a file name
+            let text: String = text
+                .chars()
+                .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+                .collect();
+            paste_into(&mut self.input_list_name, &text, 30);
+        } else if self.show_quick_add {
+            paste_into(&mut self.input_quick_add, &text, NAME_LIMIT);
+        } else if self.show_tags {
+            let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+            paste_into(&mut self.input_tag, &text, 30);
+        } else if let Some(input) = &mut self.preview_input {
+            paste_into(input, &text, DESCRIPTION_LIMIT);
+        } else if self.show_set_field {
+            paste_into(&mut self.input_field, &text, NAME_LIMIT);
+        } else if self.show_add_note {
+            paste_into(&mut self.input_note, &text, DESCRIPTION_LIMIT);
+        } else if self.show_search {
+            paste_into(&mut self.search_query, &text, 100);
+            self.search_selected = 0;
+        } else if self.show_command {
+            paste_into(&mut self.input_command, &text, 255);
+        }
+    }
+
+    /// Takes the terminal back after it was handed to another program, and
+    /// redraws everything.
+    fn reenter_terminal<B: Backend>(&self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        enable_raw_mode()?;
+        if self.inline {
+            execute!(terminal_output(), EnableBracketedPaste)?;
+        } else {
+            execute!(
+                terminal_output(),
+                EnterAlternateScreen,
+                EnableBracketedPaste,
+                EnableMouseCapture
+            )?;
+        }
+        terminal.clear()
+    }
+
+    /// Hands the terminal to `$EDITOR` with the description being typed, then
+    /// takes it back and puts the edited text in the description field.
+    fn edit_description_externally<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+    ) -> io::Result<()> {
+        restore_terminal();
+        let edited = external_editor::edit(&self.input_description);
+        self.reenter_terminal(terminal)?;
+
+        match edited {
+            Ok(edited) => {
+                tracing::info!(editor = %external_editor::editor(), "edited description externally");
+                self.take_edited_description(&edited);
+            }
+            Err(e) => self.show_error(format!("Couldn't edit the description: {e}"), None),
+        }
+        Ok(())
+    }
+
+    /// Puts the text saved in the editor in the description field, flattened
+    /// onto one line and cut to the limit as a paste would be.
+    fn take_edited_description(&mut self, edited: &str) {
+        let edited = single_line(edited);
+        self.input_description.clear();
+        paste_into(&mut self.input_description, &edited, DESCRIPTION_LIMIT);
+        if edited.chars().count() > DESCRIPTION_LIMIT {
+            self.warn(format!("Description cut to {DESCRIPTION_LIMIT} characters"));
+        }
+        self.input_focus = InputFocus::Description;
+    }
+
+    /// Clicking selects a row or card; dragging one moves it to where it's
+    /// dropped, and on the kanban board into the column it's dropped on.
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let overlay_open = !self.errors.is_empty()
+            || !self.conflicts.is_empty()
+            || self.show_create
+            || self.focus_index.is_some()
+            || self.show_new_list
+            || self.show_quick_add
+            || self.show_tags
+            || self.preview_input.is_some()
+            || self.show_replay
+            || self.show_set_field
+            || self.show_add_note
+            || self.show_search
+            || self.show_command
+            || self.show_reader
+            || self.show_info
+            || self.show_settings;
+        if overlay_open {
+            return;
+        }
+
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.dragging = self.item_at(position);
+                if let Some(index) = self.dragging {
+                    self.select_item(index);
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                if let Some(from) = self.dragging.take() {
+                    self.drop_item(from, position);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Index into `items` of the table row or kanban card at `position`.
+    fn item_at(&self, position: Position) -> Option<usize> {
+        if self.preferences.layout == ViewLayout::Kanban {
+            return self
+                .kanban_targets
+                .iter()
+                .find(|target| target.area.contains(position))
+                .and_then(|target| target.card);
+        }
+
+        // The first line is the header
+        if !self.table_area.contains(position) || position.y == self.table_area.y {
+            return None;
+        }
+        let view = self.view();
+        let line = usize::from(position.y - self.table_area.y - 1);
+        view.task_at(view.row_at_line(self.state.offset(), line)?)
+    }
+
+    fn drop_item(&mut self, from: usize, position: Position) {
+        let mut index = from;
+        if self.preferences.layout == ViewLayout::Kanban {
+            let Some(target) = self
+                .kanban_targets
+                .iter()
+                .find(|target| target.area.contains(position))
+            else {
+                return;
+            };
+            let (progress, card) = (target.progress.clone(), target.card);
+            if self.items[from].progress != progress {
+                tracing::info!(name = %self.items[from].name, ?progress, "dragged to column");
+                self.items[from].progress = progress;
+            }
+            if let Some(to) = card {
+                index = self.move_item(from, to);
+            }
+        } else if let Some(to) = self.item_at(position) {
+            index = self.move_item(from, to);
+        }
+
+        self.save_items();
+        self.select_item(index);
+    }
+
+    /// Moves the item at `from` to the position of the one at `to`, which ends
+    /// up right after it when moving down and right before it when moving up.
+    fn move_item(&mut self, from: usize, to: usize) -> usize {
+        // A sorted table would put it right back
+        if self.preferences.sort != SortMode::Manual {
+            return from;
+        }
+        if from != to {
+            tracing::info!(from, to, "reordered todo");
+            let item = self.items.remove(from);
+            self.items.insert(to, item);
+        }
+        to
+    }
+
+    /// Handles one key press, returning `false` when the app should quit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let delete_pending = self.delete_pending;
+        let running = self.dispatch_key(key);
+        // A delete waiting to be confirmed only waits for the next key
+        if delete_pending {
+            self.delete_pending = false;
+        }
+        // Any key may have changed the sort or a filter
+        self.update_selected_index();
+        self.remember_view();
+        running
+    }
+
+    fn dispatch_key(&mut self, key: KeyEvent) -> bool {
+        tracing::debug!(code = ?key.code, modifiers = ?key.modifiers, "key press");
+        // Wherever you are, as in any other terminal program
+        if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            suspend();
+            return true;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return self.interrupt();
+        }
+        self.interrupted = false;
+        if let Some(keys) = &mut self.recording {
+            keys.push(key);
+        }
+        if !self.errors.is_empty() {
+            self.handle_error_input(key.code);
+        } else if !self.conflicts.is_empty() {
+            self.handle_conflict_input(key.code);
+        } else if self.show_create {
+            match key.code {
+                KeyCode::Esc => self.show_create = false,
+                KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.jump_to_duplicate();
+                }
+                KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.open_editor = true;
+                }
+                KeyCode::Enter => {
+                    if self.input_focus == InputFocus::Name {
+                        self.input_focus = InputFocus::Description;
+                    } else {
+                        self.save_item(); // Save and close the popup
+                    }
+                }
+                _ => {
+                    self.handle_popup_input(key.code);
+                }
+            }
+        } else if self.focus_index.is_some() {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('f') => self.leave_focus(),
+                KeyCode::Char('c') => self.complete_focused(),
+                _ => {}
+            }
+        } else if self.show_new_list {
+            self.handle_new_list_input(key.code);
+        } else if self.show_quick_add {
+            self.handle_quick_add_input(key.code);
+        } else if self.show_tags {
+            self.handle_tags_input(key.code);
+        } else if self.show_replay {
+            self.handle_replay_input(key.code);
+        } else if self.show_set_field {
+            self.handle_set_field_input(key.code);
+        } else if self.show_add_note {
+            self.handle_add_note_input(key.code);
+        } else if self.show_search {
+            self.handle_search_input(key.code);
+        } else if self.show_command {
+            self.handle_command_input(key.code);
+        } else if self.preview_input.is_some() {
+            self.handle_preview_input(key.code);
+        } else if self.show_reader {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                    self.show_reader = false;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    self.reader_scroll = self.reader_scroll.saturating_add(1);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    self.reader_scroll = self.reader_scroll.saturating_sub(1);
+                }
+                KeyCode::Char('r') => self.edit_item(), // Popup opens over the reader
+                KeyCode::Char('f') => self.toggle_set_field(),
+                KeyCode::Char('a') => self.toggle_add_note(),
+                KeyCode::Char('n') => self.next_progress(),
+                KeyCode::Char('x') | KeyCode::Delete => {
+                    self.delete();
+                    // Stays open to confirm
+                    self.show_reader = self.delete_pending;
+                }
+                _ => {}
+            }
+        } else if self.show_palette {
+            return self.handle_palette_input(key.code);
+        } else if self.show_stats {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('S' | 'q') => self.show_stats = false,
+                KeyCode::Tab => self.stats_page = self.stats_page.toggle(),
+                _ => {}
+            }
+        } else if self.show_info {
+            if matches!(key.code, KeyCode::Esc | KeyCode::Char('i')) {
+                self.show_info = false;
+            }
+        } else if self.show_settings {
+            self.handle_settings_input(key.code);
+        } else {
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down if self.pane_focus == PaneFocus::Sidebar => {
+                    self.move_sidebar(1);
+                }
+                KeyCode::Char('k') | KeyCode::Up if self.pane_focus == PaneFocus::Sidebar => {
+                    self.move_sidebar(-1);
+                }
+                _ if self.pane_focus == PaneFocus::Preview && self.handle_preview_key(key.code) => {
+                }
+                KeyCode::Char(c) if self.quick_action_for(c, key.modifiers).is_some() => {
+                    if let Some(index) = self.quick_action_for(c, key.modifiers) {
+                        self.run_quick_action(index);
+                    }
+                }
+                KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(KeyModifiers::ALT) => {
+                    self.switch_list(c as usize - '1' as usize);
+                }
+                KeyCode::Char(c) if self.status_keys().contains(&c) => {
+                    let progress = match self.status_keys().iter().position(|&key| key == c) {
+                        Some(0) => Progress::Waiting,
+                        Some(1) => Progress::InProgress,
+                        _ => Progress::Done,
+                    };
+                    self.set_progress(progress);
+                }
+                KeyCode::Char(c @ '1'..='9') => {
+                    self.switch_list(c as usize - '1' as usize);
+                }
+                _ => {
+                    if let Some(action) = actions::for_key(key) {
+                        return self.run_action(action);
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Runs a table view action, from its key or the palette. Returns `false`
+    /// to quit.
+    fn run_action(&mut self, action: AppAction) -> bool {
+        let kanban = self.preferences.layout == ViewLayout::Kanban;
+        match action {
+            AppAction::Quit => return false,
+            AppAction::Next => self.next(),
+            AppAction::Previous => self.previous(),
+            AppAction::ScrollCellLeft => self.scroll_cell(-4),
+            AppAction::ScrollCellRight => self.scroll_cell(4),
+            AppAction::NextColor if !self.color_locked() => self.next_color(),
+            AppAction::PreviousColor if !self.color_locked() => self.previous_color(),
+            AppAction::NextColor | AppAction::PreviousColor => {}
+            AppAction::Delete => self.delete(),
+            AppAction::ToggleInfo => self.toggle_info(),
+            AppAction::Settings => self.toggle_settings(),
+            AppAction::OpenReader if self.picking => {
+                self.picked = self.selected_item().cloned();
+                return self.picked.is_none();
+            }
+            AppAction::OpenReader => self.open_reader(),
+            AppAction::Edit => {
+                self.edit_item(); // Call edit item logic
+            }
+            AppAction::Create => {
+                self.editing_index = None;
+                self.toggle_create(); // Toggle create popup
+            }
+            AppAction::Undo => self.undo(),
+            AppAction::RecordMacro => self.toggle_recording(),
+            AppAction::ReplayMacro => self.toggle_replay(),
+            AppAction::NextProgress => self.next_progress(),
+            AppAction::SetWaiting => self.set_progress(Progress::Waiting),
+            AppAction::SetInProgress => self.set_progress(Progress::InProgress),
+            AppAction::SetDone => self.set_progress(Progress::Done),
+            AppAction::CycleDensity => self.cycle_density(),
+            AppAction::NextList => self.cycle_list(true),
+            AppAction::PreviousList => self.cycle_list(false),
+            AppAction::NewList => self.toggle_new_list(),
+            AppAction::QuickAdd => self.toggle_quick_add(),
+            AppAction::EditTags => self.toggle_tags(),
+            AppAction::AddNote => self.toggle_add_note(),
+            AppAction::IncreasePercent => self.adjust_percent(10),
+            AppAction::DecreasePercent => self.adjust_percent(-10),
+            AppAction::PostponeDay => self.postpone(|due| due + Days::new(1)),
+            AppAction::PostponeWeek => self.postpone(|due| due + Days::new(7)),
+            AppAction::PostponeToMonday => self.postpone(|_| {
+                let today = Local::now().date_naive();
+                today + Days::new(7 - u64::from(today.weekday().num_days_from_monday()))
+            }),
+            AppAction::Focus => self.enter_focus(),
+            AppAction::PickRandom => self.pick_random(),
+            AppAction::ToggleStar => self.toggle_star(),
+            AppAction::ShowStats => self.show_stats = true,
+            AppAction::ToggleSidebar => self.toggle_sidebar(),
+            AppAction::GroupByDate => self.toggle_group_by_date(),
+            AppAction::CycleSort => self.cycle_sort(),
+            AppAction::Search => self.toggle_search(),
+            AppAction::CommandLine => self.toggle_command(),
+            AppAction::CommandPalette => self.toggle_palette(),
+            // Clearing completed todos is confirmed in the command line
+            AppAction::ClearCompleted => self.prefill_command("clear"),
+            AppAction::ExportMarkdown => self.prefill_command("export todos.md"),
+            AppAction::ExportCsv => self.prefill_command("export todos.csv"),
+            AppAction::ExportHtml => self.prefill_command("export report.html"),
+            AppAction::Import => self.prefill_command("import "),
+            AppAction::Share => self.prefill_command("share todo.json"),
+            AppAction::CycleFocus => self.cycle_focus(),
+            AppAction::CycleLayout => self.toggle_layout(),
+            AppAction::ToggleLane if kanban => self.toggle_lane(),
+            AppAction::PreviousLane if kanban => self.move_lane(false),
+            AppAction::NextLane if kanban => self.move_lane(true),
+            AppAction::ToggleLane | AppAction::PreviousLane | AppAction::NextLane => {}
+            AppAction::ShrinkColumn => self.resize_column(-2),
+            AppAction::GrowColumn => self.resize_column(2),
+            AppAction::ToggleHideCompleted => self.set_hide_completed(!self.hide_completed),
+        }
+        true
+    }
+
+    const fn color_locked(&self) -> bool {
+        self.preferences.lock_color
+    }
+
+    /// Opens the command line with `command` typed in, to confirm or finish it.
+    fn prefill_command(&mut self, command: &str) {
+        self.toggle_command();
+        self.input_command = command.to_string();
+    }
+
+    fn toggle_palette(&mut self) {
+        self.show_palette = !self.show_palette;
+        self.palette_query.clear();
+        self.palette_selected = 0;
+    }
+
+    /// Every action and list to switch to, best fuzzy matches first.
+    fn palette_entries(&self) -> Vec<PaletteEntry> {
+        let actions = actions::BINDINGS
+            .iter()
+            .filter(|binding| binding.action != AppAction::CommandPalette)
+            .map(|binding| {
+                let status_key = match binding.action {
+                    AppAction::SetWaiting => self.status_keys().first().copied(),
+                    AppAction::SetInProgress => self.status_keys().get(1).copied(),
+                    AppAction::SetDone => self.status_keys().get(2).copied(),
+                    _ => None,
+                };
+                PaletteEntry {
+                    name: binding.name.to_string(),
+                    hint: status_key.map_or_else(|| binding.hint.to_string(), String::from),
+                    target: PaletteTarget::Action(binding.action),
+                }
+            });
+        let lists = self
+            .lists
+            .iter()
+            .enumerate()
+            .map(|(index, list)| PaletteEntry {
+                name: format!("Switch to list: {}", list.name),
+                hint: if index < 9 {
+                    (index + 1).to_string()
+                } else {
+                    String::new()
+                },
+                target: PaletteTarget::List(index),
+            });
+
+        let quick_actions =
+            self.preferences
+                .quick_actions
+                .iter()
+                .enumerate()
+                .map(|(index, action)| PaletteEntry {
+                    name: format!("Quick action: {}", action.name),
+                    hint: action.key.map(String::from).unwrap_or_default(),
+                    target: PaletteTarget::QuickAction(index),
+                });
+
+        let mut entries: Vec<(i32, PaletteEntry)> = actions
+            .chain(quick_actions)
+            .chain(lists)
+            .filter_map(|entry| {
+                let score = actions::fuzzy_score(&self.palette_query, &entry.name)?;
+                Some((score, entry))
+            })
+            .collect();
+        if !self.palette_query.trim().is_empty() {
+            entries.sort_by_key(|(score, _)| Reverse(*score));
+        }
+        entries.into_iter().map(|(_, entry)| entry).collect()
+    }
+
+    fn handle_palette_input(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Esc => self.toggle_palette(),
+            KeyCode::Enter => {
+                let entry = self
+                    .palette_entries()
+                    .into_iter()
+                    .nth(self.palette_selected);
+                self.toggle_palette();
+                tracing::info!(entry = ?entry.as_ref().map(|entry| &entry.name), "palette");
+                match entry.map(|entry| entry.target) {
+                    Some(PaletteTarget::Action(action)) => return self.run_action(action),
+                    Some(PaletteTarget::List(index)) => self.switch_list(index),
+                    Some(PaletteTarget::QuickAction(index)) => self.run_quick_action(index),
+                    None => {}
+                }
+            }
+            KeyCode::Down => {
+                let count = self.palette_entries().len();
+                self.palette_selected = (self.palette_selected + 1).min(count.saturating_sub(1));
+            }
+            KeyCode::Up => self.palette_selected = self.palette_selected.saturating_sub(1),
+            KeyCode::Backspace => {
+                self.palette_query.pop();
+                self.palette_selected = 0;
+            }
+            KeyCode::Char(c) if has_room(&self.palette_query, 100) => {
+                self.palette_query.push(c);
+                self.palette_selected = 0;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Moves the selection to wherever the selected task is shown now, as
+    /// filtering, sorting or deleting moves the rows around.
+    fn update_selected_index(&mut self) {
+        let view = self.view();
+        let followed = self
+            .selected_task
+            .as_ref()
+            .and_then(|task| view.row_following(task, &self.items));
+        match (followed, view.selected()) {
+            (Some(row), _) => self.state.select(Some(row)),
+            // Hidden or gone: stay on the row, or go back to the first one
+            (None, Some(row)) => self.select_row(row),
+            // Nothing to select until a todo shows up again, maybe the same one
+            (None, None) => self.state.select(None),
+        }
+    }
+
+    /// Selects `row` of the table and starts following the task in it.
+    fn select_row(&mut self, row: usize) {
+        let view = self.view();
+        self.state.select(Some(row));
+        self.selected_task = view.follow(row, &self.items);
+    }
+    fn edit_item(&mut self) {
+        if let Some(index) = self.view().selected_index() {
+            self.editing_index = Some(index);
+            self.toggle_create(); // Open the popup
+        }
+    }
+
+    fn save_item(&mut self) {
+        if self.refuse_empty_name() || self.refuse_invalid_time() {
+            return;
+        }
+
+        if let Some(index) = self.editing_index {
+            if self.update_item(index) {
+                self.notify("Todo updated");
+            }
+        } else {
+            // Otherwise, add a new item
+            let item = self.create_item();
+            tracing::info!(name = %item.name, "added todo");
+            self.items.push(item);
+            self.notify("Todo added");
+        }
+
+        self.save_items();
+
+        // Reset the editing index and close the popup
+        self.editing_index = None;
+        self.toggle_create();
+    }
+
+    /// Applies the edit popup to `items[index]`, only touching the fields that
+    /// changed and stamping `updated` if any did. Everything the popup doesn't
+    /// show, like the creation time and custom fields, stays as it was.
+    fn update_item(&mut self, index: usize) -> bool {
+        fn set<T: PartialEq>(field: &mut T, value: T) -> bool {
+            let changed = *field != value;
+            if changed {
+                *field = value;
+            }
+            changed
+        }
+
+        let edited = self.create_item();
+        let item = &mut self.items[index];
+        let changed = set(&mut item.name, edited.name)
+            | set(&mut item.description, edited.description)
+            | set(&mut item.progress, edited.progress)
+            | set(&mut item.priority, edited.priority)
+            | set(&mut item.due, edited.due);
+        if changed {
+            item.updated = Some(timestamps::now());
+            tracing::info!(index, name = %item.name, "updated todo");
+        }
+        changed
+    }
+
+    fn open_reader(&mut self) {
+        if self.selected_item().is_some() {
+            self.show_reader = true;
+            self.reader_scroll = 0;
+        }
+    }
+
+    fn jump_to_duplicate(&mut self) {
+        let Some(index) =
+            duplicates::find_duplicate(&self.items, &self.input_name, self.editing_index)
+        else {
+            return;
+        };
+
+        // Close the popup without saving and select the existing task instead
+        self.toggle_create();
+
+        self.select_item(index);
+    }
+
+    /// Index into `items` of the selected row, if any.
+    fn selected_index(&self) -> Option<usize> {
+        self.view().selected_index()
+    }
+
+    /// Selects the row showing `items[index]`, if it's visible.
+    fn select_item(&mut self, index: usize) {
+        if let Some(row) = self.view().row_of(index) {
+            self.select_row(row);
+        }
+    }
+
+    /// The kanban swimlanes as (title, indices into `items`), in table order.
+    /// Tasks without a matching tag share a last lane.
+    fn kanban_lanes(&self) -> Vec<(String, Vec<usize>)> {
+        let mut lanes: Vec<(String, Vec<usize>)> = Vec::new();
+        let mut rest = Vec::new();
+        for index in self.filtered_indices() {
+            let tags = self.items[index].tags();
+            let lane = match &self.preferences.swimlanes {
+                Swimlanes::Off => None,
+                Swimlanes::Tags => tags.into_iter().next(),
+                Swimlanes::Prefix(prefix) => tags.into_iter().find(|tag| tag.starts_with(prefix)),
+            };
+            match lane {
+                Some(tag) => match lanes
+                    .iter_mut()
+                    .find(|(title, _)| *title == format!("#{tag}"))
+                {
+                    Some((_, indices)) => indices.push(index),
+                    None => lanes.push((format!("#{tag}"), vec![index])),
+                },
+                None => rest.push(index),
+            }
+        }
+
+        lanes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        if !rest.is_empty() || lanes.is_empty() {
+            let title = if lanes.is_empty() { "All" } else { "Other" };
+            lanes.push((title.to_string(), rest));
+        }
+        lanes
+    }
+
+    /// The swimlane holding the selected task.
+    fn selected_lane(&self, lanes: &[(String, Vec<usize>)]) -> usize {
+        self.selected_index()
+            .and_then(|selected| {
+                lanes
+                    .iter()
+                    .position(|(_, indices)| indices.contains(&selected))
+            })
+            .unwrap_or(0)
+    }
+
+    fn toggle_lane(&mut self) {
+        let lanes = self.kanban_lanes();
+        let title = &lanes[self.selected_lane(&lanes)].0;
+        if let Some(position) = self.collapsed_lanes.iter().position(|lane| lane == title) {
+            self.collapsed_lanes.remove(position);
+        } else {
+            self.collapsed_lanes.push(title.clone());
+        }
+    }
+
+    /// Selects the first task of the next (or previous) swimlane.
+    fn move_lane(&mut self, forward: bool) {
+        let lanes = self.kanban_lanes();
+        let current = self.selected_lane(&lanes);
+        let next = if forward {
+            (current + 1).min(lanes.len() - 1)
+        } else {
+            current.saturating_sub(1)
+        };
+        if let Some(&index) = lanes[next].1.first() {
+            self.select_item(index);
+        }
+    }
+
+    fn toggle_star(&mut self) {
+        let Some(index) = self.selected_index() else {
+            return;
+        };
+        self.items[index].starred = !self.items[index].starred;
+
+        self.save_items();
+
+        // The task moves to or from the top, so keep it selected
+        self.select_item(index);
+    }
+
+    /// Moves the selected todo's due date to `postpone` of it, keeping the time
+    /// of day. Todos without one or already overdue count from today, so a
+    /// day later is tomorrow.
+    fn postpone(&mut self, postpone: impl FnOnce(NaiveDate) -> NaiveDate) {
+        let Some(index) = self.selected_index() else {
+            return;
+        };
+        let today = Local::now().date_naive();
+        let workdays = Workdays::new(&self.preferences);
+        let mut due = today;
+        self.edit_selected(|item| {
+            due = postpone(item.due_date().map_or(today, |date| date.max(today)));
+            due = workdays.on_or_after(due);
+            item.due = Some(format_due(due, item.due_time()));
+            true
+        });
+
+        // The todo may move in the due and urgency orders, so keep it selected
+        self.select_item(index);
+        self.notify(format!("Postponed to {}", due.format("%a %Y-%m-%d")));
+    }
+
+    /// Moves the selected task's completion by `step` percent. Going below zero
+    /// removes the gauge again.
+    fn adjust_percent(&mut self, step: i16) {
+        let Some(index) = self.selected_index() else {
+            return;
+        };
+        let item = &mut self.items[index];
+        item.percent = match item.percent {
+            Some(0) if step < 0 => None,
+            percent => {
+                let percent = (i16::from(percent.unwrap_or(0)) + step).clamp(0, 100);
+                u8::try_from(percent).ok()
+            }
+        };
+        self.save_items();
+    }
+
+    /// Selects a random open task from the current view.
+    fn pick_random(&mut self) {
+        let view = self.view();
+        let candidates: Vec<(usize, u64)> = (0..view.len())
+            .filter_map(|row| Some((row, &self.items[view.task_at(row)?])))
+            .filter(|(_, item)| item.progress != Progress::Done)
+            .map(|(position, item)| {
+                let weight = match self.preferences.random_pick {
+                    RandomPick::Uniform => 1,
+                    RandomPick::Age => {
+                        1 + item.age_days().map_or(0, |age| age.max(0).unsigned_abs())
+                    }
+                };
+                (position, weight)
+            })
+            .collect();
+
+        let total: u64 = candidates.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            self.warn("No open todos to pick from");
+            return;
+        }
+
+        let mut roll = fastrand::u64(0..total);
+        for (position, weight) in candidates {
+            if roll < weight {
+                self.select_row(position);
+                self.cell_scroll = 0;
+                self.preview_scroll = 0;
+                return;
+            }
+            roll -= weight;
+        }
+    }
+
+    fn enter_focus(&mut self) {
+        // Prefer the selected task, otherwise the first one in progress
+        let index = self
+            .selected_index()
+            .filter(|&index| self.items[index].progress == Progress::InProgress)
+            .or_else(|| {
+                self.items
+                    .iter()
+                    .position(|item| item.progress == Progress::InProgress)
+            });
+
+        if index.is_some() {
+            self.focus_index = index;
+            self.focus_started = Instant::now();
+        }
+    }
+
+    /// Logs the time spent on the focused todo, if it's more than a minute.
+    fn end_focus_session(&mut self) {
+        let Some(index) = self.focus_index else {
+            return;
+        };
+        let seconds = self.focus_started.elapsed().as_secs();
+        if seconds < 60 {
+            return;
+        }
+        let item = &self.items[index];
+        tracing::info!(name = %item.name, seconds, "focus session");
+        self.time_log.record(Session {
+            task: item.created.clone(),
+            name: item.name.clone(),
+            day: Local::now().date_naive().format(DUE_FORMAT).to_string(),
+            seconds,
+        });
+        let path = TimeLog::path(&self.lists[0].path);
+        if let Err(e) = self.time_log.save(&path) {
+            tracing::warn!(path = %path.display(), "couldn't save the time log: {e}");
+        }
+    }
+
+    fn leave_focus(&mut self) {
+        self.end_focus_session();
+        if let Some(index) = self.focus_index.take() {
+            self.select_item(index);
+        }
+    }
+
+    fn complete_focused(&mut self) {
+        let Some(index) = self.focus_index else {
+            return;
+        };
+        self.end_focus_session();
+        self.items[index].progress = Progress::Done;
+
+        // Pull the next task in progress, or start the next waiting one
+        let next = self
+            .items
+            .iter()
+            .position(|item| item.progress == Progress::InProgress)
+            .or_else(|| {
+                let next = self
+                    .items
+                    .iter()
+                    .position(|item| item.progress == Progress::Waiting)?;
+                self.items[next].progress = Progress::InProgress;
+                Some(next)
+            });
+
+        self.save_items();
+        self.notify("Todo done");
+
+        if next.is_some() {
+            self.focus_index = next;
+            self.focus_started = Instant::now();
+        } else {
+            self.leave_focus();
+        }
+    }
+
+    /// The quick action bound to `c`, ignoring it with Ctrl or Alt held.
+    fn quick_action_for(&self, c: char, modifiers: KeyModifiers) -> Option<usize> {
+        if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+            return None;
+        }
+        self.preferences
+            .quick_actions
+            .iter()
+            .position(|action| action.key == Some(c))
+    }
+
+    /// Applies every step of a quick action to the selected todo in one save,
+    /// so a single undo takes all of it back.
+    fn run_quick_action(&mut self, index: usize) {
+        let action = &self.preferences.quick_actions[index];
+        let steps = match quick_actions::parse_steps(&action.steps) {
+            Ok(steps) => steps,
+            Err(e) => {
+                let message = format!("Quick action \"{}\" is invalid: {e}", action.name);
+                self.show_error(message, None);
+                return;
+            }
+        };
+        let Some(selected) = self.view().selected_index() else {
+            return;
+        };
+        tracing::info!(name = %action.name, steps = steps.len(), "quick action");
+        let message = format!("{} applied", action.name);
+        let today = Local::now().date_naive();
+        let workdays = Workdays::new(&self.preferences);
+        for step in &steps {
+            step.apply(&mut self.items[selected], today, &workdays);
+        }
+        self.save_items();
+        self.longest_item_lens = constraint_len_calculator(&self.items);
+        self.update_selected_index();
+        self.notify(message);
+    }
+
+    /// The digits that set Waiting, In Progress and Done, in that order.
+    fn status_keys(&self) -> Vec<char> {
+        self.preferences
+            .status_keys
+            .chars()
+            .filter(char::is_ascii_digit)
+            .take(3)
+            .collect()
+    }
+
+    fn set_progress(&mut self, progress: Progress) {
+        if let Some(index) = self.view().selected_index() {
+            self.items[index].progress = progress;
+            self.save_items();
+        }
+    }
+
+    /// Applies `edit` to the selected todo, stamping `updated` and saving if
+    /// it returns that it changed something.
+    fn edit_selected(&mut self, edit: impl FnOnce(&mut Data) -> bool) {
+        let Some(index) = self.selected_index() else {
+            return;
+        };
+        let item = &mut self.items[index];
+        if edit(item) {
+            item.updated = Some(timestamps::now());
+            tracing::info!(name = %item.name, "edited todo");
+            self.save_items();
+        }
+    }
+
+    /// Keys of the focused preview pane: `↑` / `↓` pick a field, `←` / `→`
+    /// cycle the status and priority or move the due date a day, and `Enter`
+    /// cycles or starts typing into the field. Returns whether it took the key.
+    fn handle_preview_key(&mut self, key: KeyCode) -> bool {
+        let fields = PreviewField::ALL;
+        let position = fields
+            .iter()
+            .position(|&field| field == self.preview_field)
+            .unwrap_or(0);
+        let forward = key != KeyCode::Left;
+        match (key, self.preview_field) {
+            (KeyCode::Char('j') | KeyCode::Down, _) => {
+                self.preview_field = fields[(position + 1).min(fields.len() - 1)];
+            }
+            (KeyCode::Char('k') | KeyCode::Up, _) => {
+                self.preview_field = fields[position.saturating_sub(1)];
+            }
+            (KeyCode::PageDown, _) => self.preview_scroll = self.preview_scroll.saturating_add(5),
+            (KeyCode::PageUp, _) => self.preview_scroll = self.preview_scroll.saturating_sub(5),
+            (KeyCode::Left | KeyCode::Right | KeyCode::Enter, PreviewField::Status) => {
+                self.edit_selected(|item| {
+                    item.progress = item.progress.cycle(forward);
+                    true
+                });
+            }
+            (KeyCode::Left | KeyCode::Right | KeyCode::Enter, PreviewField::Priority) => {
+                self.edit_selected(|item| {
+                    item.priority = Priority::cycle(item.priority, forward);
+                    true
+                });
+            }
+            (KeyCode::Left | KeyCode::Right, PreviewField::Due) => {
+                let days = if forward { 1 } else { -1 };
+                self.edit_selected(|item| {
+                    let due = item.due_date().unwrap_or_else(|| Local::now().date_naive());
+                    let due = due + chrono::Duration::days(days);
+                    item.due = Some(format_due(due, item.due_time()));
+                    true
+                });
+            }
+            (KeyCode::Backspace | KeyCode::Delete, PreviewField::Priority) => {
+                self.edit_selected(|item| item.priority.take().is_some());
+            }
+            (KeyCode::Backspace | KeyCode::Delete, PreviewField::Due) => {
+                self.edit_selected(|item| item.due.take().is_some());
+            }
+            (KeyCode::Backspace | KeyCode::Delete, PreviewField::Remind) => {
+                self.edit_selected(|item| !mem::take(&mut item.remind).is_empty());
+            }
+            (KeyCode::Enter, field) if field.is_text() => {
+                let Some(item) = self.selected_item() else {
+                    return true;
+                };
+                self.preview_input = Some(match field {
+                    PreviewField::Due => item.due.clone().unwrap_or_default(),
+                    PreviewField::Remind => item.remind.join(", "),
+                    PreviewField::Tags => item
+                        .tags()
+                        .iter()
+                        .map(|tag| format!("#{tag}"))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    _ => item.description.clone(),
+                });
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Typing into the preview pane's due date, tags or description: `Enter`
+    /// saves it and `Esc` leaves the todo as it was.
+    fn handle_preview_input(&mut self, key: KeyCode) {
+        let Some(input) = &mut self.preview_input else {
+            return;
+        };
+        match key {
+            KeyCode::Esc => self.preview_input = None,
+            KeyCode::Enter => {
+                let text = input.trim().to_string();
+                match self.preview_field {
+                    PreviewField::Due if !text.is_empty() => {
+                        if parse_due(&text).is_none() {
+                            self.warn(format!(
+                                "\"{text}\" isn't a date, use YYYY-MM-DD with an optional HH:MM"
+                            ));
+                            return;
+                        }
+                        self.edit_selected(|item| item.due.replace(text.clone()) != Some(text));
+                    }
+                    PreviewField::Due => self.edit_selected(|item| item.due.take().is_some()),
+                    PreviewField::Remind => {
+                        let now = Local::now().naive_local();
+                        let mut remind = Vec::new();
+                        for part in text.split(',').filter(|part| !part.trim().is_empty()) {
+                            let Some(at) = reminders::parse_time(part, now) else {
+                                self.warn(format!(
+                                    "\"{}\" isn't a time, e.g. fri 9am, tomorrow 14:30 or in 2h",
+                                    part.trim()
+                                ));
+                                return;
+                            };
+                            remind.push(at.format(reminders::REMIND_FORMAT).to_string());
+                        }
+                        remind.sort();
+                        remind.dedup();
+                        self.edit_selected(|item| {
+                            mem::replace(&mut item.remind, remind.clone()) != remind
+                        });
+                    }
+                    PreviewField::Tags => {
+                        let mut tags: Vec<String> = text
+                            .split(|c: char| c.is_whitespace() || c == ',')
+                            .map(|tag| tag.trim_start_matches('#').to_lowercase())
+                            .filter(|tag| !tag.is_empty())
+                            .collect();
+                        tags.sort();
+                        tags.dedup();
+                        self.edit_selected(|item| {
+                            let changed = item.tags() != tags;
+                            item.set_tags(&tags);
+                            changed
+                        });
+                    }
+                    _ => {
+                        let text = input.clone();
+                        self.edit_selected(|item| {
+                            std::mem::replace(&mut item.description, text.clone()) != text
+                        });
+                    }
+                }
+                self.preview_input = None;
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if has_room(input, DESCRIPTION_LIMIT) => input.push(c),
+            _ => {}
+        }
+    }
+
+    fn next_progress(&mut self) {
+        if let Some(index) = self.view().selected_index() {
+            let item = &mut self.items[index];
+            item.progress = match item.progress {
+                Progress::InProgress => Progress::Waiting,
+                Progress::Waiting => Progress::Done,
+                Progress::Done => Progress::InProgress,
+            };
+
+            self.save_items();
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let area = frame.area();
+        // The tab bar only shows up once there is more than one list
+        let tabs_height = u16::from(self.lists.len() > 1);
+        let header_height = u16::from(self.preferences.show_clock);
+        let vertical = Layout::vertical([
+            Constraint::Length(header_height),
+            Constraint::Length(tabs_height),
+            Constraint::Min(5),
+            Constraint::Length(3),
+        ]);
+        let [header_area, tabs_area, main_area, footer_area] = vertical.areas(area);
+        let rects = [main_area, footer_area];
+
+        self.set_colors();
+        if self.preferences.show_clock {
+            self.render_header(frame, header_area);
+        }
+        self.render_tabs(frame, tabs_area);
+        let content_area = if self.preferences.show_sidebar {
+            let [sidebar_area, content_area] =
+                Layout::horizontal([Constraint::Length(24), Constraint::Fill(1)]).areas(rects[0]);
+            self.render_sidebar(frame, sidebar_area);
+            content_area
+        } else {
+            rects[0]
+        };
+        match self.preferences.layout {
+            ViewLayout::Table => {
+                self.render_table(frame, content_area);
+                self.render_scrollbar(frame, content_area);
+            }
+            ViewLayout::Split => {
+                let ratio = self.preferences.split_ratio.clamp(20, 80);
+                let [table_area, preview_area] =
+                    Layout::horizontal([Constraint::Percentage(ratio), Constraint::Fill(1)])
+                        .areas(content_area);
+                self.render_preview(frame, preview_area);
+                self.render_table(frame, table_area);
+                self.render_scrollbar(frame, table_area);
+            }
+            ViewLayout::Kanban => self.render_kanban(frame, content_area),
+        }
+        self.render_footer(frame, rects[1]);
+
+        // Rendering the full-screen task reader
+        if self.show_reader {
+            let selected_item = self.selected_item().cloned();
+
+            if let Some(item) = selected_item {
+                self.reader_scroll = self.reader_scroll.min(TaskReader::max_scroll(&item, area));
+                let reader = TaskReader {
+                    item: &item,
+                    logged: self.time_log.seconds_for(&item.created),
+                    clock: self.clock,
+                    scroll: self.reader_scroll,
+                    blockers: dependencies::blockers(&item, &self.items),
+                    style: Style::default().fg(Color::White),
+                };
+                reader.render(area, frame.buffer_mut(), self.colors.selected_style_fg);
+            } else {
+                self.show_reader = false;
+            }
+        }
+
+        // Rendering the creation popup
+        if self.show_create {
+            let duplicate =
+                duplicates::find_duplicate(&self.items, &self.input_name, self.editing_index)
+                    .map(|index| &self.items[index]);
+            let create = create_popup::CreatePopup {
+                name: self.input_name.clone(),
+                description: self.input_description.clone(),
+                duplicate_of: duplicate.map(|item| item.name.clone()),
+                duplicate_created: duplicate
+                    .filter(|item| duplicates::is_exact(&item.name, &self.input_name))
+                    .map(|item| self.clock.display(&item.created)),
+                name_missing: self.name_missing && self.input_name.trim().is_empty(),
+                progress: self.input_progress.clone(),
+                priority: self.input_priority,
+                due: DatePicker {
+                    date: self.input_due,
+                    time: self.input_due_time.clone(),
+                    cursor: self.due_cursor,
+                    error: if self.due_time_invalid {
+                        Some(format!(
+                            "\"{}\" isn't a time, type it as HH:MM",
+                            self.input_due_time
+                        ))
+                    } else {
+                        self.invalid_due
+                            .as_ref()
+                            .filter(|_| self.input_due.is_none())
+                            .map(|due| format!("\"{due}\" isn't a valid date, pick a new one"))
+                    },
+                    week_start: self.preferences.week_start,
+                    style: Style::default().fg(Color::White),
+                },
+                style: Style::default().fg(Color::White),
+            };
+            create.render(
+                popup_area(area, area.width / 2, area.height),
+                frame.buffer_mut(),
+                self.input_focus,
+                self.colors.selected_style_fg,
+            );
+        }
+
+        // Rendering focus mode over everything else
+        if let Some(index) = self.focus_index {
+            let focus = FocusView {
+                item: &self.items[index],
+                elapsed: self.focus_started.elapsed(),
+                style: Style::default().fg(Color::White),
+            };
+            focus.render(area, frame.buffer_mut(), self.colors.selected_style_fg);
+        }
+
+        // Rendering the search popup
+        if self.show_search {
+            let hits = self
+                .search_results()
+                .into_iter()
+                .map(|(list, index)| {
+                    let item = self.item_in_list(list, index);
+                    SearchHit {
+                        list: self.lists[list].name.clone(),
+                        name: item.name.clone(),
+                        description: item.description.clone(),
+                    }
+                })
+                .collect();
+            let search = SearchPopup {
+                query: self.search_query.clone(),
+                error: Query::parse(&self.search_query).err(),
+                hits,
+                selected: self.search_selected,
+                style: Style::default().fg(Color::White),
+            };
+            search.render(
+                popup_area(area, area.width * 3 / 4, area.height * 3 / 4),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
+        }
+
+        // Rendering the command line
+        if self.show_command {
+            let (error, preview) = self.command_preview();
+            let command = CommandPopup {
+                command: self.input_command.clone(),
+                error,
+                preview,
+                style: Style::default().fg(Color::White),
+            };
+            command.render(
+                popup_area(area, area.width * 3 / 4, area.height * 3 / 4),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
+        }
+
+        // Rendering the quick add prompt
+        if self.show_quick_add {
+            let prompt = PromptPopup {
+                title: "Quick add (Enter) save | (Esc) close".to_string(),
+                value: self.input_quick_add.clone(),
+                style: Style::default().fg(Color::White),
+            };
+            prompt.render(
+                popup_area(area, area.width / 2, 3),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
+        }
+
+        // Rendering the tag popup
+        if self.show_tags {
+            let name = self.selected_item().map_or("", |item| item.name.as_str());
+            let popup = TagPopup {
+                name,
+                tags: &self.tag_choices,
+                selected: self.tag_selected,
+                input: &self.input_tag,
+                icons: &self.icons,
+                style: Style::default().fg(Color::White),
+            };
+            popup.render(
+                popup_area(area, area.width / 2, area.height * 3 / 4),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
         }
 
-        if let Err(e) = save_json(&self.items) {
-            eprintln!("Error saving JSON: {e}");
+        // Rendering the new list prompt
+        if self.show_new_list {
+            let prompt = PromptPopup {
+                title: "New list".to_string(),
+                value: self.input_list_name.clone(),
+                style: Style::default().fg(Color::White),
+            };
+            prompt.render(
+                popup_area(area, area.width / 2, 3),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
         }
 
-        // Reset the editing index and close the popup
-        self.editing_index = None;
-        self.toggle_create();
-    }
-
-    fn next_progress(&mut self) {
-        if let Some(selected) = self.state.selected() {
-            // Create a filtered list based on hide_completed flag
-            let filtered_items: Vec<&Data> = self.get_filtered_items();
-
-            // Ensure the selected index is valid for the filtered items
-            if selected < filtered_items.len() {
-                // Get the selected item from the filtered list
-                let selected_item = filtered_items[selected];
-
-                // Find the index in the original items to compare
-                if let Some(original_index) = self
-                    .items
-                    .iter()
-                    .position(|item| App::<'_>::item_matches(item, selected_item))
-                {
-                    // Update the progress of the original item
-                    let item = &mut self.items[original_index];
-                    item.progress = match item.progress {
-                        Progress::InProgress => Progress::Waiting,
-                        Progress::Waiting => Progress::Done,
-                        Progress::Done => Progress::InProgress,
-                    };
+        // Rendering the macro replay prompt
+        if self.show_replay {
+            let prompt = PromptPopup {
+                title: format!(
+                    "Replay the macro of {} keys how many times? (Enter for once)",
+                    self.macro_keys.len()
+                ),
+                value: self.input_replay_count.clone(),
+                style: Style::default().fg(Color::White),
+            };
+            prompt.render(
+                popup_area(area, area.width * 3 / 4, 3),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
+        }
 
-                    if let Err(e) = save_json(&self.items) {
-                        eprintln!("Error saving JSON: {e}");
-                    }
-                }
-            }
+        // Rendering the field prompt, over the reader
+        if self.show_set_field {
+            let prompt = PromptPopup {
+                title: "Set a field as key: value, with no value to remove it".to_string(),
+                value: self.input_field.clone(),
+                style: Style::default().fg(Color::White),
+            };
+            prompt.render(
+                popup_area(area, area.width * 3 / 4, 3),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
         }
-    }
 
-    fn draw(&mut self, frame: &mut Frame) {
-        let area = frame.area();
-        let vertical = Layout::vertical([Constraint::Min(5), Constraint::Length(3)]);
-        let rects = vertical.split(frame.size());
+        // Rendering the note prompt, over the reader or the table
+        if self.show_add_note {
+            let prompt = PromptPopup {
+                title: "Add a note, (Enter) logs it with the time".to_string(),
+                value: self.input_note.clone(),
+                style: Style::default().fg(Color::White),
+            };
+            prompt.render(
+                popup_area(area, area.width * 3 / 4, 3),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
+        }
 
-        self.set_colors();
-        self.render_table(frame, rects[0]);
-        self.render_scrollbar(frame, rects[0]);
-        self.render_footer(frame, rects[1]);
+        // Rendering the completion stats
+        if self.show_stats {
+            let stats = StatsPopup {
+                completions: &self.completions,
+                time_log: &self.time_log,
+                page: self.stats_page,
+                today: Local::now().date_naive(),
+                goal: self.preferences.daily_goal,
+                week_start: self.preferences.week_start,
+                style: Style::default().fg(Color::White),
+            };
+            stats.render(
+                popup_area(area, area.width.min(64), area.height.min(24)),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
+        }
 
-        // Rendering the creation popup
-        if self.show_create {
-            let create = create_popup::CreatePopup {
-                name: self.input_name.clone(),
-                description: self.input_description.clone(),
+        // Rendering the command palette
+        if self.show_palette {
+            let palette = PalettePopup {
+                query: self.palette_query.clone(),
+                entries: self
+                    .palette_entries()
+                    .into_iter()
+                    .map(|entry| (entry.name, entry.hint))
+                    .collect(),
+                selected: self.palette_selected,
                 style: Style::default().fg(Color::White),
             };
-            create.render(
-                popup_area(area, area.width / 2, area.height),
+            palette.render(
+                popup_area(area, area.width / 2, area.height * 3 / 4),
                 frame.buffer_mut(),
-                self.input_focus,
                 self.colors.selected_style_fg,
             );
         }
@@ -646,40 +4216,200 @@ impl App<'_> {
                 self.colors.selected_style_fg,
             );
         }
+
+        // Rendering the settings screen
+        if self.show_settings {
+            let settings = SettingsPopup {
+                entries: Setting::ALL
+                    .iter()
+                    .map(|&setting| {
+                        let value = match self.setting(setting) {
+                            Value::Toggle(on) => self.icons.checkbox(on).to_string(),
+                            Value::Choice(choice) => choice,
+                            Value::Text(text) if text.is_empty() => "Default".to_string(),
+                            Value::Text(text) => text,
+                        };
+                        (setting, value)
+                    })
+                    .collect(),
+                selected: self.settings_selected,
+                editing: self.settings_input.clone(),
+                style: Style::default().fg(Color::White),
+            };
+            settings.render(
+                popup_area(area, area.width.min(64), area.height.min(26)),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
+        }
+
+        // Rendering sync conflicts over everything else
+        if let Some(conflict) = self.conflicts.first() {
+            let popup = ConflictPopup {
+                conflict,
+                remaining: self.conflicts.len(),
+                selected: self.conflict_field,
+                style: Style::default().fg(Color::White),
+            };
+            popup.render(
+                popup_area(area, area.width * 3 / 4, 8),
+                frame.buffer_mut(),
+                self.colors.selected_style_fg,
+            );
+        }
+
+        // Rendering notifications above the footer
+        let toasts = ToastStack {
+            toasts: &self.toasts,
+            labelled: self.no_color,
+            style: Style::default().fg(Color::White),
+        };
+        toasts.render(rects[0], frame.buffer_mut());
+
+        // Rendering errors over everything else
+        if let Some(error) = self.errors.first() {
+            let popup = ErrorPopup {
+                message: &error.message,
+                can_retry: error.retry.is_some(),
+                remaining: self.errors.len(),
+                style: Style::default().fg(Color::White),
+            };
+            popup.render(popup_area(area, area.width / 2, 7), frame.buffer_mut());
+        }
+
+        if self.no_color {
+            strip_colors(frame.buffer_mut());
+        }
+        if self.preferences.ascii_only {
+            ascii::asciify(frame.buffer_mut());
+        }
     }
 
     fn render_table(&mut self, frame: &mut Frame, area: Rect) {
-        let header_style = Style::default()
+        let mut header_style = Style::default()
             .fg(self.colors.header_fg)
             .bg(self.colors.header_bg);
-        let selected_style = Style::default()
+        if self.no_color {
+            header_style = header_style.add_modifier(Modifier::BOLD);
+        }
+        let mut selected_style = Style::default()
             .add_modifier(Modifier::REVERSED)
             .fg(self.colors.selected_style_fg);
+        if self.colors.bold_selection {
+            selected_style = selected_style.add_modifier(Modifier::BOLD);
+        }
+        // Confirms a todo was just marked done, struck through for when
+        // colors are off
+        let flash_style = Style::new()
+            .fg(Color::Black)
+            .bg(Color::Green)
+            .add_modifier(Modifier::CROSSED_OUT);
+        if self
+            .selected_item()
+            .is_some_and(|item| self.is_flashing(item))
+        {
+            selected_style = flash_style.add_modifier(Modifier::BOLD);
+        }
 
-        let header = ["Name", "Description", "Progress", "Created"]
+        let focused_header = match self.column_focus {
+            ColumnFocus::Name => 1,
+            ColumnFocus::Description => 2,
+        };
+        // The gauge column only shows up once a task in the list has a percentage
+        let show_percent = self.items.iter().any(|item| item.percent.is_some());
+        let show_age = self.preferences.show_age;
+        let strike_done = self.preferences.strike_done;
+        let mut titles = vec!["", "Name", "Description", "Progress"];
+        if show_percent {
+            titles.push("Done");
+        }
+        if show_age {
+            titles.push("Age");
+        }
+        let field_columns = self.preferences.field_columns.clone();
+        titles.extend(field_columns.iter().map(String::as_str));
+        titles.push("Created");
+        let now = Utc::now();
+        let today = self.clock.date(now);
+        let header = titles
             .into_iter()
-            .map(Cell::from)
+            .enumerate()
+            .map(|(i, title)| {
+                if i == focused_header {
+                    Cell::from(title).add_modifier(Modifier::UNDERLINED)
+                } else {
+                    Cell::from(title)
+                }
+            })
             .collect::<Row>()
             .style(header_style)
             .height(1);
 
-        // Filter items based on hide_completed flag
-        let filtered_items: Vec<&Data> = if self.hide_completed {
-            self.items
-                .iter()
-                .filter(|item| item.progress != Progress::Done)
-                .collect()
-        } else {
-            self.items.iter().collect()
-        };
+        self.update_selected_index();
+        let view = self.view();
+        if view.len() == 0 {
+            self.render_empty(frame, area);
+            self.table_area = area;
+            return;
+        }
 
-        if let Some(selected_index) = self.state.selected() {
-            if selected_index >= filtered_items.len() {
-                self.state.select(Some(0));
-            }
+        let widths = self.preferences.column_widths;
+        let mut constraints = vec![
+            Constraint::Length(self.icons.column_width()),
+            Constraint::Length(widths.name),
+            Constraint::Length(widths.description),
+            Constraint::Min(self.longest_item_lens.2),
+        ];
+        if show_percent {
+            constraints.push(Constraint::Length(10)); // Five gauge glyphs and the number
+        }
+        if show_age {
+            constraints.push(Constraint::Length(4));
+        }
+        for key in &field_columns {
+            // As wide as the longest value, within reason
+            let longest = self
+                .items
+                .iter()
+                .filter_map(|item| item.fields.get(key))
+                .map(|value| value.width())
+                .chain([key.width()])
+                .max()
+                .unwrap_or(0);
+            constraints.push(Constraint::Length(
+                u16::try_from(longest.min(20)).unwrap_or(20),
+            ));
         }
+        constraints.push(Constraint::Min(self.longest_item_lens.3 + 5)); // Room for the age badge
+        let highlight_symbol = self.density().highlight_symbol(self.preferences.ascii_only);
+        let selection_width = u16::try_from(highlight_symbol.width()).unwrap_or(0);
+        // The table squeezes the columns to fit, so wrap to the widths they end up with
+        let columns = Layout::horizontal(constraints.clone())
+            .spacing(1)
+            .split(Rect::new(
+                0,
+                0,
+                area.width.saturating_sub(selection_width),
+                1,
+            ));
+        let (name_width, description_width) = (columns[1].width, columns[2].width);
 
-        let rows = filtered_items.iter().enumerate().map(|(i, data)| {
+        let row_height = self.row_height();
+        let selected = view.selected();
+        let stale_days = self.preferences.stale_days;
+        let rows = view.rows().iter().enumerate().map(|(i, &entry)| {
+            let index = match entry {
+                Entry::Task(index) => index,
+                Entry::Header(group) => {
+                    return Row::new([Cell::default(), Cell::from(group.title())]).style(
+                        Style::new()
+                            .fg(self.colors.selected_style_fg)
+                            .bg(self.colors.buffer_bg)
+                            .add_modifier(Modifier::BOLD),
+                    );
+                }
+            };
+            let data = &self.items[index];
             let color = if i % 2 == 0 {
                 self.colors.normal_row_color
             } else {
@@ -689,44 +4419,276 @@ impl App<'_> {
             let progress_text = progress_display.1; // Extract the text
             let progress_color = progress_display.0; // Extract the color
 
+            // Waiting on an open todo shows as its own status until that's done
+            let blocked = dependencies::is_blocked(data, &self.items);
+            let (status_color, status_text) = if blocked {
+                (Color::Red, "Blocked".to_string())
+            } else {
+                (progress_color, progress_text)
+            };
+
+            // The focused cell of the selected row can be scrolled horizontally
+            let (name_offset, description_offset) = match self.column_focus {
+                _ if selected != Some(i) => (0, 0),
+                ColumnFocus::Name => (self.cell_scroll, 0),
+                ColumnFocus::Description => (0, self.cell_scroll),
+            };
+
             // Wrap both name and information if they exceed the specified lengths
-            let wrapped_name = wrap_text(&data.name, 22);
-            let wrapped_description = wrap_text(&data.description, 42);
+            let star = if data.starred {
+                format!("{} ", self.icons.star)
+            } else {
+                String::new()
+            };
+            let (priority_color, priority) = data
+                .priority
+                .map_or((Color::Reset, String::new()), |priority| {
+                    (priority.display().0, format!("{} ", priority.marker()))
+                });
+            // Today's todos show the time they're due at
+            let due_time = data
+                .due_time()
+                .filter(|_| data.due_date() == Some(today))
+                .map(|time| format!("{} ", time.format(DUE_TIME_FORMAT)))
+                .unwrap_or_default();
+            let badge = if blocked { "Blocked " } else { "" };
+            let wrapped_name = wrap_text(
+                &scrolled_text(&data.name, name_offset),
+                usize::from(name_width)
+                    .saturating_sub(
+                        star.width() + priority.width() + due_time.width() + badge.width(),
+                    )
+                    .max(1),
+                row_height,
+            );
+            let mut name_text = Text::from(wrapped_name);
+            // Set apart even with the Status column scrolled off or narrow
+            if strike_done && data.progress == Progress::Done {
+                name_text = name_text
+                    .style(Style::new().add_modifier(Modifier::DIM | Modifier::CROSSED_OUT));
+            }
+            if let Some(first_line) = name_text.lines.first_mut() {
+                first_line.spans.splice(
+                    0..0,
+                    [
+                        Span::styled(star, Style::new().fg(Color::Yellow)),
+                        Span::styled(priority, Style::new().fg(priority_color).bold()),
+                        Span::styled(due_time, Style::new().fg(Color::Cyan)),
+                        Span::styled(badge, Style::new().fg(Color::Red).bold()),
+                    ],
+                );
+            }
+            let wrapped_description = wrap_text(
+                &scrolled_text(&data.description, description_offset),
+                usize::from(description_width).max(1),
+                row_height,
+            );
 
-            Row::new(vec![
-                Cell::from(Text::from(wrapped_name)),
+            let mut cells = vec![
+                Cell::from(
+                    Text::from(self.icons.progress(&data.progress))
+                        .style(Style::new().fg(progress_color)),
+                ),
+                Cell::from(name_text),
                 Cell::from(Text::from(wrapped_description)),
-                Cell::from(Text::from(progress_text).style(Style::new().fg(progress_color))),
-                Cell::from(Text::from(data.created.clone())),
-            ])
-            .style(Style::new().fg(self.colors.row_fg).bg(color))
-            .height(u16::try_from(ITEM_HEIGHT).expect("REASON"))
+                Cell::from(Text::from(status_text).style(Style::new().fg(status_color))),
+            ];
+            if show_percent {
+                cells.push(data.percent.map_or_else(Cell::default, |percent| {
+                    let gauge = format!("{} {percent:>3}%", self.icons.gauge(percent, 5));
+                    let style = if percent == 100 {
+                        Style::new().fg(Color::Green)
+                    } else {
+                        Style::new()
+                    };
+                    Cell::from(Text::from(gauge).style(style))
+                }));
+            }
+            if show_age {
+                let age = timestamps::parse(&data.created)
+                    .map(|created| timestamps::short_age(created, now));
+                cells.push(Cell::from(age.unwrap_or_default()));
+            }
+            for key in &field_columns {
+                cells.push(Cell::from(
+                    data.fields.get(key).cloned().unwrap_or_default(),
+                ));
+            }
+            cells.push(created_cell(
+                data,
+                stale_days,
+                self.clock,
+                self.preferences.relative_dates,
+                self.no_color,
+            ));
+
+            let mut style = Style::new()
+                .fg(self.colors.row_fg)
+                .bg(color)
+                .patch(rules::style_for(&self.rules, data));
+            if self
+                .flashing
+                .iter()
+                .any(|(created, _)| created == &data.created)
+            {
+                style = style.patch(flash_style);
+            }
+            Row::new(cells)
+                .style(style)
+                .height(u16::try_from(row_height).expect("row heights are at most 4 lines"))
         });
 
-        let t = Table::new(
-            rows,
-            [
-                Constraint::Length(22),
-                Constraint::Length(42),
-                Constraint::Min(self.longest_item_lens.2),
-                Constraint::Min(self.longest_item_lens.3),
-            ],
-        )
-        .header(header)
-        .highlight_style(selected_style)
-        .highlight_symbol(Text::from(vec![
-            "".into(),
-            " █ ".into(),
-            " █ ".into(),
-            "".into(),
-        ]))
-        .bg(self.colors.buffer_bg)
-        .highlight_spacing(HighlightSpacing::Always);
+        let t = Table::new(rows, constraints)
+            .header(header)
+            .highlight_style(selected_style)
+            .highlight_symbol(highlight_symbol)
+            .bg(self.colors.buffer_bg)
+            .highlight_spacing(HighlightSpacing::Always);
 
         frame.render_stateful_widget(t, area, &mut self.state);
+        self.table_area = area;
+    }
+
+    /// Says why the table is empty and how to fill it, in place of the table.
+    fn render_empty(&self, frame: &mut Frame, area: Rect) {
+        let (title, hint) = if self.items.is_empty() {
+            ("No todos yet", "Press A to add one")
+        } else if self.hide_completed
+            && self
+                .items
+                .iter()
+                .any(|item| item.progress == Progress::Done)
+        {
+            ("No todos match", "Press T to show completed todos too")
+        } else if !self.date_filters.is_empty() {
+            (
+                "No todos match",
+                "Run :filter without ranges to clear the date filter",
+            )
+        } else {
+            (
+                "No todos match",
+                "Pick All in the tag sidebar to show every tag",
+            )
+        };
+        let [_, message_area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        frame.render_widget(Block::new().bg(self.colors.buffer_bg), area);
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(title).bold().fg(self.colors.header_fg),
+                Line::from(hint).fg(self.colors.row_fg),
+            ])
+            .centered(),
+            message_area,
+        );
+    }
+
+    fn render_tabs(&self, frame: &mut Frame, area: Rect) {
+        let titles = self
+            .lists
+            .iter()
+            .enumerate()
+            .map(|(i, list)| format!(" {} {} ", i + 1, list.name));
+        let mut highlight = Style::new()
+            .fg(self.colors.header_fg)
+            .bg(self.colors.header_bg)
+            .add_modifier(Modifier::BOLD);
+        if self.no_color {
+            highlight = highlight.add_modifier(Modifier::REVERSED);
+        }
+        let tabs = Tabs::new(titles)
+            .select(self.active_list)
+            .style(
+                Style::new()
+                    .fg(self.colors.header_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .highlight_style(highlight)
+            .padding("", "")
+            .divider("|");
+        frame.render_widget(tabs, area);
+    }
+
+    fn render_sidebar(&mut self, frame: &mut Frame, area: Rect) {
+        let tags = self.tag_counts();
+
+        // Tags can disappear as tasks change, so keep the selection on the active filter
+        self.sidebar_selected = self
+            .tag_filter
+            .as_ref()
+            .and_then(|filter| tags.iter().position(|(tag, _)| tag == filter))
+            .map_or(0, |index| index + 1);
+
+        let sidebar = TagSidebar {
+            tags: &tags,
+            open_total: self
+                .items
+                .iter()
+                .filter(|item| item.progress != Progress::Done)
+                .count(),
+            selected: self.sidebar_selected,
+            focused: self.pane_focus == PaneFocus::Sidebar,
+            style: Style::default().fg(Color::White),
+        };
+        sidebar.render(area, frame.buffer_mut(), self.colors.selected_style_fg);
+    }
+
+    fn render_kanban(&mut self, frame: &mut Frame, area: Rect) {
+        self.update_selected_index();
+        let lanes = self.kanban_lanes();
+        let selected_lane = self.selected_lane(&lanes);
+        let selected = self.selected_index().map(|index| &self.items[index]);
+        let board = KanbanBoard {
+            lanes: lanes
+                .into_iter()
+                .map(|(title, indices)| Lane {
+                    collapsed: self.collapsed_lanes.contains(&title),
+                    title,
+                    cards: indices
+                        .into_iter()
+                        .map(|index| (index, &self.items[index]))
+                        .collect(),
+                })
+                .collect(),
+            selected,
+            selected_lane,
+            icons: &self.icons,
+            style: Style::default()
+                .fg(self.colors.row_fg)
+                .bg(self.colors.buffer_bg),
+        };
+        self.kanban_targets = board.render(area, frame.buffer_mut(), self.colors.selected_style_fg);
+    }
+
+    fn render_preview(&self, frame: &mut Frame, area: Rect) {
+        let preview = PreviewPane {
+            item: self.selected_item(),
+            logged: self
+                .selected_item()
+                .map_or(0, |item| self.time_log.seconds_for(&item.created)),
+            clock: self.clock,
+            scroll: self.preview_scroll,
+            focused: self.pane_focus == PaneFocus::Preview,
+            field: self.preview_field,
+            editing: self.preview_input.as_deref(),
+            blockers: self
+                .selected_item()
+                .map(|item| dependencies::blockers(item, &self.items))
+                .unwrap_or_default(),
+            style: Style::default().fg(Color::White),
+        };
+        preview.render(area, frame.buffer_mut(), self.colors.selected_style_fg);
     }
 
+    /// Follows the rows the table was just rendered with, below its header.
     fn render_scrollbar(&mut self, frame: &mut Frame, area: Rect) {
+        let viewport = usize::from(area.height.saturating_sub(1));
+        let mut state = self.view().scrollbar(self.state.offset(), viewport);
         frame.render_stateful_widget(
             Scrollbar::default()
                 .orientation(ScrollbarOrientation::VerticalRight)
@@ -736,34 +4698,346 @@ impl App<'_> {
                 vertical: 1,
                 horizontal: 1,
             }),
-            &mut self.scroll_state,
+            &mut state,
+        );
+    }
+
+    /// e.g. `4 due today, 1 overdue`, counting the open todos of every list,
+    /// empty when none are.
+    fn due_summary(&self) -> String {
+        let today = Local::now().date_naive();
+        let (mut due_today, mut overdue) = (0, 0);
+        let lists = self.lists.iter().enumerate().map(|(index, list)| {
+            if index == self.active_list {
+                &self.items
+            } else {
+                &list.items
+            }
+        });
+        for item in lists.flatten() {
+            if item.progress == Progress::Done {
+                continue;
+            }
+            match item.due_date() {
+                Some(due) if due == today => due_today += 1,
+                Some(due) if due < today => overdue += 1,
+                _ => {}
+            }
+        }
+        let mut counts = Vec::new();
+        if due_today > 0 {
+            counts.push(format!("{due_today} due today"));
+        }
+        if overdue > 0 {
+            counts.push(format!("{overdue} overdue"));
+        }
+        counts.join(", ")
+    }
+
+    /// e.g. `todo-tui — 4 due today, 1 overdue`, so the taskbar shows the
+    /// day's load.
+    fn window_title(&self) -> String {
+        match self.due_summary() {
+            summary if summary.is_empty() => "todo-tui".to_string(),
+            summary => format!("todo-tui — {summary}"),
+        }
+    }
+
+    /// The date and time, kept current by the tick, with what's due today
+    /// on the right.
+    fn render_header(&self, frame: &mut Frame, area: Rect) {
+        let now = Utc::now();
+        let date = format!(
+            " {} {} ",
+            self.clock.date(now).format("%a"),
+            self.clock.format(now)
         );
+        let summary = match self.due_summary() {
+            summary if summary.is_empty() => " Nothing due today ".to_string(),
+            summary => format!(" {summary} "),
+        };
+        let style = Style::new()
+            .fg(self.colors.header_fg)
+            .bg(self.colors.header_bg);
+        let [date_area, summary_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(u16::try_from(summary.width()).unwrap_or(u16::MAX)),
+        ])
+        .areas(area);
+        frame.render_widget(Paragraph::new(date).bold().style(style), date_area);
+        frame.render_widget(Paragraph::new(summary).style(style), summary_area);
+    }
+
+    /// Sets the terminal's window title when the counts in it changed, saving
+    /// the terminal's own title first so it's put back on exit.
+    fn update_window_title(&mut self) {
+        let title = self.window_title();
+        if title == self.window_title && TITLE_SAVED.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut output = terminal_output();
+        if !TITLE_SAVED.swap(true, Ordering::Relaxed) {
+            let _ = write!(output, "\x1b[22;0t");
+        }
+        if let Err(e) = execute!(output, SetTitle(&title)) {
+            tracing::warn!("couldn't set the window title: {e}");
+        }
+        self.window_title = title;
+    }
+
+    /// How many todos of the active list have each status, including hidden ones.
+    fn status_counts(&self) -> String {
+        let count = |progress: Progress| {
+            self.items
+                .iter()
+                .filter(|item| item.progress == progress)
+                .count()
+        };
+        let hidden = if self.hide_completed { " (hidden)" } else { "" };
+        format!(
+            "{} open · {} in progress · {} done{hidden}",
+            count(Progress::Waiting),
+            count(Progress::InProgress),
+            count(Progress::Done)
+        )
+    }
+
+    /// The `:filter` date ranges on the right of the footer's border, if any.
+    fn date_filter_title(&self) -> Line<'static> {
+        if self.date_filters.is_empty() {
+            return Line::default();
+        }
+        let terms: Vec<String> = self.date_filters.iter().map(DateFilter::term).collect();
+        Line::from(format!(" Filter: {} ", terms.join(" "))).right_aligned()
+    }
+
+    /// The time left on the open todos due by the end of the week, overdue
+    /// ones included, as their remaining time or estimate minus time logged.
+    fn remaining_this_week(&self) -> u64 {
+        let today = Local::now().date_naive();
+        let week_end = today
+            + Days::new(u64::from(
+                6 - self.preferences.week_start.day_of_week(today),
+            ));
+        self.items
+            .iter()
+            .filter(|item| item.progress != Progress::Done)
+            .filter(|item| item.due_date().is_some_and(|due| due <= week_end))
+            .filter_map(|item| item.remaining_seconds(self.time_log.seconds_for(&item.created)))
+            .sum()
+    }
+
+    fn remaining_title(&self) -> Line<'static> {
+        match self.remaining_this_week() {
+            0 => Line::default(),
+            seconds => Line::from(format!(" {} left this week ", stats::duration(seconds))),
+        }
     }
 
     fn render_footer(&self, frame: &mut Frame, area: Rect) {
-        let info_footer = Paragraph::new(Line::from(INFO_TEXT))
-            .style(
-                Style::new()
-                    .fg(self.colors.row_fg)
-                    .bg(self.colors.buffer_bg),
+        let style = Style::new()
+            .fg(self.colors.row_fg)
+            .bg(self.colors.buffer_bg);
+        let block = Block::bordered()
+            .border_type(BorderType::Double)
+            .border_style(Style::new().fg(self.colors.footer_border_color))
+            .title(format!(" {} ", self.status_counts()))
+            .title(self.date_filter_title())
+            .title_bottom(self.remaining_title())
+            .title_bottom(
+                Line::from(format!(" Sort: {} ", self.preferences.sort.label())).right_aligned(),
             )
-            .centered()
-            .block(
-                Block::bordered()
-                    .border_type(BorderType::Double)
-                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            .style(style);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        // The streak and daily goal only take up room once there is one
+        let today = Local::now().date_naive();
+        let goal = self.preferences.daily_goal;
+        let done_today = self.completions.count(today);
+        let streak = self.completions.streak(today, goal);
+        let width = match (goal, streak) {
+            (0, 0) => 0,
+            (0, _) => 16,
+            _ => 30,
+        };
+        let [info_area, goal_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(width)]).areas(inner);
+        let mut info = if self.picking {
+            PICK_INFO_TEXT.to_string()
+        } else {
+            INFO_TEXT.to_string()
+        };
+        let statuses = ["waiting", "in progress", "done"];
+        let mapping: Vec<String> = self
+            .status_keys()
+            .iter()
+            .zip(statuses)
+            .map(|(key, status)| format!("({key}) {status}"))
+            .collect();
+        if !mapping.is_empty() {
+            info = format!("{} | {info}", mapping.join(" "));
+        }
+        if self.recording.is_some() {
+            info = format!("● Recording macro, (Shift+Q) stop | {info}");
+        }
+        frame.render_widget(Paragraph::new(Line::from(info)).centered(), info_area);
+        if goal > 0 {
+            let gauge = LineGauge::default()
+                .label(format!("🔥{streak} {done_today}/{goal}"))
+                .ratio((f64::from(done_today) / f64::from(goal)).min(1.0))
+                .filled_style(Style::new().fg(self.colors.selected_style_fg))
+                .unfilled_style(Style::new().fg(self.colors.alt_row_color));
+            frame.render_widget(gauge, goal_area);
+        } else if streak > 0 {
+            let days = if streak == 1 { "day" } else { "days" };
+            frame.render_widget(
+                Paragraph::new(format!("🔥 {streak} {days}")).right_aligned(),
+                goal_area,
             );
-        frame.render_widget(info_footer, area);
+        }
+    }
+}
+
+/// Parses a due date as stored, `2024-02-01`, or `2024-02-01 14:30` for a
+/// time of day.
+fn parse_due(due: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let (date, time) = match due.trim().split_once(' ') {
+        Some((date, time)) => (date, Some(time.trim())),
+        None => (due.trim(), None),
+    };
+    let date = NaiveDate::parse_from_str(date, DUE_FORMAT).ok()?;
+    let time = match time {
+        Some(time) => Some(NaiveTime::parse_from_str(time, DUE_TIME_FORMAT).ok()?),
+        None => None,
+    };
+    Some((date, time))
+}
+
+fn format_due(date: NaiveDate, time: Option<NaiveTime>) -> String {
+    match time {
+        Some(time) => format!(
+            "{} {}",
+            date.format(DUE_FORMAT),
+            time.format(DUE_TIME_FORMAT)
+        ),
+        None => date.format(DUE_FORMAT).to_string(),
+    }
+}
+
+/// Breaks `text` into lines at most `max_len` columns wide, never inside a
+/// wide character. Text past `max_lines` is cut at the last line with a `…`;
+/// the reader shows all of it.
+fn wrap_text(text: &str, max_len: usize, max_lines: usize) -> String {
+    let mut lines = vec![String::new()];
+    let mut width = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if c == '\n' || (width > 0 && width + char_width > max_len) {
+            lines.push(String::new());
+            width = 0;
+            if c == '\n' {
+                continue;
+            }
+        }
+        if let Some(line) = lines.last_mut() {
+            line.push(c);
+        }
+        width += char_width;
+    }
+    if lines.len() > max_lines {
+        lines.truncate(max_lines.max(1));
+        if let Some(last) = lines.last_mut() {
+            while last.width() + 1 > max_len && last.pop().is_some() {}
+            last.push('…');
+        }
+    }
+    lines.join("\n")
+}
+
+/// Drops every color the widgets set, keeping bold, reversed and the like,
+/// so the terminal's own colors are all that's left.
+fn strip_colors(buf: &mut Buffer) {
+    for cell in &mut buf.content {
+        cell.set_fg(Color::Reset).set_bg(Color::Reset);
+        cell.underline_color = Color::Reset;
+    }
+}
+
+/// The Created cell, with an age badge on open tasks past the stale thresholds.
+/// Without colors, an `!` after the age tells alerts from warnings. `relative`
+/// shows how long ago it was created, like `3d ago`, instead of when.
+fn created_cell(
+    data: &Data,
+    stale_days: StaleDays,
+    clock: Clock,
+    relative: bool,
+    no_color: bool,
+) -> Cell<'static> {
+    let created = match timestamps::parse(&data.created) {
+        Some(created) if relative => {
+            format!("{} ago", timestamps::short_age(created, Utc::now()))
+        }
+        _ => clock.display(&data.created),
+    };
+    let age = data
+        .age_days()
+        .filter(|_| data.progress != Progress::Done)
+        .unwrap_or(0);
+    let (color, marker) = if age >= stale_days.alert {
+        (Color::Red, if no_color { "!" } else { "" })
+    } else if age >= stale_days.warning {
+        (Color::Yellow, "")
+    } else {
+        return Cell::from(Text::from(created));
+    };
+
+    Cell::from(Text::from(format!("{created} {age}d{marker}")).style(Style::new().fg(color)))
+}
+
+fn scrolled_text(text: &str, offset: usize) -> String {
+    if offset == 0 {
+        text.to_string()
+    } else {
+        // Mark the cell so it's obvious the start of the text is hidden
+        format!("«{}", text.chars().skip(offset).collect::<String>())
     }
 }
 
-fn wrap_text(text: &str, max_len: usize) -> String {
-    text.chars()
-        .collect::<Vec<_>>() // Collect into a vector of chars for easier manipulation
-        .chunks(max_len) // Split into chunks of max_len
-        .map(|chunk| chunk.iter().collect::<String>()) // Collect each chunk back into a string
-        .collect::<Vec<_>>() // Collect into a vector of lines
-        .join("\n")
+/// Preview lines for one todo changed by a command.
+fn change_lines(item: &Data, change: Change) -> Vec<Line<'static>> {
+    if change.remove {
+        return vec![Line::from(vec![
+            Span::styled(item.name.clone(), Style::new().fg(Color::Red)),
+            Span::from("  deleted").italic(),
+        ])];
+    }
+
+    let mut lines = vec![match change.name {
+        Some(name) => Line::from(vec![
+            Span::styled(item.name.clone(), Style::new().fg(Color::Red)),
+            Span::from(" → "),
+            Span::styled(name, Style::new().fg(Color::Green)),
+        ]),
+        None => Line::from(item.name.clone()),
+    }];
+    if let Some(description) = change.description {
+        lines.push(Line::from(vec![
+            Span::from("  description: "),
+            Span::styled(description, Style::new().fg(Color::Green)),
+        ]));
+    }
+    if let Some(progress) = change.progress {
+        let (old_color, old) = item.progress.display();
+        let (new_color, new) = progress.display();
+        lines.push(Line::from(vec![
+            Span::from("  status: "),
+            Span::styled(old, Style::new().fg(old_color)),
+            Span::from(" → "),
+            Span::styled(new, Style::new().fg(new_color)),
+        ]));
+    }
+    lines
 }
 
 fn constraint_len_calculator(items: &[Data]) -> (u16, u16, u16, u16) {
@@ -788,33 +5062,101 @@ fn constraint_len_calculator(items: &[Data]) -> (u16, u16, u16, u16) {
 
     let created_len = items
         .iter()
-        .map(|data| u16::try_from(data.created.len()).unwrap_or(0))
+        // The shown length is the same in every zone, and no format is longer than the default
+        .map(|data| u16::try_from(Clock::default().display(&data.created).len()).unwrap_or(0))
         .max()
         .unwrap_or(0);
 
     (name_len, description_len, progress_len, created_len)
 }
 
-fn read_json() -> io::Result<Vec<Data>> {
-    let file = File::open(JSON_FILE_PATH).map_err(|e| {
-        eprintln!("Error opening file: {e}");
-        e
-    })?;
+fn read_json(path: &Path) -> io::Result<Vec<Data>> {
+    let file = File::open(path)?;
     let reader = BufReader::new(file);
-    let data: Vec<Data> = serde_json::from_reader(reader).map_err(|e| {
-        eprintln!("Error parsing JSON: {e}");
-        e
-    })?;
+    let data: Vec<Data> = serde_json::from_reader(reader)?;
     Ok(data)
 }
 
-fn save_json(data: &[Data]) -> io::Result<()> {
-    let file = File::create(JSON_FILE_PATH)?;
+fn save_json(path: &Path, data: &[Data]) -> io::Result<()> {
+    let file = File::create(path)?;
     serde_json::to_writer_pretty(file, data)?;
     Ok(())
 }
 
+/// Whether the list file is a Markdown checklist rather than JSON.
+fn is_checklist(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
+}
+
+fn read_list(path: &Path) -> io::Result<Vec<Data>> {
+    if is_checklist(path) {
+        Ok(checklist::from_markdown(&fs::read_to_string(path)?))
+    } else {
+        read_json(path)
+    }
+}
+
+/// Identifies the done todos, by creation time, to notice newly completed ones.
+fn done_keys(items: &[Data]) -> Vec<String> {
+    items
+        .iter()
+        .filter(|item| item.progress == Progress::Done)
+        .map(|item| item.created.clone())
+        .collect()
+}
+
+fn save_list(path: &Path, data: &[Data]) -> io::Result<()> {
+    if is_checklist(path) {
+        fs::write(path, checklist::to_markdown(data))
+    } else {
+        save_json(path, data)
+    }
+}
+
+/// Flattens pasted or edited text onto the single line the inputs hold.
+fn single_line(text: &str) -> String {
+    text.replace("\r\n", " ").replace(['\r', '\n', '\t'], " ")
+}
+
+/// Whether an input can take another character under `limit`, counted in
+/// characters rather than bytes so accents and emoji count as one.
+fn has_room(input: &str, limit: usize) -> bool {
+    input.chars().count() < limit
+}
+
+/// Appends `text` to an input, stopping at the same length limit as typing.
+fn paste_into(input: &mut String, text: &str, limit: usize) {
+    let room = limit.saturating_sub(input.chars().count());
+    input.extend(text.chars().take(room));
+}
+
+/// Waits up to `timeout` for a terminal event.
+fn next_terminal_event(timeout: Duration) -> io::Result<Option<Event>> {
+    Ok(if event::poll(timeout)? {
+        Some(event::read()?)
+    } else {
+        None
+    })
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Hashes a file's bytes, `None` if it can't be read, e.g. it doesn't exist yet.
+fn file_hash(path: &Path) -> Option<u64> {
+    let contents = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
 fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    // Cut down to fit, as in a short inline viewport
+    let width = width.min(area.width);
+    let height = height.min(area.height);
     Rect::new(
         area.x + (area.width - width) / 2,
         area.y + (area.height - height) / 2,
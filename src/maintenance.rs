@@ -0,0 +1,156 @@
+use crate::preferences::Preferences;
+use crate::{is_checklist, read_list, save_list, timestamps, Data};
+use chrono::{Duration, SecondsFormat, Utc};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const USAGE: &str = "usage: todo-tui maintenance [--dry-run]";
+
+/// Fields every todo is written with, see `Data`.
+const FIELDS: [&str; 5] = ["name", "description", "progress", "created", "starred"];
+
+/// What `tidy` changed, or would change on a dry run.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub todos: usize,      // Left in the file
+    pub duplicates: usize, // Exact copies removed
+    pub new_ids: usize,    // Given a creation time, as theirs was missing, invalid or taken
+    pub fixed: usize,      // Written back with missing or invalid fields filled in
+    pub sorted: bool,      // Put in creation order
+}
+
+impl Report {
+    fn changed(&self) -> bool {
+        self.duplicates > 0 || self.new_ids > 0 || self.fixed > 0 || self.sorted
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let todos = |count: usize| match count {
+            1 => "1 todo".to_string(),
+            _ => format!("{count} todos"),
+        };
+        let mut lines = Vec::new();
+        match self.duplicates {
+            0 => {}
+            1 => lines.push("Removed 1 exact duplicate".to_string()),
+            count => lines.push(format!("Removed {count} exact duplicates")),
+        }
+        if self.new_ids > 0 {
+            lines.push(format!("Gave {} a new creation time", todos(self.new_ids)));
+        }
+        if self.fixed > 0 {
+            lines.push(format!(
+                "Filled in missing or invalid fields of {}",
+                todos(self.fixed)
+            ));
+        }
+        if self.sorted {
+            lines.push("Sorted the todos by creation time".to_string());
+        }
+        lines
+    }
+}
+
+/// `todo-tui maintenance`: checks the main list's file and tidies it up,
+/// printing what it changed. `--dry-run` only prints it.
+pub fn run(mut args: impl Iterator<Item = String>) -> io::Result<()> {
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+    let mut dry_run = false;
+    if let Some(arg) = args.next() {
+        if arg != "--dry-run" {
+            return Err(invalid(format!("unknown argument {arg:?}\n{USAGE}")));
+        }
+        dry_run = true;
+    }
+
+    let preferences = Preferences::load().unwrap_or_else(|e| {
+        tracing::warn!("{e}");
+        Preferences::default()
+    });
+    let path = preferences.data_path();
+    let report = tidy(&path, !dry_run)
+        .map_err(|e| io::Error::new(e.kind(), format!("{} isn't valid: {e}", path.display())))?;
+    tracing::info!(path = %path.display(), ?report, dry_run, "maintenance");
+
+    println!("Checked {}: {} todos", path.display(), report.todos);
+    let lines = report.lines();
+    if lines.is_empty() {
+        println!("Nothing to fix");
+    }
+    for line in lines {
+        println!("{line}");
+    }
+    if dry_run && report.changed() {
+        println!("Dry run, the file was left as it was");
+    }
+    Ok(())
+}
+
+/// Removes exact duplicates, gives todos without a unique creation time (which
+/// identifies them) a new one, and sorts the list by it, writing the result
+/// back when `write` is set and anything changed. A file that can't be read is
+/// an error and left alone.
+pub fn tidy(path: &Path, write: bool) -> io::Result<Report> {
+    let fixed = fixed_fields(path)?;
+    let mut items = read_list(path)?;
+    let mut report = Report {
+        fixed,
+        ..Report::default()
+    };
+
+    let mut seen = HashSet::new();
+    let before = items.len();
+    items.retain(|item| seen.insert(serde_json::to_string(item).unwrap_or_default()));
+    report.duplicates = before - items.len();
+
+    let mut ids = HashSet::new();
+    let mut next_id = Utc::now();
+    for item in &mut items {
+        if timestamps::parse(&item.created).is_some() && ids.insert(item.created.clone()) {
+            continue;
+        }
+        // A second apart, as that's what creation times are stored to
+        while !ids.insert(next_id.to_rfc3339_opts(SecondsFormat::Secs, true)) {
+            next_id += Duration::seconds(1);
+        }
+        item.created = next_id.to_rfc3339_opts(SecondsFormat::Secs, true);
+        report.new_ids += 1;
+    }
+
+    // Stable, so todos created the same second keep their order
+    let order = |item: &Data| (timestamps::parse(&item.created), item.created.clone());
+    report.sorted = items
+        .windows(2)
+        .any(|pair| order(&pair[0]) > order(&pair[1]));
+    items.sort_by_key(order);
+
+    report.todos = items.len();
+    if write && report.changed() {
+        save_list(path, &items)?;
+    }
+    Ok(report)
+}
+
+/// Counts the todos in a JSON list that lack a field, or hold a value that
+/// loading replaced with the default. Checklists always load complete.
+fn fixed_fields(path: &Path) -> io::Result<usize> {
+    if is_checklist(path) {
+        return Ok(0);
+    }
+    let raw: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let items = read_list(path)?;
+    Ok(raw
+        .iter()
+        .zip(&items)
+        .filter(|(raw, item)| {
+            let Ok(loaded) = serde_json::to_value(item) else {
+                return false;
+            };
+            FIELDS
+                .iter()
+                .any(|field| raw.get(field) != loaded.get(field))
+        })
+        .count())
+}
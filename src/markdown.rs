@@ -0,0 +1,85 @@
+use ratatui::prelude::{Color, Line, Modifier, Span, Style, Text};
+
+/// Renders the basic Markdown used in descriptions: `**bold**`, `*italic*` or
+/// `_italic_`, `` `inline code` `` and `-`/`*` bullet lists. Anything else,
+/// including unclosed markers, is shown as written.
+pub fn to_text(markdown: &str) -> Text<'static> {
+    Text::from(markdown.lines().map(line).collect::<Vec<_>>())
+}
+
+fn line(source: &str) -> Line<'static> {
+    let indent = source.len() - source.trim_start().len();
+    let rest = &source[indent..];
+    match rest.strip_prefix("- ").or_else(|| rest.strip_prefix("* ")) {
+        Some(item) => {
+            let mut spans = vec![Span::from(format!("{}• ", &source[..indent]))];
+            spans.extend(inline(item));
+            Line::from(spans)
+        }
+        None => Line::from(inline(source)),
+    }
+}
+
+fn inline(source: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = source;
+    let mut previous: Option<char> = None;
+
+    while let Some(c) = rest.chars().next() {
+        // Emphasis only opens at the start of a word, so snake_case stays as is
+        let at_word_start = !previous.is_some_and(char::is_alphanumeric);
+        let styled = if c == '`' {
+            span(
+                rest,
+                "`",
+                Style::new().fg(Color::Yellow).bg(Color::DarkGray),
+            )
+        } else if rest.starts_with("**") {
+            span(rest, "**", Style::new().add_modifier(Modifier::BOLD))
+        } else if (c == '*' || c == '_') && at_word_start {
+            span(
+                rest,
+                &rest[..1],
+                Style::new().add_modifier(Modifier::ITALIC),
+            )
+        } else {
+            None
+        };
+
+        match styled {
+            Some((styled, consumed)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::from(std::mem::take(&mut plain)));
+                }
+                spans.push(styled);
+                previous = rest[..consumed].chars().last();
+                rest = &rest[consumed..];
+            }
+            None => {
+                plain.push(c);
+                previous = Some(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::from(plain));
+    }
+    spans
+}
+
+/// The text between `marker` at the start of `source` and the next `marker`,
+/// with the number of bytes used. The content can't start or end with a space.
+fn span(source: &str, marker: &str, style: Style) -> Option<(Span<'static>, usize)> {
+    let after = &source[marker.len()..];
+    let end = after.find(marker)?;
+    let content = &after[..end];
+    if content.is_empty() || content.starts_with(' ') || content.ends_with(' ') {
+        return None;
+    }
+    Some((
+        Span::styled(content.to_string(), style),
+        marker.len() * 2 + end,
+    ))
+}
@@ -0,0 +1,289 @@
+//! The setup shown on the very first launch, when there is neither a
+//! `preferences.json` nor a `data.json` yet. It ends by writing the preferences,
+//! so it only ever runs once.
+
+use crate::preferences::Preferences;
+use crate::timestamps::{self, Clock, DateFormat};
+use crate::{popup_area, save_list, Data, Priority, Progress, DUE_FORMAT, JSON_FILE_PATH, THEMES};
+use chrono::{Days, Local, Utc};
+use ratatui::backend::Backend;
+use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::layout::Rect;
+use ratatui::prelude::{Line, Modifier, Span, Style, Stylize, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
+use ratatui::Terminal;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const THEME_NAMES: [&str; THEMES.len()] = ["Blue", "Emerald", "Indigo", "Red", "High contrast"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Location,
+    Theme,
+    DateFormat,
+    Examples,
+}
+
+impl Step {
+    const ALL: [Self; 4] = [
+        Self::Location,
+        Self::Theme,
+        Self::DateFormat,
+        Self::Examples,
+    ];
+
+    fn number(self) -> usize {
+        Self::ALL.iter().position(|&step| step == self).unwrap_or(0) + 1
+    }
+}
+
+pub struct Onboarding {
+    pub step: Step,
+    pub data_dir: String, // Empty for the working directory
+    pub theme: usize,
+    pub date_format: DateFormat,
+    pub examples: bool,
+}
+
+impl Default for Onboarding {
+    fn default() -> Self {
+        Self {
+            step: Step::Location,
+            data_dir: String::new(),
+            theme: 0,
+            date_format: DateFormat::default(),
+            examples: true,
+        }
+    }
+}
+
+impl Onboarding {
+    /// Whether this is the first launch in the working directory.
+    pub fn needed() -> bool {
+        !Preferences::exist() && !Path::new(JSON_FILE_PATH).exists()
+    }
+
+    /// Shows the setup until it's finished or skipped, then writes the files.
+    pub fn run<B: Backend>(
+        terminal: &mut Terminal<B>,
+        mut next_event: impl FnMut(Duration) -> io::Result<Option<Event>>,
+    ) -> io::Result<()> {
+        let mut onboarding = Self::default();
+        loop {
+            terminal.draw(|frame| onboarding.render(frame.area(), frame.buffer_mut()))?;
+            let Some(Event::Key(key)) = next_event(crate::TICK_RATE)? else {
+                continue;
+            };
+            if key.kind == KeyEventKind::Press && !onboarding.handle_key(key.code) {
+                break;
+            }
+        }
+        onboarding.write()
+    }
+
+    /// Returns false once the setup is over.
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
+        let index = self.step.number() - 1;
+        match key {
+            KeyCode::Esc => {
+                // Skipping keeps the defaults, without example tasks
+                *self = Self {
+                    examples: false,
+                    ..Self::default()
+                };
+                return false;
+            }
+            KeyCode::Enter => match Step::ALL.get(index + 1) {
+                Some(&step) => self.step = step,
+                None => return false,
+            },
+            KeyCode::BackTab => self.step = Step::ALL[index.saturating_sub(1)],
+            KeyCode::Up | KeyCode::Left => self.choose(false),
+            KeyCode::Down | KeyCode::Right => self.choose(true),
+            KeyCode::Char(c) if self.step == Step::Location => self.data_dir.push(c),
+            KeyCode::Backspace if self.step == Step::Location => {
+                self.data_dir.pop();
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Moves to the next or previous option of the current step.
+    fn choose(&mut self, forward: bool) {
+        match self.step {
+            Step::Location => {}
+            Step::Theme => self.theme = cycle(self.theme, THEMES.len(), forward),
+            Step::DateFormat => {
+                let formats = DateFormat::ALL;
+                let index = formats.iter().position(|&f| f == self.date_format);
+                self.date_format = formats[cycle(index.unwrap_or(0), formats.len(), forward)];
+            }
+            Step::Examples => self.examples = !self.examples,
+        }
+    }
+
+    /// The preferences the chosen settings make up.
+    pub fn preferences(&self) -> Preferences {
+        let data_dir = self.data_dir.trim();
+        Preferences {
+            data_dir: (!data_dir.is_empty()).then(|| PathBuf::from(data_dir)),
+            theme: self.theme,
+            date_format: self.date_format,
+            ..Preferences::default()
+        }
+    }
+
+    /// A few tasks that show off statuses, priorities, due dates and tags.
+    pub fn example_tasks(&self) -> Vec<Data> {
+        if !self.examples {
+            return Vec::new();
+        }
+        let in_days = |days| {
+            Local::now()
+                .date_naive()
+                .checked_add_days(Days::new(days))
+                .map(|date| date.format(DUE_FORMAT).to_string())
+        };
+        let example = |name: &str, description: &str, progress| Data {
+            name: name.to_string(),
+            description: description.to_string(),
+            progress,
+            created: timestamps::now(),
+            ..Data::default()
+        };
+        vec![
+            Data {
+                priority: Some(Priority::High),
+                ..example(
+                    "Try the keys",
+                    "Press **a** to add, **r** to edit, **n** to change the status and **x** to delete. **i** lists the rest.",
+                    Progress::InProgress,
+                )
+            },
+            Data {
+                due: in_days(2),
+                ..example(
+                    "Plan the week #planning",
+                    "Tasks with a due date are reminded of as it gets close.",
+                    Progress::Waiting,
+                )
+            },
+            example(
+                "Set up Todo-TUI",
+                "Done already. Press **t** to hide finished tasks.",
+                Progress::Done,
+            ),
+        ]
+    }
+
+    /// Creates the data folder with the example tasks, unless it already holds
+    /// a list, and saves the preferences.
+    fn write(&self) -> io::Result<()> {
+        let preferences = self.preferences();
+        let dir = preferences.data_dir();
+        if !dir.as_os_str().is_empty() {
+            fs::create_dir_all(dir)?;
+        }
+        let data_path = preferences.data_path();
+        let tasks = self.example_tasks();
+        if !tasks.is_empty() && !data_path.exists() {
+            save_list(&data_path, &tasks)?;
+        }
+        tracing::info!(data_dir = %dir.display(), examples = tasks.len(), "finished setup");
+        preferences.save()
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        let accent = THEMES[self.theme].selected_style_fg;
+        let area = popup_area(area, area.width.min(64), area.height.min(14));
+        Clear.render(area, buf);
+
+        let selected = Style::new().fg(accent).add_modifier(Modifier::BOLD);
+        let option = |label: String, chosen: bool| {
+            if chosen {
+                Line::from(Span::styled(format!("› {label}"), selected))
+            } else {
+                Line::from(format!("  {label}"))
+            }
+        };
+
+        let (question, mut lines) = match self.step {
+            Step::Location => (
+                "Where should your todos be kept?",
+                vec![
+                    Line::from(if self.data_dir.is_empty() {
+                        Span::from("This folder").italic()
+                    } else {
+                        Span::styled(self.data_dir.clone(), selected)
+                    }),
+                    Line::from("Type a folder, or leave it empty for this one.").italic(),
+                ],
+            ),
+            Step::Theme => (
+                "Pick a color theme",
+                THEME_NAMES
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| {
+                        let line = option((*name).to_string(), i == self.theme);
+                        line.patch_style(Style::new().fg(THEMES[i].selected_style_fg))
+                    })
+                    .collect(),
+            ),
+            Step::DateFormat => (
+                "How should dates look?",
+                DateFormat::ALL
+                    .iter()
+                    .map(|&format| {
+                        let clock = Clock {
+                            format,
+                            ..Clock::default()
+                        };
+                        option(clock.format(Utc::now()), format == self.date_format)
+                    })
+                    .collect(),
+            ),
+            Step::Examples => (
+                "Add a few example tasks to start with?",
+                vec![
+                    option("Yes".to_string(), self.examples),
+                    option("No".to_string(), !self.examples),
+                ],
+            ),
+        };
+        lines.insert(0, Line::from(question).bold());
+        lines.insert(1, Line::default());
+        lines.push(Line::default());
+        lines.push(
+            Line::from("Enter next · ↑↓ choose · Shift+Tab back · Esc skip")
+                .style(Style::new().dim()),
+        );
+
+        Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::new()
+                    .title(format!(
+                        " Welcome to Todo-TUI ({}/{}) ",
+                        self.step.number(),
+                        Step::ALL.len()
+                    ))
+                    .borders(Borders::ALL)
+                    .border_style(Style::new().fg(accent)),
+            )
+            .render(area, buf);
+    }
+}
+
+fn cycle(index: usize, len: usize, forward: bool) -> usize {
+    if forward {
+        (index + 1) % len
+    } else {
+        (index + len - 1) % len
+    }
+}
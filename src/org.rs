@@ -0,0 +1,153 @@
+//! Org-mode files, so the same todos can be kept in Emacs. Each todo is a
+//! headline with a TODO or DONE keyword, its priority cookie and tags, the due
+//! date as a DEADLINE and the creation time in a property drawer.
+
+use crate::{timestamps, Data, Priority, Progress, DUE_FORMAT};
+use chrono::NaiveDate;
+use regex::Regex;
+use std::sync::LazyLock;
+
+static HEADLINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\*+\s+(?:([A-Z]+)\s+)?(?:\[#([A-C])\]\s+)?(.*?)(?:\s+(:[\w@#%:]+:))?\s*$")
+        .expect("valid regex")
+});
+static PLANNING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(SCHEDULED|DEADLINE):\s*<(\d{4}-\d{2}-\d{2})[^>]*>").expect("valid regex")
+});
+static PROPERTY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*:([\w-]+):\s*(.*?)\s*$").expect("valid regex"));
+
+/// Writes every todo as a top-level headline.
+pub fn to_org(items: &[&Data]) -> String {
+    let mut org = String::new();
+    for item in items {
+        let keyword = if item.progress == Progress::Done {
+            "DONE"
+        } else {
+            "TODO"
+        };
+        org.push_str(&format!("* {keyword} "));
+        if let Some(priority) = item.priority {
+            org.push_str(&format!("[#{}] ", cookie(priority)));
+        }
+        org.push_str(&item.name);
+        let tags = item.tags();
+        if !tags.is_empty() {
+            org.push_str(&format!(" :{}:", tags.join(":")));
+        }
+        org.push('\n');
+
+        if let Some(due) = item.due_date() {
+            org.push_str(&format!("  DEADLINE: <{}>\n", due.format("%Y-%m-%d %a")));
+        }
+        org.push_str("  :PROPERTIES:\n");
+        org.push_str(&format!("  :CREATED: {}\n", item.created));
+        // Org only knows TODO and DONE out of the box
+        if item.progress == Progress::InProgress {
+            org.push_str("  :PROGRESS: InProgress\n");
+        }
+        org.push_str("  :END:\n");
+        for line in item.description.lines() {
+            org.push_str(&format!("  {line}\n"));
+        }
+    }
+    org
+}
+
+/// Reads every headline with a TODO-style keyword as a todo. NEXT, STARTED and
+/// DOING count as in progress, CANCELLED as done. The DEADLINE, or otherwise
+/// the SCHEDULED date, becomes the due date, and tags missing from the name or
+/// description are added to the description.
+pub fn from_org(contents: &str) -> Vec<Data> {
+    let mut items = Vec::new();
+    let mut lines = contents.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let Some(headline) = HEADLINE.captures(line) else {
+            continue;
+        };
+        let keyword = headline.get(1).map_or("", |keyword| keyword.as_str());
+        let mut progress = match keyword {
+            "TODO" | "WAITING" => Progress::Waiting,
+            "NEXT" | "STARTED" | "DOING" => Progress::InProgress,
+            "DONE" | "CANCELLED" | "CANCELED" => Progress::Done,
+            // Plain headlines are notes, not todos
+            _ => continue,
+        };
+
+        let mut item = Data {
+            name: headline[3].to_string(),
+            priority: headline.get(2).and_then(|cookie| match cookie.as_str() {
+                "A" => Some(Priority::High),
+                "B" => Some(Priority::Medium),
+                "C" => Some(Priority::Low),
+                _ => None,
+            }),
+            ..Data::default()
+        };
+
+        let mut deadline = None;
+        let mut scheduled = None;
+        let mut body = Vec::new();
+        let mut in_drawer = false;
+        while let Some(line) = lines.next_if(|line| !HEADLINE.is_match(line)) {
+            let trimmed = line.trim();
+            if in_drawer {
+                if trimmed == ":END:" {
+                    in_drawer = false;
+                } else if let Some(property) = PROPERTY.captures(line) {
+                    match &property[1] {
+                        "CREATED" => item.created = property[2].to_string(),
+                        "PROGRESS" => progress = Progress::parse(&property[2]).unwrap_or(progress),
+                        _ => {}
+                    }
+                }
+            } else if trimmed == ":PROPERTIES:" {
+                in_drawer = true;
+            } else if PLANNING.is_match(line) {
+                for planning in PLANNING.captures_iter(line) {
+                    let date = NaiveDate::parse_from_str(&planning[2], "%Y-%m-%d").ok();
+                    match &planning[1] {
+                        "DEADLINE" => deadline = date,
+                        _ => scheduled = date,
+                    }
+                }
+            } else if !trimmed.starts_with("CLOSED:") {
+                body.push(line.strip_prefix("  ").unwrap_or(line));
+            }
+        }
+        while body.last().is_some_and(|line| line.trim().is_empty()) {
+            body.pop();
+        }
+
+        item.progress = progress;
+        item.description = body.join("\n");
+        item.due = deadline
+            .or(scheduled)
+            .map(|due| due.format(DUE_FORMAT).to_string());
+        if item.created.is_empty() {
+            item.created = timestamps::now();
+        }
+        if let Some(tags) = headline.get(4) {
+            let known = item.tags();
+            for tag in tags.as_str().split(':').filter(|tag| !tag.is_empty()) {
+                if !known.contains(&tag.to_lowercase()) {
+                    if !item.description.is_empty() {
+                        item.description.push(' ');
+                    }
+                    item.description.push_str(&format!("#{tag}"));
+                }
+            }
+        }
+        items.push(item);
+    }
+    items
+}
+
+const fn cookie(priority: Priority) -> char {
+    match priority {
+        Priority::High => 'A',
+        Priority::Medium => 'B',
+        Priority::Low => 'C',
+    }
+}
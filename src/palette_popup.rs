@@ -0,0 +1,70 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Span, Style, Stylize, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+/// Every action by name, filtered by a fuzzy query, with the keys that run it.
+pub struct PalettePopup {
+    pub query: String,
+    pub entries: Vec<(String, String)>, // Name and keys
+    pub selected: usize,
+    pub style: Style,
+}
+
+impl PalettePopup {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        Clear.render(area, buf);
+
+        let [input_area, entries_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(area);
+
+        Paragraph::new(Text::from(self.query))
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title("Command palette")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(selected_style_fg)),
+            )
+            .render(input_area, buf);
+
+        let width = usize::from(entries_area.width.saturating_sub(2));
+        let lines: Vec<Line> = self
+            .entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, hint))| {
+                let gap = width.saturating_sub(name.chars().count() + hint.chars().count());
+                let line = Line::from(vec![
+                    Span::from(name),
+                    Span::from(" ".repeat(gap)),
+                    Span::from(hint).fg(Color::DarkGray),
+                ]);
+                if i == self.selected {
+                    line.style(
+                        Style::new()
+                            .fg(selected_style_fg)
+                            .add_modifier(Modifier::REVERSED),
+                    )
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        // Keep the selected entry in view
+        let visible = usize::from(entries_area.height.saturating_sub(2));
+        let scroll = u16::try_from((self.selected + 1).saturating_sub(visible)).unwrap_or(0);
+
+        Paragraph::new(lines)
+            .scroll((scroll, 0))
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title_bottom(Line::from(" (↑/↓) select | (Enter) run | (Esc) close ").italic())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::White)),
+            )
+            .render(entries_area, buf);
+    }
+}
@@ -0,0 +1,78 @@
+//! `todo-tui pick`: the app as a selector for shell scripts, like fzf for
+//! todos. The UI is drawn on stderr so stdout only gets the picked todo.
+
+use crate::{
+    install_crash_handlers, next_terminal_event, no_color_requested, restore_terminal, App, Data,
+    UI_ON_STDERR,
+};
+use crossterm::event::{EnableBracketedPaste, EnableMouseCapture};
+use crossterm::execute;
+use crossterm::terminal::{enable_raw_mode, EnterAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use std::io::{self, Write};
+use std::sync::atomic::Ordering;
+
+const USAGE: &str = "usage: todo-tui pick [--format json|id|name] [--no-color]";
+
+/// Exit status when nothing was picked, as fzf does.
+const CANCELLED: i32 = 130;
+
+/// What's printed of the picked todo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json, // The whole todo on one line
+    Id,   // Its creation time, which identifies it across lists and copies
+    Name,
+}
+
+pub fn run(mut args: impl Iterator<Item = String>) -> color_eyre::Result<()> {
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+    let mut format = Format::Json;
+    while let Some(arg) = args.next() {
+        if arg == "--no-color" {
+            continue;
+        }
+        if arg != "--format" {
+            return Err(invalid(format!("unknown argument {arg:?}\n{USAGE}")).into());
+        }
+        format = match args.next().as_deref() {
+            Some("json") => Format::Json,
+            Some("id") => Format::Id,
+            Some("name") => Format::Name,
+            _ => return Err(invalid(format!("--format takes json, id or name\n{USAGE}")).into()),
+        };
+    }
+
+    tracing::info!(?format, "starting picker");
+    UI_ON_STDERR.store(true, Ordering::Relaxed);
+    install_crash_handlers();
+    enable_raw_mode()?;
+    execute!(
+        io::stderr(),
+        EnterAlternateScreen,
+        EnableBracketedPaste,
+        EnableMouseCapture
+    )?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stderr()))?;
+    let mut app = App::new();
+    app.picking = true;
+    app.no_color = no_color_requested();
+    let result = app.run(&mut terminal, next_terminal_event);
+    restore_terminal();
+    result?;
+
+    let Some(item) = app.picked else {
+        std::process::exit(CANCELLED);
+    };
+    writeln!(io::stdout(), "{}", output(format, &item)?)?;
+    Ok(())
+}
+
+pub fn output(format: Format, item: &Data) -> serde_json::Result<String> {
+    Ok(match format {
+        Format::Json => serde_json::to_string(item)?,
+        Format::Id => item.created.clone(),
+        Format::Name => item.name.clone(),
+    })
+}
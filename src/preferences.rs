@@ -0,0 +1,331 @@
+use crate::density::Density;
+use crate::icons::{IconOverrides, IconSet};
+use crate::serde_compat::or_default;
+use crate::timestamps::DateFormat;
+use crate::{Progress, JSON_FILE_PATH};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+
+const PREFERENCES_FILE_PATH: &str = "preferences.json";
+
+/// User preferences stored next to the data file. Every field has a default so
+/// a partial (or missing) file still loads.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Preferences {
+    pub data_dir: Option<PathBuf>, // Holds `data.json` and `lists/`, the working directory if unset
+    pub data_file: Option<PathBuf>, // The main list's file in `data_dir`, a `.md` one is a checklist
+    pub theme: usize,               // The color palette the app starts with
+    pub lock_color: bool,           // Ignore the keys that switch the color palette
+    #[serde(deserialize_with = "or_default")]
+    pub icon_set: IconSet,
+    pub icons: IconOverrides,
+    pub ascii_only: bool, // Plain ASCII borders and symbols too, implies the `Ascii` icon set
+    #[serde(deserialize_with = "or_default")]
+    pub density: Density,
+    pub column_widths: ColumnWidths,
+    pub show_age: bool, // An Age column with the time since each todo was created
+    pub strike_done: bool, // Done todos' names dimmed and struck through
+    pub relative_dates: bool, // The Created column as `3d ago` instead of a timestamp
+    pub confirm_delete: bool, // Deleting a todo takes a second press
+    pub show_clock: bool, // A header with the date, time and todos due today
+    #[serde(deserialize_with = "or_default")]
+    pub new_status: Progress, // The status todos are added with
+    pub field_columns: Vec<String>, // User-defined fields shown as columns, e.g. `ticket`
+    #[serde(deserialize_with = "or_default")]
+    pub layout: ViewLayout,
+    pub split_ratio: u16, // Percentage of the width given to the table in the split layout
+    #[serde(deserialize_with = "or_default")]
+    pub swimlanes: Swimlanes,
+    #[serde(deserialize_with = "or_default")]
+    pub random_pick: RandomPick,
+    pub stale_days: StaleDays,
+    pub reminders: Reminders,
+    pub timezone: Option<String>, // `UTC` or an offset like `+02:00` to show timestamps in
+    #[serde(deserialize_with = "or_default")]
+    pub date_format: DateFormat,
+    #[serde(deserialize_with = "or_default")]
+    pub week_start: WeekStart,
+    pub skip_weekends: bool, // Postponing never lands on a Saturday or Sunday
+    pub holidays: Vec<String>, // Days postponing skips, `YYYY-MM-DD` or `MM-DD` for every year
+    pub group_by_date: bool, // Group the table under Today, Tomorrow, This week and Later headers
+    #[serde(deserialize_with = "or_default")]
+    pub sort: SortMode,
+    pub daily_goal: u32, // Todos to complete each day for the streak, 0 for no goal
+    pub status_keys: String, // Digits setting Waiting, In Progress and Done, empty to switch lists
+    pub rules: Vec<String>, // Conditional formatting, see `rules::Rule`
+    pub quick_actions: Vec<QuickAction>,
+    pub show_sidebar: bool,
+    pub remote: Option<Remote>, // Keep the list files on a server, with the local files as a cache
+    pub jira: Option<Jira>,     // Where `todo-tui jira` fetches issues from
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>, // Settings from newer versions
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            data_dir: None,
+            data_file: None,
+            theme: 0,
+            lock_color: false,
+            icon_set: IconSet::default(),
+            ascii_only: false,
+            icons: IconOverrides::default(),
+            density: Density::default(),
+            column_widths: ColumnWidths::default(),
+            show_age: false,
+            strike_done: true,
+            relative_dates: false,
+            confirm_delete: false,
+            show_clock: false,
+            new_status: Progress::default(),
+            field_columns: Vec::new(),
+            layout: ViewLayout::default(),
+            split_ratio: 60,
+            swimlanes: Swimlanes::default(),
+            random_pick: RandomPick::default(),
+            stale_days: StaleDays::default(),
+            reminders: Reminders::default(),
+            timezone: None,
+            date_format: DateFormat::default(),
+            week_start: WeekStart::default(),
+            skip_weekends: false,
+            holidays: Vec::new(),
+            group_by_date: false,
+            sort: SortMode::default(),
+            daily_goal: 0,
+            status_keys: "123".to_string(),
+            rules: Vec::new(),
+            quick_actions: Vec::new(),
+            show_sidebar: false,
+            remote: None,
+            jira: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+/// A remote copy of the list files, see `storage::Storage`.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum Remote {
+    WebDav {
+        url: String, // The collection holding the files
+        username: String,
+        password: String,
+    },
+    S3 {
+        endpoint: String, // e.g. `https://s3.eu-west-1.amazonaws.com` or a MinIO server
+        bucket: String,
+        region: String,
+        access_key: String,
+        secret_key: String,
+    },
+}
+
+/// Steps run together on the selected todo from a key or the palette, see
+/// `quick_actions::Step` for what they can be.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuickAction {
+    pub name: String,
+    pub key: Option<char>, // Takes over the key from the built-in action, if any
+    pub steps: Vec<String>,
+}
+
+/// A Jira site to import issues from, see `jira::fetch`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Jira {
+    pub url: String,           // e.g. `https://example.atlassian.net`
+    pub email: Option<String>, // Jira Cloud signs in with the email and an API token
+    pub token: String,         // Without an email, sent as a Data Center personal access token
+    pub jql: Option<String>,   // The issues to import, your open assigned ones if unset
+}
+
+/// Age thresholds (in days) after which open tasks are flagged as stale.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct StaleDays {
+    pub warning: i64,
+    pub alert: i64,
+}
+
+impl Default for StaleDays {
+    fn default() -> Self {
+        Self {
+            warning: 7,
+            alert: 30,
+        }
+    }
+}
+
+/// When open tasks with a due date are reminded of, in days before the due
+/// date by priority, and whether reminders also go to the desktop.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct Reminders {
+    pub enabled: bool,
+    pub high: i64,
+    pub medium: i64,
+    pub low: i64,
+    pub none: i64, // Tasks without a priority
+    pub desktop: bool,
+}
+
+impl Default for Reminders {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            high: 3,
+            medium: 1,
+            low: 0,
+            none: 0,
+            desktop: false,
+        }
+    }
+}
+
+/// How the random task picker weighs open tasks.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RandomPick {
+    #[default]
+    Uniform,
+    Age, // Older tasks are proportionally more likely to be picked
+}
+
+/// The first day of the week, for the calendar and the This week group.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    /// Days from the start of the week to `date`, 0 to 6.
+    pub fn day_of_week(self, date: NaiveDate) -> u32 {
+        match self {
+            Self::Monday => date.weekday().num_days_from_monday(),
+            Self::Sunday => date.weekday().num_days_from_sunday(),
+        }
+    }
+}
+
+/// The order of the todos, cycled with `Shift+O`. Starred todos stay on top in
+/// every one of them.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    #[default]
+    Manual, // As stored, reordered by dragging
+    Created,  // Newest first
+    Due,      // Soonest first, todos without a due date last
+    Priority, // Highest first
+    Urgency,  // See `Data::urgency`
+    Name,
+}
+
+impl SortMode {
+    const ALL: [Self; 6] = [
+        Self::Manual,
+        Self::Created,
+        Self::Due,
+        Self::Priority,
+        Self::Urgency,
+        Self::Name,
+    ];
+
+    pub fn next(self) -> Self {
+        let position = Self::ALL.iter().position(|mode| *mode == self).unwrap_or(0);
+        Self::ALL[(position + 1) % Self::ALL.len()]
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Created => "created",
+            Self::Due => "due",
+            Self::Priority => "priority",
+            Self::Urgency => "urgency",
+            Self::Name => "name",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ViewLayout {
+    #[default]
+    Table,
+    Split,
+    Kanban,
+}
+
+/// How the kanban board is split into horizontal lanes.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub enum Swimlanes {
+    #[default]
+    Off,
+    Tags,           // A lane per tag, by each task's first tag
+    Prefix(String), // A lane per tag starting with the prefix, e.g. `project-`
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct ColumnWidths {
+    pub name: u16,
+    pub description: u16,
+}
+
+impl ColumnWidths {
+    pub const MIN: u16 = 8;
+    pub const MAX: u16 = 120;
+}
+
+impl Default for ColumnWidths {
+    fn default() -> Self {
+        Self {
+            name: 22,
+            description: 42,
+        }
+    }
+}
+
+impl Preferences {
+    /// Whether the preferences were ever saved, i.e. this isn't the first run.
+    pub fn exist() -> bool {
+        Path::new(PREFERENCES_FILE_PATH).exists()
+    }
+
+    /// The folder list files are read from and written to.
+    pub fn data_dir(&self) -> &Path {
+        self.data_dir.as_deref().unwrap_or(Path::new(""))
+    }
+
+    /// The main list's file, `data.json` unless `data_file` is set.
+    pub fn data_path(&self) -> PathBuf {
+        self.data_dir().join(
+            self.data_file
+                .as_deref()
+                .unwrap_or(Path::new(JSON_FILE_PATH)),
+        )
+    }
+
+    /// Defaults when there is no file yet; a file that can't be read is an
+    /// error, so it isn't silently overwritten.
+    pub fn load() -> Result<Self, String> {
+        let file = match File::open(PREFERENCES_FILE_PATH) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(format!("Couldn't open {PREFERENCES_FILE_PATH}: {e}")),
+        };
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| format!("Couldn't read {PREFERENCES_FILE_PATH}, using defaults: {e}"))
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let file = File::create(PREFERENCES_FILE_PATH)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+}
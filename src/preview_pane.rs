@@ -0,0 +1,182 @@
+use crate::markdown;
+use crate::timestamps::Clock;
+use crate::{Data, Progress};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::{Color, Line, Modifier, Span, Style, Stylize, Text};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Widget, Wrap};
+
+/// The fields the focused pane edits, in the order it shows them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewField {
+    #[default]
+    Status,
+    Priority,
+    Due,
+    Remind,
+    Tags,
+    Description,
+}
+
+impl PreviewField {
+    pub const ALL: [Self; 6] = [
+        Self::Status,
+        Self::Priority,
+        Self::Due,
+        Self::Remind,
+        Self::Tags,
+        Self::Description,
+    ];
+
+    /// Whether it's typed in, rather than cycled with `←` / `→`.
+    pub const fn is_text(self) -> bool {
+        matches!(
+            self,
+            Self::Due | Self::Remind | Self::Tags | Self::Description
+        )
+    }
+}
+
+pub struct PreviewPane<'a> {
+    pub item: Option<&'a Data>,
+    pub logged: u64, // Seconds spent on it in focus mode
+    pub clock: Clock,
+    pub scroll: u16,
+    pub focused: bool,
+    pub field: PreviewField,      // Highlighted while focused
+    pub editing: Option<&'a str>, // The text typed so far into the field
+    pub blockers: Vec<&'a str>,   // The open todos it waits on
+    pub style: Style,
+}
+
+impl PreviewPane<'_> {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        let border_color = if self.focused {
+            selected_style_fg
+        } else {
+            Color::White
+        };
+        let block = Block::new()
+            .title("Preview")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(border_color));
+
+        let Some(item) = self.item else {
+            Paragraph::new(Line::from("Nothing selected").italic())
+                .centered()
+                .style(self.style)
+                .block(block)
+                .render(area, buf);
+            return;
+        };
+
+        let none = || Span::from("None").fg(Color::DarkGray);
+        let (progress_color, progress_text) = item.progress.display();
+        let priority = item.priority.map_or_else(none, |priority| {
+            let (priority_color, priority_text) = priority.display();
+            Span::styled(priority_text, Style::new().fg(priority_color))
+        });
+        let due = item.due.clone().map_or_else(none, Span::from);
+        let remind = if item.remind.is_empty() {
+            none()
+        } else {
+            Span::from(item.remind.join(", "))
+        };
+        let tags = item.tags();
+        let tags = if tags.is_empty() {
+            none()
+        } else {
+            Span::from(
+                tags.iter()
+                    .map(|tag| format!("#{tag}"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        };
+
+        let mut text = Text::from(Line::from(item.name.clone()).bold().fg(selected_style_fg));
+        let fields = [
+            (
+                PreviewField::Status,
+                "Progress: ",
+                Span::styled(progress_text, Style::new().fg(progress_color)),
+            ),
+            (PreviewField::Priority, "Priority: ", priority),
+            (PreviewField::Due, "Due: ", due),
+            (PreviewField::Remind, "Remind: ", remind),
+            (PreviewField::Tags, "Tags: ", tags),
+        ];
+        for (field, label, value) in fields {
+            let value = match self.editing {
+                Some(input) if field == self.field => Span::from(format!("{input}_")),
+                _ => value,
+            };
+            text.push_line(self.highlight(
+                field,
+                Line::from(vec![label.into(), value]),
+                selected_style_fg,
+            ));
+        }
+        if !self.blockers.is_empty() && item.progress != Progress::Done {
+            text.push_line(
+                Line::from(format!("Blocked by {}", self.blockers.join(", ")))
+                    .fg(Color::Red)
+                    .bold(),
+            );
+        }
+        if let Some(effort) = item.effort(self.logged) {
+            text.push_line(Line::from(format!("Effort: {effort}")));
+        }
+        text.push_line(Line::from(format!(
+            "Created: {}",
+            self.clock.display(&item.created)
+        )));
+        text.push_line(Line::from(""));
+        if self.focused {
+            let label = Line::from("Description").italic();
+            text.push_line(self.highlight(PreviewField::Description, label, selected_style_fg));
+        }
+        match self.editing {
+            Some(input) if self.field == PreviewField::Description => {
+                text.extend(Text::from(format!("{input}_")));
+            }
+            _ => text.extend(markdown::to_text(&item.description)),
+        }
+        if !item.notes.is_empty() {
+            text.push_line(Line::from(""));
+            text.push_line(Line::from("Notes").italic());
+            for note in &item.notes {
+                text.push_line(Line::from(vec![
+                    Span::from(self.clock.display(&note.at)).fg(Color::DarkGray),
+                    Span::from(format!("  {}", note.text)),
+                ]));
+            }
+        }
+
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .style(self.style)
+            .block(block)
+            .render(area, buf);
+    }
+
+    /// Marks the line of the field the keys would edit.
+    fn highlight<'l>(
+        &self,
+        field: PreviewField,
+        line: Line<'l>,
+        selected_style_fg: Color,
+    ) -> Line<'l> {
+        if self.focused && field == self.field {
+            line.style(
+                Style::new()
+                    .fg(selected_style_fg)
+                    .add_modifier(Modifier::REVERSED),
+            )
+        } else {
+            line
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::{Color, Style, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+/// A single-line text input, used wherever the full create popup is overkill.
+#[derive(Default)]
+pub struct PromptPopup {
+    pub title: String,
+    pub value: String,
+    pub style: Style,
+}
+
+impl PromptPopup {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        Clear.render(area, buf);
+
+        Paragraph::new(Text::from(self.value))
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title(self.title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(selected_style_fg)),
+            )
+            .render(area, buf);
+    }
+}
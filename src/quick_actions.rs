@@ -0,0 +1,141 @@
+//! The steps of user-defined quick actions, e.g. `priority high`, `tag urgent`
+//! and `due today`, applied together to the selected todo.
+
+use crate::workdays::Workdays;
+use crate::{format_due, Data, Priority, Progress, DUE_FORMAT};
+use chrono::{Days, NaiveDate};
+
+pub const STEPS: &str = "status <waiting|in-progress|done> | priority <low|medium|high|none> | tag <name> | untag <name> | due <today|tomorrow|+N|next-business-day|YYYY-MM-DD|none> | star | unstar | percent <0-100>";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    Status(Progress),
+    Priority(Option<Priority>),
+    Tag(String),
+    Untag(String),
+    Due(Option<Due>),
+    Star(bool),
+    Percent(u8),
+}
+
+/// A due date, relative ones counted from the day the action runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Due {
+    InDays(u64),
+    NextBusinessDay,
+    On(NaiveDate),
+}
+
+impl Step {
+    pub fn parse(step: &str) -> Result<Self, String> {
+        let (name, argument) = step.trim().split_once(' ').unwrap_or((step.trim(), ""));
+        let argument = argument.trim();
+        let step = match name {
+            "status" => Self::Status(
+                Progress::parse(argument)
+                    .ok_or("Unknown status, use waiting, in-progress or done")?,
+            ),
+            "priority" if argument == "none" => Self::Priority(None),
+            "priority" => Self::Priority(Some(
+                Priority::parse(argument)
+                    .ok_or("Unknown priority, use low, medium, high or none")?,
+            )),
+            "tag" | "untag" => {
+                let tag = argument.trim_start_matches('#').to_lowercase();
+                if tag.is_empty() || tag.contains(char::is_whitespace) {
+                    return Err(format!("\"{name}\" takes one tag, e.g. {name} urgent"));
+                }
+                if name == "tag" {
+                    Self::Tag(tag)
+                } else {
+                    Self::Untag(tag)
+                }
+            }
+            "due" => Self::Due(parse_due(argument)?),
+            "star" => Self::Star(true),
+            "unstar" => Self::Star(false),
+            "percent" => Self::Percent(
+                argument
+                    .parse()
+                    .ok()
+                    .filter(|percent| *percent <= 100)
+                    .ok_or("The percent is a number from 0 to 100")?,
+            ),
+            _ => return Err(format!("Unknown step \"{name}\", use {STEPS}")),
+        };
+        Ok(step)
+    }
+
+    pub fn apply(&self, item: &mut Data, today: NaiveDate, workdays: &Workdays) {
+        match self {
+            Self::Status(progress) => item.progress = progress.clone(),
+            Self::Priority(priority) => item.priority = *priority,
+            Self::Tag(tag) => {
+                if !item.tags().contains(tag) {
+                    if !item.description.is_empty() {
+                        item.description.push(' ');
+                    }
+                    item.description.push_str(&format!("#{tag}"));
+                }
+            }
+            Self::Untag(tag) => {
+                item.name = without_tag(&item.name, tag);
+                item.description = without_tag(&item.description, tag);
+            }
+            Self::Due(due) => {
+                let date = due.map(|due| match due {
+                    Due::InDays(days) => today + Days::new(days),
+                    Due::NextBusinessDay => workdays.next_business_day(today),
+                    Due::On(date) => date,
+                });
+                // Moving the date keeps the time of day
+                item.due = date.map(|date| format_due(date, item.due_time()));
+            }
+            Self::Star(starred) => item.starred = *starred,
+            Self::Percent(percent) => item.percent = Some(*percent),
+        }
+    }
+}
+
+/// Parses every step, failing on the first invalid one so nothing is half applied.
+pub fn parse_steps(steps: &[String]) -> Result<Vec<Step>, String> {
+    steps.iter().map(|step| Step::parse(step)).collect()
+}
+
+fn parse_due(due: &str) -> Result<Option<Due>, String> {
+    let due = match due {
+        "none" => return Ok(None),
+        "today" => Due::InDays(0),
+        "tomorrow" => Due::InDays(1),
+        "next-business-day" | "workday" => Due::NextBusinessDay,
+        _ => match due.strip_prefix('+') {
+            Some(days) => Due::InDays(days.parse().map_err(|_| "Use +N for N days from now")?),
+            None => Due::On(NaiveDate::parse_from_str(due, DUE_FORMAT).map_err(|_| {
+                "Use today, tomorrow, +N, next-business-day, a YYYY-MM-DD date or none"
+            })?),
+        },
+    };
+    Ok(Some(due))
+}
+
+/// `text` without the `#tag` words, keeping its lines.
+fn without_tag(text: &str, tag: &str) -> String {
+    let is_tag = |word: &str| {
+        word.strip_prefix('#').is_some_and(|word| {
+            word.trim_end_matches(|c: char| !c.is_alphanumeric())
+                .eq_ignore_ascii_case(tag)
+        })
+    };
+    if !text.split_whitespace().any(is_tag) {
+        return text.to_string();
+    }
+    text.lines()
+        .map(|line| {
+            line.split_whitespace()
+                .filter(|word| !is_tag(word))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
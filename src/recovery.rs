@@ -0,0 +1,37 @@
+use crate::Data;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const RECOVERY_FILE_PATH: &str = "recovery.json";
+
+/// State that isn't in the list files yet, written to `recovery.json` while it
+/// exists so a crash or kill doesn't lose it.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Recovery {
+    pub list: PathBuf,
+    pub draft_name: String,
+    pub draft_description: String,
+    pub editing_index: Option<usize>,
+    #[serde(default)]
+    pub unsaved_items: Option<Vec<Data>>, // Set when saving the list failed
+}
+
+/// The recovery file left behind by a session that didn't exit cleanly.
+pub fn load() -> Option<Recovery> {
+    let contents = fs::read_to_string(RECOVERY_FILE_PATH).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Writes the already serialized recovery state, or removes the file when there
+/// is nothing to recover.
+pub fn write(contents: Option<&str>) -> io::Result<()> {
+    match contents {
+        Some(contents) => fs::write(RECOVERY_FILE_PATH, contents),
+        None => match fs::remove_file(RECOVERY_FILE_PATH) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        },
+    }
+}
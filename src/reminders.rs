@@ -0,0 +1,171 @@
+use crate::preferences::Reminders;
+use crate::{stats, Data, Priority, Progress, DUE_FORMAT, DUE_TIME_FORMAT};
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use std::process::Command;
+
+pub const REMIND_FORMAT: &str = "%Y-%m-%d %H:%M"; // How a todo's own reminders are stored
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Days before the due date that reminders start, by priority.
+fn lead_days(reminders: &Reminders, priority: Option<Priority>) -> i64 {
+    match priority {
+        Some(Priority::High) => reminders.high,
+        Some(Priority::Medium) => reminders.medium,
+        Some(Priority::Low) => reminders.low,
+        None => reminders.none,
+    }
+}
+
+/// Identifies one reminder, so a task whose due date moves is reminded again.
+pub fn key(item: &Data) -> String {
+    format!(
+        "{}|{}",
+        item.created,
+        item.due.as_deref().unwrap_or_default()
+    )
+}
+
+/// The reminder for an open task whose due date is within its lead time of
+/// `now`, or already past. One due at a time of day is overdue once it passes.
+pub fn reminder(item: &Data, now: NaiveDateTime, reminders: &Reminders) -> Option<String> {
+    if item.progress == Progress::Done {
+        return None;
+    }
+    let due = item.due_date()?;
+    let time = item.due_time();
+    let days_left = (due - now.date()).num_days();
+    if days_left > lead_days(reminders, item.priority) {
+        return None;
+    }
+
+    let at = time
+        .map(|time| format!(" at {}", time.format(DUE_TIME_FORMAT)))
+        .unwrap_or_default();
+    let when = match days_left {
+        0 if time.is_some_and(|time| time <= now.time()) => format!("Overdue since today{at}"),
+        ..=-1 => format!("Overdue since {due}{at}"),
+        0 => format!("Due today{at}"),
+        1 => format!("Due tomorrow{at}"),
+        days => format!("Due in {days} days"),
+    };
+    Some(format!("{when}: {}", item.name))
+}
+
+/// Identifies one of a todo's own reminders, apart from its due date's.
+pub fn own_key(item: &Data, at: &str) -> String {
+    format!("{}|remind|{at}", item.created)
+}
+
+/// The todo's own reminders whose time has come, as stored; none once it's
+/// done.
+pub fn own_reminders(item: &Data, now: NaiveDateTime) -> Vec<&str> {
+    if item.progress == Progress::Done {
+        return Vec::new();
+    }
+    item.remind
+        .iter()
+        .filter(|at| NaiveDateTime::parse_from_str(at, REMIND_FORMAT).is_ok_and(|at| at <= now))
+        .map(String::as_str)
+        .collect()
+}
+
+/// Parses a reminder time counted from `now`, like `fri 9am`, `tomorrow
+/// 14:30`, `2024-03-01 09:00`, `17:00` or `in 2h`. A day alone is at 9:00; a
+/// time alone is today, or tomorrow once it has passed; a weekday is the next
+/// one, today included while the time is still ahead.
+pub fn parse_time(text: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let text = text.trim().to_lowercase();
+    if let Some(duration) = text.strip_prefix("in ") {
+        let seconds = i64::try_from(stats::parse_duration(duration)?).ok()?;
+        return Some(now + TimeDelta::try_seconds(seconds)?);
+    }
+
+    let today = now.date();
+    let (mut date, mut weekday, mut time) = (None, None, None);
+    for word in text.split_whitespace() {
+        match word {
+            "at" | "on" | "next" => {}
+            "today" => date = Some(today),
+            "tomorrow" => date = Some(today + Days::new(1)),
+            _ => {
+                if let Some(clock) = parse_clock(word) {
+                    time = Some(clock);
+                } else if let Some(day) = WEEKDAYS
+                    .iter()
+                    .position(|day| word.len() >= 3 && day.starts_with(word))
+                {
+                    weekday = Weekday::try_from(u8::try_from(day).ok()?).ok();
+                } else {
+                    date = Some(NaiveDate::parse_from_str(word, DUE_FORMAT).ok()?);
+                }
+            }
+        }
+    }
+    if date.is_none() && weekday.is_none() && time.is_none() {
+        return None;
+    }
+
+    let time = time.unwrap_or(NaiveTime::from_hms_opt(9, 0, 0)?);
+    let still_today = time > now.time();
+    let date = match (date, weekday) {
+        (Some(date), _) => date,
+        (None, Some(weekday)) => {
+            let mut days =
+                (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+            if days == 0 && !still_today {
+                days = 7;
+            }
+            today + Days::new(u64::from(days))
+        }
+        (None, None) if still_today => today,
+        (None, None) => today + Days::new(1),
+    };
+    Some(date.and_time(time))
+}
+
+/// `9am`, `9:30pm` or `14:30`.
+fn parse_clock(word: &str) -> Option<NaiveTime> {
+    let (clock, pm) = match word.strip_suffix("am") {
+        Some(clock) => (clock, Some(false)),
+        None => match word.strip_suffix("pm") {
+            Some(clock) => (clock, Some(true)),
+            None => (word, None),
+        },
+    };
+    let (hour, minute) = clock.split_once(':').unwrap_or((clock, "0"));
+    let (mut hour, minute): (u32, u32) = (hour.parse().ok()?, minute.parse().ok()?);
+    match pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(pm) => hour = hour % 12 + if pm { 12 } else { 0 },
+        // Bare numbers are days of nothing, so a time needs its minutes
+        None if !clock.contains(':') => return None,
+        None => {}
+    }
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Shows a desktop notification with `notify-send` or, on macOS, `osascript`.
+/// Runs in the background and only logs failures, as the toast is shown anyway.
+pub fn notify_desktop(message: String) {
+    std::thread::spawn(move || {
+        let status = if cfg!(target_os = "macos") {
+            let script = format!("display notification {:?} with title \"Todo-TUI\"", message);
+            Command::new("osascript").args(["-e", &script]).status()
+        } else {
+            Command::new("notify-send")
+                .args(["Todo-TUI", &message])
+                .status()
+        };
+        if let Err(e) = status {
+            tracing::warn!("couldn't show a desktop notification: {e}");
+        }
+    });
+}
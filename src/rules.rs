@@ -0,0 +1,223 @@
+use crate::{Data, Priority, Progress};
+use ratatui::style::{Color, Modifier, Style};
+use std::str::FromStr;
+
+/// A conditional formatting rule from the preferences file, written as
+/// `<condition> -> <style>`, e.g. `tag:work -> blue` or `status:done -> dim`.
+///
+/// Conditions: `tag:<name>`, `status:<waiting|in_progress|done>`,
+/// `priority:<low|medium|high>`, `starred`, `text:<substring>` and `age><days>`,
+/// combined with `&`.
+/// Styles: any mix of colors (`red`, `lightblue`, `#ff8800`), `on <color>` for the
+/// background, and `bold`, `dim`, `italic`, `underlined`, `reversed`, `crossed_out`.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    conditions: Vec<Condition>,
+    style: Style,
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    Tag(String),
+    Status(Progress),
+    Priority(Priority),
+    Starred,
+    Text(String),
+    OlderThan(i64),
+}
+
+impl Condition {
+    fn parse(condition: &str) -> Option<Self> {
+        let condition = condition.trim();
+        if condition == "starred" {
+            return Some(Self::Starred);
+        }
+        if let Some(days) = condition.strip_prefix("age>") {
+            return days.trim().parse().ok().map(Self::OlderThan);
+        }
+
+        let (key, value) = condition.split_once(':')?;
+        let value = value.trim().to_lowercase();
+        match key.trim() {
+            "tag" => Some(Self::Tag(value.trim_start_matches('#').to_string())),
+            "text" => Some(Self::Text(value)),
+            "status" => Progress::parse(&value).map(Self::Status),
+            "priority" => Priority::parse(&value).map(Self::Priority),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, item: &Data) -> bool {
+        match self {
+            Self::Tag(tag) => item.tags().contains(tag),
+            Self::Status(progress) => item.progress == *progress,
+            Self::Priority(priority) => item.priority == Some(*priority),
+            Self::Starred => item.starred,
+            Self::Text(text) => {
+                item.name.to_lowercase().contains(text)
+                    || item.description.to_lowercase().contains(text)
+            }
+            Self::OlderThan(days) => item.age_days().is_some_and(|age| age > *days),
+        }
+    }
+}
+
+fn parse_style(style: &str) -> Option<Style> {
+    let mut result = Style::default();
+    let mut words = style.split_whitespace();
+
+    while let Some(word) = words.next() {
+        result = match word.to_lowercase().as_str() {
+            "bold" => result.add_modifier(Modifier::BOLD),
+            "dim" => result.add_modifier(Modifier::DIM),
+            "italic" => result.add_modifier(Modifier::ITALIC),
+            "underlined" => result.add_modifier(Modifier::UNDERLINED),
+            "reversed" => result.add_modifier(Modifier::REVERSED),
+            "crossed_out" => result.add_modifier(Modifier::CROSSED_OUT),
+            "on" => result.bg(Color::from_str(words.next()?).ok()?),
+            color => result.fg(Color::from_str(color).ok()?),
+        };
+    }
+
+    Some(result)
+}
+
+impl Rule {
+    pub fn parse(rule: &str) -> Option<Self> {
+        let (conditions, style) = rule.split_once("->")?;
+        Some(Self {
+            conditions: conditions
+                .split('&')
+                .map(Condition::parse)
+                .collect::<Option<_>>()?,
+            style: parse_style(style)?,
+        })
+    }
+
+    fn matches(&self, item: &Data) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.matches(item))
+    }
+}
+
+/// Parses every rule, skipping the ones that aren't valid.
+pub fn parse_rules(rules: &[String]) -> Vec<Rule> {
+    rules.iter().filter_map(|rule| Rule::parse(rule)).collect()
+}
+
+/// The combined style of every rule matching `item`, later rules winning.
+pub fn style_for(rules: &[Rule], item: &Data) -> Style {
+    rules
+        .iter()
+        .filter(|rule| rule.matches(item))
+        .fold(Style::default(), |style, rule| style.patch(rule.style))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Days, SecondsFormat, Utc};
+
+    fn task(name: &str, description: &str) -> Data {
+        Data {
+            name: name.to_string(),
+            description: description.to_string(),
+            created: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            ..Data::default()
+        }
+    }
+
+    fn rule(rule: &str) -> Rule {
+        Rule::parse(rule).expect("valid rule")
+    }
+
+    #[test]
+    fn each_condition_matches_its_todos() {
+        let mut item = task("Call the Bank", "About the loan #work");
+        assert!(rule("tag:work -> red").matches(&item));
+        assert!(rule("tag:#Work -> red").matches(&item));
+        assert!(!rule("tag:home -> red").matches(&item));
+        assert!(rule("text:bank -> red").matches(&item));
+        assert!(rule("text:LOAN -> red").matches(&item));
+        assert!(!rule("text:mortgage -> red").matches(&item));
+        assert!(rule("status:waiting -> red").matches(&item));
+        assert!(!rule("status:done -> red").matches(&item));
+        assert!(!rule("priority:high -> red").matches(&item));
+        assert!(!rule("starred -> red").matches(&item));
+        assert!(!rule("age>7 -> red").matches(&item));
+
+        item.progress = Progress::Done;
+        item.priority = Some(Priority::High);
+        item.starred = true;
+        item.created = (Utc::now() - Days::new(10)).to_rfc3339_opts(SecondsFormat::Secs, true);
+        assert!(rule("status:done -> red").matches(&item));
+        assert!(rule("priority:high -> red").matches(&item));
+        assert!(rule("starred -> red").matches(&item));
+        assert!(rule("age>7 -> red").matches(&item));
+        assert!(!rule("age>10 -> red").matches(&item));
+    }
+
+    #[test]
+    fn combined_conditions_all_have_to_match() {
+        let mut item = task("Write report", "#work");
+        let work_and_starred = rule("tag:work & starred -> bold");
+        assert!(!work_and_starred.matches(&item));
+        item.starred = true;
+        assert!(work_and_starred.matches(&item));
+    }
+
+    #[test]
+    fn styles_mix_colors_backgrounds_and_modifiers() {
+        assert_eq!(
+            rule("starred -> lightblue on #ff8800 bold crossed_out").style,
+            Style::new()
+                .fg(Color::LightBlue)
+                .bg(Color::Rgb(0xff, 0x88, 0x00))
+                .add_modifier(Modifier::BOLD | Modifier::CROSSED_OUT)
+        );
+        assert_eq!(
+            rule("starred -> dim italic underlined reversed").style,
+            Style::new().add_modifier(
+                Modifier::DIM | Modifier::ITALIC | Modifier::UNDERLINED | Modifier::REVERSED
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_rules_are_skipped() {
+        let rules = parse_rules(&[
+            "tag:work -> blue".to_string(),
+            "tag:work blue".to_string(),
+            "owner:sam -> blue".to_string(),
+            "status:someday -> blue".to_string(),
+            "age>soon -> blue".to_string(),
+            "starred -> sparkly".to_string(),
+            "starred -> on".to_string(),
+        ]);
+        assert_eq!(rules.len(), 1);
+    }
+
+    #[test]
+    fn every_matching_rule_applies_and_later_ones_win() {
+        let rules = parse_rules(&[
+            "tag:work -> blue bold".to_string(),
+            "status:done -> dim".to_string(),
+            "starred -> red".to_string(),
+        ]);
+        let mut item = task("Write report", "#work");
+        assert_eq!(
+            style_for(&rules, &item),
+            Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD)
+        );
+        item.progress = Progress::Done;
+        item.starred = true;
+        assert_eq!(
+            style_for(&rules, &item),
+            Style::new()
+                .fg(Color::Red)
+                .add_modifier(Modifier::BOLD | Modifier::DIM)
+        );
+        assert_eq!(style_for(&rules, &task("Relax", "")), Style::default());
+    }
+}
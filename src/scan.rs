@@ -0,0 +1,182 @@
+//! `todo-tui scan <dir>`: imports the TODO and FIXME comments in a source
+//! tree into their own list, `lists/code.json`, with the file and line as the
+//! `source` field. Scanning again follows comments that moved, adds new ones
+//! and marks the todos of comments that are gone as done.
+
+use crate::preferences::Preferences;
+use crate::{read_list, save_list, Data, Progress, NAME_LIMIT};
+use chrono::{SecondsFormat, Utc};
+use regex::Regex;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+const USAGE: &str = "usage: todo-tui scan [DIR]";
+const LIST_PATH: &str = "lists/code.json";
+const SOURCE_FIELD: &str = "source";
+const MAX_FILE_SIZE: u64 = 1024 * 1024; // Larger files are likely generated or data
+const SKIPPED_DIRS: [&str; 3] = ["target", "node_modules", "vendor"];
+
+/// A keyword right after a comment marker, e.g. `// TODO: retry` or
+/// `# FIXME(sam) flaky`, without a closing `*/` or `-->`.
+static COMMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?://+|#+|/\*+|--|;+|<!--|^\s*\*)\s*(TODO|FIXME)\b(?:\([^)]*\))?:?\s*(.*?)\s*(?:\*/|-->)?\s*$")
+        .expect("valid regex")
+});
+
+/// A TODO or FIXME comment found in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found {
+    pub kind: String, // `todo` or `fixme`
+    pub text: String,
+    pub file: String, // As reached from the scanned folder, e.g. `src/main.rs`
+    pub line: usize,
+}
+
+pub fn run(mut args: impl Iterator<Item = String>) -> io::Result<()> {
+    let dir = PathBuf::from(args.next().unwrap_or_else(|| ".".to_string()));
+    if let Some(arg) = args.next() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unknown argument {arg:?}\n{USAGE}"),
+        ));
+    }
+    let preferences = Preferences::load().unwrap_or_else(|e| {
+        tracing::warn!("{e}");
+        Preferences::default()
+    });
+
+    let found = scan(&dir)?;
+    let path = preferences.data_dir().join(LIST_PATH);
+    let (added, moved, resolved) = store(&path, &dir, found)?;
+    tracing::info!(added, moved, resolved, path = %path.display(), "scanned code");
+    println!(
+        "Scan: {added} added, {moved} moved, {resolved} resolved in {}",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Every comment in the files under `dir`, skipping hidden folders, build
+/// output and files that aren't text.
+pub fn scan(dir: &Path) -> io::Result<Vec<Found>> {
+    let mut found = Vec::new();
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    entries.sort();
+    for path in entries {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                found.extend(scan(&path)?);
+            }
+            continue;
+        }
+        if fs::metadata(&path).map_or(true, |metadata| metadata.len() > MAX_FILE_SIZE) {
+            continue;
+        }
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let file = path.display().to_string();
+        found.extend(contents.lines().enumerate().filter_map(|(i, line)| {
+            let captures = COMMENT.captures(line)?;
+            Some(Found {
+                kind: captures[1].to_lowercase(),
+                text: captures[2].to_string(),
+                file: file.clone(),
+                line: i + 1,
+            })
+        }));
+    }
+    Ok(found)
+}
+
+/// Merges the comments found under `dir` into the list at `path`, creating
+/// it if needed. A todo matches a comment with the same text in the same
+/// file, wherever it moved to. Returns how many were added, moved and
+/// resolved, i.e. marked done as their comment is gone.
+pub fn store(path: &Path, dir: &Path, found: Vec<Found>) -> io::Result<(usize, usize, usize)> {
+    let mut items = if path.exists() {
+        read_list(path)?
+    } else {
+        Vec::new()
+    };
+    let mut matched = vec![false; items.len()];
+    let (mut added, mut moved) = (0, 0);
+    let mut at = Utc::now();
+
+    for comment in found {
+        let name = name(&comment);
+        let source = format!("{}:{}", comment.file, comment.line);
+        let existing = items.iter().enumerate().position(|(i, item)| {
+            !matched[i]
+                && item.name == name
+                && file_of(item).is_some_and(|file| file == comment.file)
+        });
+        if let Some(index) = existing {
+            matched[index] = true;
+            let item = &mut items[index];
+            if item.fields.get(SOURCE_FIELD) != Some(&source) {
+                item.fields.insert(SOURCE_FIELD.to_string(), source);
+                moved += 1;
+            }
+            continue;
+        }
+
+        // Unique creation times, as they're what identifies todos
+        let created = loop {
+            let created = at.to_rfc3339_opts(SecondsFormat::Secs, true);
+            at += chrono::Duration::seconds(1);
+            if !items.iter().any(|item| item.created == created) {
+                break created;
+            }
+        };
+        let mut item = Data {
+            name,
+            description: format!("#{}", comment.kind),
+            created,
+            ..Data::default()
+        };
+        item.fields.insert(SOURCE_FIELD.to_string(), source);
+        items.push(item);
+        matched.push(true);
+        added += 1;
+    }
+
+    let mut resolved = 0;
+    for (item, matched) in items.iter_mut().zip(matched) {
+        let scanned = file_of(item).is_some_and(|file| Path::new(file).starts_with(dir));
+        if !matched && scanned && item.progress != Progress::Done {
+            item.progress = Progress::Done;
+            resolved += 1;
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    save_list(path, &items)?;
+    Ok((added, moved, resolved))
+}
+
+/// The comment's text, or where it is when it has none.
+fn name(comment: &Found) -> String {
+    let name = if comment.text.is_empty() {
+        format!("{} in {}", comment.kind.to_uppercase(), comment.file)
+    } else {
+        comment.text.clone()
+    };
+    name.chars().take(NAME_LIMIT).collect()
+}
+
+/// The file of a todo imported by a scan, from its `source` field.
+fn file_of(item: &Data) -> Option<&str> {
+    let source = item.fields.get(SOURCE_FIELD)?;
+    source.rsplit_once(':').map(|(file, _)| file)
+}
@@ -0,0 +1,70 @@
+use crate::date_range::DateFilter;
+use crate::preferences::WeekStart;
+use crate::timestamps::Clock;
+use crate::Data;
+use regex::{Regex, RegexBuilder};
+
+const REGEX_PREFIX: &str = "re:";
+
+/// A parsed search query. Plain queries need every whitespace-separated term to
+/// appear in the name or description, apart from `created:` and `completed:`
+/// date ranges; `re:` queries are a regular expression over either. Both are
+/// case-insensitive.
+pub enum Query {
+    Terms(Vec<String>, Vec<DateFilter>),
+    Regex(Regex),
+}
+
+impl Query {
+    /// Fails with a short, displayable message when a `re:` pattern is invalid.
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let Some(pattern) = query.strip_prefix(REGEX_PREFIX) else {
+            let mut terms = Vec::new();
+            let mut dates = Vec::new();
+            for term in query.split_whitespace() {
+                match DateFilter::parse(term) {
+                    Some(date) => dates.push(date?),
+                    None => terms.push(term.to_lowercase()),
+                }
+            }
+            return Ok(Self::Terms(terms, dates));
+        };
+
+        RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map(Self::Regex)
+            .map_err(|e| match e {
+                regex::Error::Syntax(message) => message
+                    .lines()
+                    .last()
+                    .unwrap_or("invalid pattern")
+                    .trim_start_matches("error: ")
+                    .to_string(),
+                _ => e.to_string(),
+            })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Terms(terms, dates) => terms.is_empty() && dates.is_empty(),
+            Self::Regex(regex) => regex.as_str().is_empty(),
+        }
+    }
+
+    pub fn matches(&self, item: &Data, clock: Clock, week_start: WeekStart) -> bool {
+        match self {
+            Self::Terms(terms, dates) => {
+                let name = item.name.to_lowercase();
+                let description = item.description.to_lowercase();
+                terms
+                    .iter()
+                    .all(|term| name.contains(term) || description.contains(term))
+                    && dates
+                        .iter()
+                        .all(|date| date.matches(item, clock, week_start))
+            }
+            Self::Regex(regex) => regex.is_match(&item.name) || regex.is_match(&item.description),
+        }
+    }
+}
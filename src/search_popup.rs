@@ -0,0 +1,86 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Span, Style, Stylize, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+/// One search hit, already formatted for display.
+pub struct SearchHit {
+    pub list: String,
+    pub name: String,
+    pub description: String,
+}
+
+pub struct SearchPopup {
+    pub query: String,
+    pub error: Option<String>, // Shown in the search bar when a `re:` pattern is invalid
+    pub hits: Vec<SearchHit>,
+    pub selected: usize,
+    pub style: Style,
+}
+
+impl SearchPopup {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        Clear.render(area, buf);
+
+        let [input_area, results_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(area);
+
+        let mut input_block = Block::new()
+            .title("Search all lists (re: for regex)")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(selected_style_fg));
+        if let Some(error) = self.error {
+            input_block = input_block
+                .title_bottom(Line::from(format!(" {error} ")).fg(Color::Red))
+                .border_style(Style::default().fg(Color::Red));
+        }
+
+        Paragraph::new(Text::from(self.query))
+            .style(self.style)
+            .block(input_block)
+            .render(input_area, buf);
+
+        let title = format!("Results ({})", self.hits.len());
+        let lines: Vec<Line> = self
+            .hits
+            .into_iter()
+            .enumerate()
+            .map(|(i, hit)| {
+                let description = hit.description.lines().next().unwrap_or("").to_string();
+                let line = Line::from(vec![
+                    Span::styled(format!("[{}] ", hit.list), Style::new().fg(Color::Cyan)),
+                    Span::from(hit.name),
+                    Span::from(format!("  {description}")).fg(Color::DarkGray),
+                ]);
+                if i == self.selected {
+                    line.style(
+                        Style::new()
+                            .fg(selected_style_fg)
+                            .add_modifier(Modifier::REVERSED),
+                    )
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        // Keep the selected hit in view
+        let visible = usize::from(results_area.height.saturating_sub(2));
+        let scroll = u16::try_from((self.selected + 1).saturating_sub(visible)).unwrap_or(0);
+
+        Paragraph::new(lines)
+            .scroll((scroll, 0))
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title(title)
+                    .title_bottom(
+                        Line::from(" (Tab/Shift+Tab) select | (↑/↓) history | (Enter) open ")
+                            .italic(),
+                    )
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::White)),
+            )
+            .render(results_area, buf);
+    }
+}
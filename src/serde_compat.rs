@@ -0,0 +1,14 @@
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Deserializes a field, falling back to its default when the value isn't
+/// understood, e.g. an enum variant added by a newer version. The rest of the
+/// file still loads instead of failing as a whole.
+pub fn or_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(T::deserialize(value).unwrap_or_default())
+}
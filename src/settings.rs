@@ -0,0 +1,224 @@
+//! The settings screen's options, grouped as they're shown. All but Hide
+//! Completed live in `preferences.json`; that one is part of the view state,
+//! see `view_state`.
+
+use crate::density::Density;
+use crate::onboarding::THEME_NAMES;
+use crate::preferences::{Preferences, WeekStart};
+use crate::timestamps::DateFormat;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Group {
+    Appearance,
+    Behavior,
+    Storage,
+}
+
+impl Group {
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::Appearance => "Appearance",
+            Self::Behavior => "Behavior",
+            Self::Storage => "Storage",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    Theme,
+    LockColor,
+    Density,
+    DateFormat,
+    RelativeDates,
+    ShowAge,
+    StrikeDone,
+    ShowClock,
+    HideCompleted,
+    ConfirmDelete,
+    NewStatus,
+    WeekStart,
+    SkipWeekends,
+    DataFolder,
+    DataFile,
+}
+
+/// What a setting holds, and so how it's changed: toggles and choices with
+/// `Enter` or the arrows, text by typing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Toggle(bool),
+    Choice(String),
+    Text(String),
+}
+
+impl Setting {
+    /// In the order they're shown, by group.
+    pub const ALL: [Self; 15] = [
+        Self::Theme,
+        Self::LockColor,
+        Self::Density,
+        Self::DateFormat,
+        Self::RelativeDates,
+        Self::ShowAge,
+        Self::StrikeDone,
+        Self::ShowClock,
+        Self::HideCompleted,
+        Self::ConfirmDelete,
+        Self::NewStatus,
+        Self::WeekStart,
+        Self::SkipWeekends,
+        Self::DataFolder,
+        Self::DataFile,
+    ];
+
+    pub const fn group(self) -> Group {
+        match self {
+            Self::Theme
+            | Self::LockColor
+            | Self::Density
+            | Self::DateFormat
+            | Self::RelativeDates
+            | Self::ShowAge
+            | Self::StrikeDone
+            | Self::ShowClock => Group::Appearance,
+            Self::HideCompleted
+            | Self::ConfirmDelete
+            | Self::NewStatus
+            | Self::WeekStart
+            | Self::SkipWeekends => Group::Behavior,
+            Self::DataFolder | Self::DataFile => Group::Storage,
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Theme => "Theme",
+            Self::LockColor => "Lock Color",
+            Self::Density => "Row Density",
+            Self::DateFormat => "Date Format",
+            Self::RelativeDates => "Relative Dates",
+            Self::ShowAge => "Show Age",
+            Self::StrikeDone => "Strike Done",
+            Self::ShowClock => "Show Clock",
+            Self::HideCompleted => "Hide Completed",
+            Self::ConfirmDelete => "Confirm Delete",
+            Self::NewStatus => "New Todo Status",
+            Self::WeekStart => "Week Starts On",
+            Self::SkipWeekends => "Skip Weekends",
+            Self::DataFolder => "Data Folder",
+            Self::DataFile => "Data File",
+        }
+    }
+
+    /// Shown under the list while the setting is selected.
+    pub const fn hint(self) -> &'static str {
+        match self {
+            Self::Theme => "The color palette the app starts with",
+            Self::LockColor => "Ignore the keys that cycle the color palette",
+            Self::Density => "Lines per table row",
+            Self::DateFormat => "How the Created column shows timestamps",
+            Self::RelativeDates => "The Created column as 3d ago instead of a timestamp",
+            Self::ShowAge => "An Age column with the time since each todo was created",
+            Self::StrikeDone => "Done todos' names dimmed and struck through in the table",
+            Self::ShowClock => "A header with the date, time and how many todos are due today",
+            Self::HideCompleted => "Leave done todos out of this list and layout",
+            Self::ConfirmDelete => "Deleting a todo asks to press X again",
+            Self::NewStatus => "The status todos are created with, changed in the popup as well",
+            Self::WeekStart => "For the due date calendar, grouping and date filters",
+            Self::SkipWeekends => "Postponing moves a todo on to Monday instead of the weekend",
+            Self::DataFolder => "Holds data.json and lists/, used from the next start",
+            Self::DataFile => "The main list's file in the data folder, used from the next start",
+        }
+    }
+
+    pub fn value(self, preferences: &Preferences, hide_completed: bool) -> Value {
+        let path = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default()
+        };
+        match self {
+            Self::Theme => {
+                Value::Choice(THEME_NAMES[preferences.theme % THEME_NAMES.len()].to_string())
+            }
+            Self::LockColor => Value::Toggle(preferences.lock_color),
+            Self::Density => Value::Choice(format!("{:?}", preferences.density)),
+            Self::DateFormat => Value::Choice(format!("{:?}", preferences.date_format)),
+            Self::RelativeDates => Value::Toggle(preferences.relative_dates),
+            Self::ShowAge => Value::Toggle(preferences.show_age),
+            Self::StrikeDone => Value::Toggle(preferences.strike_done),
+            Self::ShowClock => Value::Toggle(preferences.show_clock),
+            Self::HideCompleted => Value::Toggle(hide_completed),
+            Self::ConfirmDelete => Value::Toggle(preferences.confirm_delete),
+            Self::NewStatus => Value::Choice(preferences.new_status.display().1),
+            Self::WeekStart => Value::Choice(format!("{:?}", preferences.week_start)),
+            Self::SkipWeekends => Value::Toggle(preferences.skip_weekends),
+            Self::DataFolder => Value::Text(path(&preferences.data_dir)),
+            Self::DataFile => Value::Text(path(&preferences.data_file)),
+        }
+    }
+
+    /// Flips a toggle or moves a choice to its next or previous option. Text
+    /// settings are set with `set_text` instead.
+    pub fn step(self, preferences: &mut Preferences, hide_completed: &mut bool, forward: bool) {
+        match self {
+            Self::Theme => {
+                let themes: Vec<usize> = (0..THEME_NAMES.len()).collect();
+                preferences.theme = cycle(&themes, preferences.theme, forward);
+            }
+            Self::LockColor => preferences.lock_color = !preferences.lock_color,
+            Self::Density => {
+                let densities = [Density::Compact, Density::Cozy, Density::Full];
+                preferences.density = cycle(&densities, preferences.density, forward);
+            }
+            Self::DateFormat => {
+                preferences.date_format = cycle(&DateFormat::ALL, preferences.date_format, forward);
+            }
+            Self::RelativeDates => preferences.relative_dates = !preferences.relative_dates,
+            Self::ShowAge => preferences.show_age = !preferences.show_age,
+            Self::StrikeDone => preferences.strike_done = !preferences.strike_done,
+            Self::ShowClock => preferences.show_clock = !preferences.show_clock,
+            Self::HideCompleted => *hide_completed = !*hide_completed,
+            Self::ConfirmDelete => preferences.confirm_delete = !preferences.confirm_delete,
+            Self::NewStatus => preferences.new_status = preferences.new_status.cycle(forward),
+            Self::WeekStart => {
+                let starts = [WeekStart::Monday, WeekStart::Sunday];
+                preferences.week_start = cycle(&starts, preferences.week_start, forward);
+            }
+            Self::SkipWeekends => preferences.skip_weekends = !preferences.skip_weekends,
+            Self::DataFolder | Self::DataFile => {}
+        }
+    }
+
+    /// Sets a text setting, unsetting it when `text` is blank.
+    pub fn set_text(self, preferences: &mut Preferences, text: &str) {
+        let path = Some(text.trim())
+            .filter(|text| !text.is_empty())
+            .map(PathBuf::from);
+        match self {
+            Self::DataFolder => preferences.data_dir = path,
+            Self::DataFile => preferences.data_file = path,
+            _ => {}
+        }
+    }
+
+    /// Whether it's kept in `preferences.json`, so changing it saves them.
+    pub const fn is_preference(self) -> bool {
+        !matches!(self, Self::HideCompleted)
+    }
+}
+
+/// The option after (or before) `current`, wrapping around; the first one if
+/// `current` isn't among them.
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, forward: bool) -> T {
+    let count = options.len();
+    let index = options.iter().position(|&option| option == current);
+    let next = match index {
+        Some(index) if forward => (index + 1) % count,
+        Some(index) => (index + count - 1) % count,
+        None => 0,
+    };
+    options[next]
+}
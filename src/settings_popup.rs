@@ -0,0 +1,93 @@
+use crate::settings::{Group, Setting};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Span, Style, Stylize};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
+
+/// Every setting under its group with its current value, and what the
+/// selected one does.
+pub struct SettingsPopup {
+    pub entries: Vec<(Setting, String)>, // The setting and its value as shown
+    pub selected: usize,
+    pub editing: Option<String>, // The text typed so far for a text setting
+    pub style: Style,
+}
+
+impl SettingsPopup {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        Clear.render(area, buf);
+
+        let [entries_area, hint_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(4)]).areas(area);
+
+        let width = usize::from(entries_area.width.saturating_sub(4));
+        let mut lines = Vec::new();
+        let mut group: Option<Group> = None;
+        for (i, (setting, value)) in self.entries.iter().enumerate() {
+            if group != Some(setting.group()) {
+                if group.is_some() {
+                    lines.push(Line::default());
+                }
+                group = Some(setting.group());
+                lines.push(
+                    Line::from(setting.group().title())
+                        .fg(selected_style_fg)
+                        .bold(),
+                );
+            }
+
+            let selected = i == self.selected;
+            let value = match &self.editing {
+                Some(input) if selected => format!("{input}_"),
+                _ => value.clone(),
+            };
+            let gap = width.saturating_sub(setting.label().chars().count() + value.chars().count());
+            let line = Line::from(vec![
+                Span::from("  "),
+                Span::from(setting.label()),
+                Span::from(" ".repeat(gap)),
+                Span::from(value),
+            ]);
+            lines.push(if selected {
+                line.style(
+                    Style::new()
+                        .fg(selected_style_fg)
+                        .add_modifier(Modifier::REVERSED),
+                )
+            } else {
+                line
+            });
+        }
+
+        let keys = if self.editing.is_some() {
+            " (Enter) save | (Esc) cancel "
+        } else {
+            " (↑/↓) select | (Enter/←/→) change | (Esc) close "
+        };
+        Paragraph::new(lines)
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title("Settings")
+                    .title_bottom(Line::from(keys).italic())
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(selected_style_fg)),
+            )
+            .render(entries_area, buf);
+
+        let hint = self
+            .entries
+            .get(self.selected)
+            .map(|(setting, _)| setting.hint())
+            .unwrap_or_default();
+        Paragraph::new(hint)
+            .wrap(Wrap { trim: true })
+            .style(self.style)
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::White)),
+            )
+            .render(hint_area, buf);
+    }
+}
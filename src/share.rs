@@ -0,0 +1,29 @@
+//! Hands one todo to a teammate using todo-tui: `:share` writes it as a
+//! standalone JSON object, custom fields and estimates included, to a file or
+//! the clipboard, and `:import` merges such a file like any other.
+
+use crate::Data;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+pub fn to_json(item: &Data) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(item)
+}
+
+pub fn write(path: &Path, item: &Data) -> io::Result<()> {
+    fs::write(path, to_json(item)?)
+}
+
+/// Puts the todo on the clipboard with the OSC 52 escape sequence, which most
+/// terminals pass on to the system clipboard, over SSH too.
+pub fn copy(item: &Data) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(
+        stdout,
+        "\x1b]52;c;{}\x07",
+        BASE64_STANDARD.encode(to_json(item)?)
+    )?;
+    stdout.flush()
+}
@@ -0,0 +1,233 @@
+//! How many todos were completed each day and the time spent in focus mode,
+//! kept next to the main list so they cover every list and survive clearing
+//! done todos.
+
+use crate::DUE_FORMAT;
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const COMPLETIONS_FILE: &str = "completions.json";
+const TIME_LOG_FILE: &str = "time_log.json";
+/// Seconds of focus that make up one pomodoro.
+pub const POMODORO: u64 = 25 * 60;
+
+/// Completed todos per day, keyed by the date in `DUE_FORMAT`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Completions(BTreeMap<String, u32>);
+
+impl Completions {
+    /// Where the completions of the lists next to `main_list` are kept.
+    pub fn path(main_list: &Path) -> PathBuf {
+        main_list.with_file_name(COMPLETIONS_FILE)
+    }
+
+    /// Empty if the file is missing or unreadable, so stats never block the app.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Adds `completed` todos to `day`; reopened ones take them away again.
+    pub fn record(&mut self, day: NaiveDate, completed: u32, reopened: u32) {
+        let count = self
+            .0
+            .entry(day.format(DUE_FORMAT).to_string())
+            .or_default();
+        *count = (*count + completed).saturating_sub(reopened);
+    }
+
+    pub fn count(&self, day: NaiveDate) -> u32 {
+        self.0
+            .get(&day.format(DUE_FORMAT).to_string())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.0.values().sum()
+    }
+
+    /// Days in a row reaching the goal, or completing anything without one, up
+    /// to today. A today still short of it doesn't break the streak yet.
+    pub fn streak(&self, today: NaiveDate, goal: u32) -> u32 {
+        let reached = |day: NaiveDate| self.count(day) >= goal.max(1);
+        let mut day = if reached(today) {
+            today
+        } else {
+            today - Days::new(1)
+        };
+        let mut streak = 0;
+        while reached(day) {
+            streak += 1;
+            day = day - Days::new(1);
+        }
+        streak
+    }
+
+    /// The longest streak ever.
+    pub fn best_streak(&self, goal: u32) -> u32 {
+        let mut best = 0;
+        let mut current = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for (day, &count) in &self.0 {
+            let Ok(day) = NaiveDate::parse_from_str(day, DUE_FORMAT) else {
+                continue;
+            };
+            if count < goal.max(1) {
+                current = 0;
+                continue;
+            }
+            let follows = previous.is_some_and(|previous| previous + Days::new(1) == day);
+            current = if follows { current + 1 } else { 1 };
+            previous = Some(day);
+            best = best.max(current);
+        }
+        best
+    }
+}
+
+/// One stretch of focus mode on a todo.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub task: String, // The todo's `created`, which stays put when it's renamed
+    pub name: String, // The todo's name at the time
+    pub day: String,  // In `DUE_FORMAT`
+    pub seconds: u64,
+}
+
+impl Session {
+    /// Every full 25 minutes of the session.
+    pub const fn pomodoros(&self) -> u64 {
+        self.seconds / POMODORO
+    }
+}
+
+/// Time tracked per todo, across every list.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct TimeLog(Vec<Session>);
+
+/// A todo's tracked time added up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskTime {
+    pub name: String,
+    pub seconds: u64,
+    pub pomodoros: u64,
+}
+
+impl TimeLog {
+    pub fn path(main_list: &Path) -> PathBuf {
+        main_list.with_file_name(TIME_LOG_FILE)
+    }
+
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn record(&mut self, session: Session) {
+        self.0.push(session);
+    }
+
+    fn on(&self, day: NaiveDate) -> impl Iterator<Item = &Session> {
+        let day = day.format(DUE_FORMAT).to_string();
+        self.0.iter().filter(move |session| session.day == day)
+    }
+
+    pub fn seconds_on(&self, day: NaiveDate) -> u64 {
+        self.on(day).map(|session| session.seconds).sum()
+    }
+
+    /// Every session on the todo created at `task` added up.
+    pub fn seconds_for(&self, task: &str) -> u64 {
+        self.0
+            .iter()
+            .filter(|session| session.task == task)
+            .map(|session| session.seconds)
+            .sum()
+    }
+
+    pub fn pomodoros_on(&self, day: NaiveDate) -> u64 {
+        self.on(day).map(Session::pomodoros).sum()
+    }
+
+    /// Time per todo from `since` on, or ever, most worked on first.
+    pub fn totals(&self, since: Option<NaiveDate>) -> Vec<TaskTime> {
+        let mut totals: Vec<(&str, TaskTime)> = Vec::new();
+        for session in &self.0 {
+            let in_range = since.is_none_or(|since| {
+                NaiveDate::parse_from_str(&session.day, DUE_FORMAT).is_ok_and(|day| day >= since)
+            });
+            if !in_range {
+                continue;
+            }
+            match totals.iter_mut().find(|(task, _)| *task == session.task) {
+                Some((_, total)) => {
+                    // The latest name wins
+                    total.name.clone_from(&session.name);
+                    total.seconds += session.seconds;
+                    total.pomodoros += session.pomodoros();
+                }
+                None => totals.push((
+                    &session.task,
+                    TaskTime {
+                        name: session.name.clone(),
+                        seconds: session.seconds,
+                        pomodoros: session.pomodoros(),
+                    },
+                )),
+            }
+        }
+        let mut totals: Vec<TaskTime> = totals.into_iter().map(|(_, total)| total).collect();
+        totals.sort_by_key(|total| Reverse(total.seconds));
+        totals
+    }
+}
+
+/// Parses a duration like `2h`, `45m` or `1h 30m` into seconds.
+pub fn parse_duration(text: &str) -> Option<u64> {
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in text.chars().filter(|c| !c.is_whitespace()) {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            'h' => 60 * 60,
+            'm' => 60,
+            _ => return None,
+        };
+        seconds += number.parse::<u64>().ok()? * unit;
+        number.clear();
+    }
+    (number.is_empty() && !text.trim().is_empty()).then_some(seconds)
+}
+
+/// `1h 05m`, or `25m` under an hour.
+pub fn duration(seconds: u64) -> String {
+    let minutes = seconds / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{minutes}m")
+    }
+}
@@ -0,0 +1,191 @@
+use crate::preferences::WeekStart;
+use crate::stats::{self, Completions, TimeLog};
+use chrono::{Days, NaiveDate};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Style, Stylize};
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Borders, Clear, Gauge, Paragraph, Widget};
+
+/// Days shown in the charts.
+const CHART_DAYS: u64 = 14;
+/// Todos listed under each heading of the time page.
+const TOP_TASKS: usize = 5;
+
+/// Which of the stats is shown, switched with Tab.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StatsPage {
+    #[default]
+    Completions,
+    Time,
+}
+
+impl StatsPage {
+    pub const fn toggle(self) -> Self {
+        match self {
+            Self::Completions => Self::Time,
+            Self::Time => Self::Completions,
+        }
+    }
+}
+
+/// Completed todos per day, the streak and today's progress towards the goal,
+/// and on the second page the time tracked in focus mode.
+pub struct StatsPopup<'a> {
+    pub completions: &'a Completions,
+    pub time_log: &'a TimeLog,
+    pub page: StatsPage,
+    pub today: NaiveDate,
+    pub goal: u32, // 0 when no daily goal is set
+    pub week_start: WeekStart,
+    pub style: Style,
+}
+
+impl StatsPopup<'_> {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        Clear.render(area, buf);
+
+        let title = match self.page {
+            StatsPage::Completions => " Stats: completions ",
+            StatsPage::Time => " Stats: time tracked ",
+        };
+        let block = Block::new()
+            .title(title)
+            .title_bottom(Line::from(" (Tab) completions/time | (Esc) close ").italic())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(selected_style_fg))
+            .style(self.style);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        match self.page {
+            StatsPage::Completions => self.render_completions(inner, buf, selected_style_fg),
+            StatsPage::Time => self.render_time(inner, buf, selected_style_fg),
+        }
+    }
+
+    fn render_completions(&self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        let [summary_area, gauge_area, _, chart_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(u16::from(self.goal > 0)),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        let done_today = self.completions.count(self.today);
+        let streak = self.completions.streak(self.today, self.goal);
+        let days = |count: u32| if count == 1 { "day" } else { "days" };
+        let best = self.completions.best_streak(self.goal);
+        Paragraph::new(vec![
+            Line::from(format!("Completed today: {done_today}")),
+            Line::from(format!(
+                "Streak: {streak} {} (best {best} {})",
+                days(streak),
+                days(best)
+            )),
+            Line::from(format!("Completed in total: {}", self.completions.total())),
+        ])
+        .style(self.style)
+        .render(summary_area, buf);
+
+        if self.goal > 0 {
+            Gauge::default()
+                .label(format!("{done_today} of {} today", self.goal))
+                .ratio((f64::from(done_today) / f64::from(self.goal)).min(1.0))
+                .gauge_style(Style::new().fg(selected_style_fg).bg(Color::DarkGray))
+                .render(gauge_area, buf);
+        }
+
+        let bars = self.daily_bars(|day| {
+            let count = self.completions.count(day);
+            let reached = self.goal > 0 && count >= self.goal;
+            let color = if reached {
+                Color::Green
+            } else {
+                selected_style_fg
+            };
+            (u64::from(count), color)
+        });
+        self.chart("Completed in the last two weeks", &bars)
+            .render(chart_area, buf);
+    }
+
+    fn render_time(&self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        let [summary_area, _, chart_area, _, tasks_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(TOP_TASKS as u16 + 1),
+        ])
+        .areas(area);
+
+        let week_began = self.today - Days::new(u64::from(self.week_start.day_of_week(self.today)));
+        let this_week = self.time_log.totals(Some(week_began));
+        let week_seconds: u64 = this_week.iter().map(|task| task.seconds).sum();
+        let week_pomodoros: u64 = this_week.iter().map(|task| task.pomodoros).sum();
+        Paragraph::new(vec![
+            Line::from(format!(
+                "Today: {} (🍅 {})",
+                stats::duration(self.time_log.seconds_on(self.today)),
+                self.time_log.pomodoros_on(self.today)
+            )),
+            Line::from(format!(
+                "This week: {} (🍅 {week_pomodoros})",
+                stats::duration(week_seconds)
+            )),
+        ])
+        .style(self.style)
+        .render(summary_area, buf);
+
+        // In minutes, so the bars have a readable value
+        let bars = self.daily_bars(|day| (self.time_log.seconds_on(day) / 60, selected_style_fg));
+        self.chart("Minutes in the last two weeks", &bars)
+            .render(chart_area, buf);
+
+        let [week_area, all_time_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(tasks_area);
+        top_tasks("Most worked on this week", this_week).render(week_area, buf);
+        top_tasks("All time", self.time_log.totals(None)).render(all_time_area, buf);
+    }
+
+    /// One bar per day, oldest first, labelled with the day of the month.
+    fn daily_bars(&self, value: impl Fn(NaiveDate) -> (u64, Color)) -> Vec<Bar<'static>> {
+        (0..CHART_DAYS)
+            .rev()
+            .map(|days_ago| {
+                let day = self.today - Days::new(days_ago);
+                let (value, color) = value(day);
+                Bar::default()
+                    .value(value)
+                    .label(Line::from(day.format("%d").to_string()))
+                    .style(Style::new().fg(color))
+            })
+            .collect()
+    }
+
+    fn chart<'a>(&self, title: &'a str, bars: &'a [Bar<'a>]) -> BarChart<'a> {
+        BarChart::default()
+            .block(Block::new().title(title))
+            .data(BarGroup::default().bars(bars))
+            .bar_width(3)
+            .bar_gap(1)
+            .style(self.style)
+    }
+}
+
+fn top_tasks(title: &str, totals: Vec<stats::TaskTime>) -> Paragraph<'static> {
+    let mut lines = vec![Line::from(title.to_string()).bold()];
+    if totals.is_empty() {
+        lines.push(Line::from("Nothing tracked yet, press F to focus").italic());
+    }
+    lines.extend(totals.into_iter().take(TOP_TASKS).map(|task| {
+        Line::from(format!(
+            "{:>7} {:>2}🍅 {}",
+            stats::duration(task.seconds),
+            task.pomodoros,
+            task.name
+        ))
+    }));
+    Paragraph::new(lines)
+}
@@ -0,0 +1,359 @@
+use crate::preferences::Remote;
+use crate::Data;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+use ureq::{Agent, AgentBuilder, Request};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A remote copy of the list files. Files are named by their path relative to
+/// the data folder, e.g. `data.json` or `lists/work.json`.
+///
+/// Saving always writes the local files first and only then queues them with
+/// an `Uploader`: the local copy is the cache used offline, the base the
+/// three-way merge compares against and what the disk watcher checks, so it
+/// isn't one `Storage` among others.
+pub trait Storage: Send {
+    /// The file's contents, or `None` if it doesn't exist remotely yet.
+    fn get(&self, name: &str) -> io::Result<Option<Vec<u8>>>;
+    fn put(&self, name: &str, contents: &[u8]) -> io::Result<()>;
+}
+
+pub fn open(remote: &Remote) -> Box<dyn Storage> {
+    let agent = AgentBuilder::new().timeout(TIMEOUT).build();
+    match remote {
+        Remote::WebDav {
+            url,
+            username,
+            password,
+        } => Box::new(WebDav {
+            agent,
+            url: url.trim_end_matches('/').to_string(),
+            authorization: format!(
+                "Basic {}",
+                BASE64_STANDARD.encode(format!("{username}:{password}"))
+            ),
+        }),
+        Remote::S3 {
+            endpoint,
+            bucket,
+            region,
+            access_key,
+            secret_key,
+        } => Box::new(S3 {
+            agent,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.clone(),
+            region: region.clone(),
+            access_key: access_key.clone(),
+            secret_key: secret_key.clone(),
+        }),
+    }
+}
+
+/// Replaces the local copies of `paths` with the remote ones, so the local
+/// files act as a cache when the remote can't be reached. Returns the errors.
+pub fn pull(storage: &dyn Storage, dir: &Path, paths: &[PathBuf]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for path in paths {
+        let name = name(dir, path);
+        tracing::info!(file = %name, "downloading");
+        match storage.get(&name) {
+            // Never overwrite the cache with something that isn't a list
+            Ok(Some(contents)) if serde_json::from_slice::<Vec<Data>>(&contents).is_ok() => {
+                if let Err(e) = fs::write(path, contents) {
+                    errors.push(format!("Couldn't cache {name}: {e}"));
+                }
+            }
+            Ok(_) => {}
+            Err(e) => errors.push(format!(
+                "Couldn't download {name}, using the local copy: {e}"
+            )),
+        }
+    }
+    errors
+}
+
+/// Uploads files in the background so saving never waits on the network.
+pub struct Uploader {
+    paths: Sender<PathBuf>,
+    errors: Receiver<String>,
+}
+
+impl Uploader {
+    /// `dir` is the data folder the uploaded files are in.
+    pub fn spawn(storage: Box<dyn Storage>, dir: PathBuf) -> Self {
+        let (paths, path_receiver) = mpsc::channel::<PathBuf>();
+        let (error_sender, errors) = mpsc::channel();
+        thread::spawn(move || {
+            for path in path_receiver {
+                let name = name(&dir, &path);
+                tracing::info!(file = %name, "uploading");
+                let result = fs::read(&path).and_then(|contents| storage.put(&name, &contents));
+                if let Err(e) = result {
+                    let _ = error_sender.send(format!("Couldn't upload {name}: {e}"));
+                }
+            }
+        });
+        Self { paths, errors }
+    }
+
+    /// Queues a file written locally for upload.
+    pub fn upload(&self, path: PathBuf) {
+        let _ = self.paths.send(path);
+    }
+
+    /// Errors from finished uploads, without waiting for running ones.
+    pub fn errors(&self) -> impl Iterator<Item = String> + '_ {
+        self.errors.try_iter()
+    }
+}
+
+/// The remote name of a file in the data folder `dir`.
+fn name(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Sends the request, mapping a 404 to `None`.
+fn fetch(request: Request) -> io::Result<Option<Vec<u8>>> {
+    match request.call() {
+        Ok(response) => {
+            let mut contents = Vec::new();
+            response.into_reader().read_to_end(&mut contents)?;
+            Ok(Some(contents))
+        }
+        Err(ureq::Error::Status(404, _)) => Ok(None),
+        Err(e) => Err(io::Error::other(e)),
+    }
+}
+
+struct WebDav {
+    agent: Agent,
+    url: String,
+    authorization: String,
+}
+
+impl WebDav {
+    fn request(&self, method: &str, name: &str) -> Request {
+        self.agent
+            .request(method, &format!("{}/{name}", self.url))
+            .set("Authorization", &self.authorization)
+    }
+}
+
+impl Storage for WebDav {
+    fn get(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        fetch(self.request("GET", name))
+    }
+
+    fn put(&self, name: &str, contents: &[u8]) -> io::Result<()> {
+        match self.request("PUT", name).send_bytes(contents) {
+            // The parent collection is missing, e.g. for the first file in `lists/`
+            Err(ureq::Error::Status(409, _)) if name.contains('/') => {
+                let (parent, _) = name.rsplit_once('/').unwrap_or_default();
+                self.request("MKCOL", &format!("{parent}/"))
+                    .call()
+                    .map_err(io::Error::other)?;
+                self.request("PUT", name)
+                    .send_bytes(contents)
+                    .map_err(io::Error::other)?;
+            }
+            result => {
+                result.map_err(io::Error::other)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An S3-compatible bucket, addressed path-style and signed with AWS Signature
+/// Version 4.
+struct S3 {
+    agent: Agent,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3 {
+    fn request(&self, method: &str, name: &str, payload: &[u8]) -> Request {
+        let host = self
+            .endpoint
+            .split_once("://")
+            .map_or(self.endpoint.as_str(), |(_, host)| host);
+        let path = format!("/{}/{}", uri_encode(&self.bucket), uri_encode(name));
+        let now = Utc::now();
+        let date = now.format("%Y%m%d").to_string();
+        let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let payload_hash = hex(&Sha256::digest(payload));
+
+        let canonical_request =
+            canonical_request(method, &path, "", host, &payload_hash, &timestamp);
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+        let signature = signature(
+            &self.secret_key,
+            &self.region,
+            &timestamp,
+            &canonical_request,
+        );
+
+        self.agent
+            .request(method, &format!("{}{path}", self.endpoint))
+            .set("x-amz-content-sha256", &payload_hash)
+            .set("x-amz-date", &timestamp)
+            .set(
+                "Authorization",
+                &format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={signature}",
+                    self.access_key
+                ),
+            )
+    }
+}
+
+impl Storage for S3 {
+    fn get(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        fetch(self.request("GET", name, &[]))
+    }
+
+    fn put(&self, name: &str, contents: &[u8]) -> io::Result<()> {
+        self.request("PUT", name, contents)
+            .send_bytes(contents)
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
+/// The request as Signature Version 4 signs it, with the headers sent.
+fn canonical_request(
+    method: &str,
+    path: &str,
+    query: &str,
+    host: &str,
+    payload_hash: &str,
+    timestamp: &str,
+) -> String {
+    format!(
+        "{method}\n{path}\n{query}\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{timestamp}\n\nhost;x-amz-content-sha256;x-amz-date\n{payload_hash}"
+    )
+}
+
+/// The key for the day of `timestamp`, derived from the secret key.
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    [region, service, "aws4_request"].iter().fold(
+        hmac(format!("AWS4{secret_key}").as_bytes(), date.as_bytes()),
+        |key, part| hmac(&key, part.as_bytes()),
+    )
+}
+
+/// Signs `canonical_request`, sent at `timestamp` as `YYYYMMDDTHHMMSSZ`.
+fn signature(secret_key: &str, region: &str, timestamp: &str, canonical_request: &str) -> String {
+    let date = timestamp.get(..8).unwrap_or(timestamp);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{timestamp}\n{date}/{region}/s3/aws4_request\n{}",
+        hex(&Sha256::digest(canonical_request))
+    );
+    let signing_key = signing_key(secret_key, date, region, "s3");
+    hex(&hmac(&signing_key, string_to_sign.as_bytes()))
+}
+
+fn hmac(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Percent-encodes everything but unreserved characters and `/`, as S3 expects.
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The examples in AWS's "Signature Calculations for the Authorization
+    // Header: Transferring Payload in a Single Chunk" for S3
+    const SECRET_KEY: &str = "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY";
+    const HOST: &str = "examplebucket.s3.amazonaws.com";
+    const TIMESTAMP: &str = "20130524T000000Z";
+    const EMPTY_HASH: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn the_signing_key_matches_the_aws_example() {
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn requests_are_signed_as_in_the_aws_examples() {
+        assert_eq!(hex(&Sha256::digest(b"")), EMPTY_HASH);
+
+        // GET Bucket Lifecycle
+        let request = canonical_request("GET", "/", "lifecycle=", HOST, EMPTY_HASH, TIMESTAMP);
+        assert_eq!(
+            hex(&Sha256::digest(&request)),
+            "9766c798316ff2757b517bc739a67f6213b4ab36dd5da2f94eaebf79c77395ca"
+        );
+        assert_eq!(
+            signature(SECRET_KEY, "us-east-1", TIMESTAMP, &request),
+            "fea454ca298b7da1c68078a5d1bdbfbbe0d65c699e0f91ac7a200a0136783543"
+        );
+
+        // GET Bucket (List Objects)
+        let request = canonical_request(
+            "GET",
+            "/",
+            "max-keys=2&prefix=J",
+            HOST,
+            EMPTY_HASH,
+            TIMESTAMP,
+        );
+        assert_eq!(
+            signature(SECRET_KEY, "us-east-1", TIMESTAMP, &request),
+            "34b48302e7b5fa45bde8084f4b7868a86f0a534bc59db6670ed5711ef69dc6f7"
+        );
+    }
+
+    #[test]
+    fn names_are_percent_encoded_but_keep_their_slashes() {
+        assert_eq!(
+            uri_encode("lists/work notes.json"),
+            "lists/work%20notes.json"
+        );
+        assert_eq!(uri_encode("a+b~c"), "a%2Bb~c");
+    }
+}
@@ -0,0 +1,30 @@
+/// The `old/new` arguments of a `:substitute` command.
+pub struct Substitution {
+    pub find: String,
+    pub replace: String,
+}
+
+impl Substitution {
+    pub fn parse(arguments: &str) -> Result<Self, String> {
+        let arguments = arguments.strip_prefix('/').unwrap_or(arguments);
+
+        let (find, replace) = arguments
+            .split_once('/')
+            .ok_or("Missing replacement, use old/new")?;
+        if find.is_empty() {
+            return Err("Nothing to find".to_string());
+        }
+
+        Ok(Self {
+            find: find.to_string(),
+            // Allow a trailing slash like in vim
+            replace: replace.strip_suffix('/').unwrap_or(replace).to_string(),
+        })
+    }
+
+    /// The replaced text, or `None` when there is nothing to replace.
+    pub fn apply(&self, text: &str) -> Option<String> {
+        text.contains(&self.find)
+            .then(|| text.replace(&self.find, &self.replace))
+    }
+}
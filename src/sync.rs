@@ -0,0 +1,324 @@
+use crate::Data;
+
+/// A field that can be merged on its own.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Description,
+    Progress,
+    Starred,
+    Percent,
+    Due,
+    Priority,
+    Fields,
+}
+
+impl Field {
+    pub const ALL: [Self; 8] = [
+        Self::Name,
+        Self::Description,
+        Self::Progress,
+        Self::Starred,
+        Self::Percent,
+        Self::Due,
+        Self::Priority,
+        Self::Fields,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Description => "Description",
+            Self::Progress => "Status",
+            Self::Starred => "Starred",
+            Self::Percent => "Percent",
+            Self::Due => "Due",
+            Self::Priority => "Priority",
+            Self::Fields => "Fields",
+        }
+    }
+
+    pub fn value(self, item: &Data) -> String {
+        match self {
+            Self::Name => item.name.clone(),
+            Self::Description => item.description.clone(),
+            Self::Progress => item.progress.display().1,
+            Self::Starred => item.starred.to_string(),
+            Self::Percent => item
+                .percent
+                .map_or_else(String::new, |percent| format!("{percent}%")),
+            Self::Due => item.due.clone().unwrap_or_default(),
+            Self::Priority => item
+                .priority
+                .map_or_else(String::new, |priority| priority.display().1),
+            Self::Fields => item
+                .fields
+                .iter()
+                .map(|(key, value)| format!("{key}: {value}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+
+    fn differs(self, a: &Data, b: &Data) -> bool {
+        match self {
+            Self::Name => a.name != b.name,
+            Self::Description => a.description != b.description,
+            Self::Progress => a.progress != b.progress,
+            Self::Starred => a.starred != b.starred,
+            Self::Percent => a.percent != b.percent,
+            Self::Due => a.due != b.due,
+            Self::Priority => a.priority != b.priority,
+            Self::Fields => a.fields != b.fields,
+        }
+    }
+
+    /// Copies this field from `from` into `into`.
+    fn copy(self, from: &Data, into: &mut Data) {
+        match self {
+            Self::Name => into.name = from.name.clone(),
+            Self::Description => into.description = from.description.clone(),
+            Self::Progress => {
+                into.progress = from.progress.clone();
+                into.completed = from.completed.clone();
+            }
+            Self::Starred => into.starred = from.starred,
+            Self::Percent => into.percent = from.percent,
+            Self::Due => into.due = from.due.clone(),
+            Self::Priority => into.priority = from.priority,
+            Self::Fields => into.fields = from.fields.clone(),
+        }
+    }
+}
+
+/// A task changed both in memory and on disk in incompatible ways.
+pub struct Conflict {
+    pub local: Data,
+    pub remote: Data,
+    /// The fields changed differently on both sides
+    pub fields: Vec<Field>,
+    /// Per conflicting field, whether the merged version takes the disk copy
+    pub use_remote: Vec<bool>,
+    /// Both sides' other changes combined
+    base_merge: Data,
+}
+
+impl Conflict {
+    pub fn merged(&self) -> Data {
+        let mut merged = self.base_merge.clone();
+        for (field, &use_remote) in self.fields.iter().zip(&self.use_remote) {
+            let from = if use_remote {
+                &self.remote
+            } else {
+                &self.local
+            };
+            field.copy(from, &mut merged);
+        }
+        merged
+    }
+}
+
+fn same(a: &Data, b: &Data) -> bool {
+    Field::ALL.iter().all(|field| !field.differs(a, b))
+}
+
+pub fn same_items(a: &[Data], b: &[Data]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| same(a, b) && a.created == b.created)
+}
+
+/// Finds the first task not taken yet with the given creation time, which is
+/// what identifies a task across copies.
+fn take<'a>(items: &'a [Data], taken: &mut [bool], created: &str) -> Option<&'a Data> {
+    let index = (0..items.len()).find(|&i| !taken[i] && items[i].created == created)?;
+    taken[index] = true;
+    Some(&items[index])
+}
+
+/// Three-way merges the in-memory `local` tasks and the `remote` copy on disk
+/// against `base`, the copy last read from disk. Fields changed on one side
+/// win; fields changed on both sides become conflicts and keep the local value
+/// until resolved.
+pub fn merge(base: &[Data], local: &[Data], remote: &[Data]) -> (Vec<Data>, Vec<Conflict>) {
+    let mut local_taken = vec![false; local.len()];
+    let mut base_taken = vec![false; base.len()];
+    let mut merged = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for remote_item in remote {
+        let base_item = take(base, &mut base_taken, &remote_item.created);
+        let Some(local_item) = take(local, &mut local_taken, &remote_item.created) else {
+            // Deleted locally, kept only if it changed on disk since
+            if base_item.is_none_or(|base_item| !same(base_item, remote_item)) {
+                merged.push(remote_item.clone());
+            }
+            continue;
+        };
+
+        let mut item = remote_item.clone();
+        let mut fields = Vec::new();
+        for field in Field::ALL {
+            if !field.differs(local_item, remote_item) {
+                continue;
+            }
+            match base_item {
+                Some(base_item) if !field.differs(base_item, remote_item) => {
+                    field.copy(local_item, &mut item);
+                }
+                Some(base_item) if !field.differs(base_item, local_item) => {}
+                _ => fields.push(field),
+            }
+        }
+
+        if !fields.is_empty() {
+            let base_merge = item.clone();
+            for field in &fields {
+                field.copy(local_item, &mut item);
+            }
+            conflicts.push(Conflict {
+                local: local_item.clone(),
+                remote: remote_item.clone(),
+                use_remote: vec![false; fields.len()],
+                fields,
+                base_merge,
+            });
+        }
+        merged.push(item);
+    }
+
+    // Tasks only in memory were added locally, or deleted on disk
+    for (local_item, _) in local.iter().zip(local_taken).filter(|(_, taken)| !taken) {
+        let base_item = take(base, &mut base_taken, &local_item.created);
+        if base_item.is_none_or(|base_item| !same(base_item, local_item)) {
+            merged.push(local_item.clone());
+        }
+    }
+
+    (merged, conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Progress;
+
+    fn task(name: &str, created: &str) -> Data {
+        Data {
+            name: name.to_string(),
+            description: format!("About {name}"),
+            created: created.to_string(),
+            ..Data::default()
+        }
+    }
+
+    fn names(items: &[Data]) -> Vec<&str> {
+        items.iter().map(|item| item.name.as_str()).collect()
+    }
+
+    #[test]
+    fn todos_added_on_either_side_are_kept() {
+        let base = [task("Buy milk", "1")];
+        let local = [task("Buy milk", "1"), task("Call mum", "2")];
+        let remote = [task("Buy milk", "1"), task("Book dentist", "3")];
+        let (merged, conflicts) = merge(&base, &local, &remote);
+        assert_eq!(names(&merged), ["Buy milk", "Book dentist", "Call mum"]);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn todos_deleted_on_one_side_and_untouched_on_the_other_stay_deleted() {
+        let base = [task("Buy milk", "1"), task("Call mum", "2")];
+        let (merged, _) = merge(&base, &base[..1], &base);
+        assert_eq!(names(&merged), ["Buy milk"]);
+        let (merged, _) = merge(&base, &base, &base[1..]);
+        assert_eq!(names(&merged), ["Call mum"]);
+    }
+
+    #[test]
+    fn an_edit_wins_over_a_delete_on_the_other_side() {
+        let base = [task("Buy milk", "1"), task("Call mum", "2")];
+        let mut edited = task("Call mum", "2");
+        edited.progress = Progress::Done;
+
+        // Deleted in memory, edited on disk
+        let remote = [task("Buy milk", "1"), edited.clone()];
+        let (merged, conflicts) = merge(&base, &base[..1], &remote);
+        assert_eq!(names(&merged), ["Buy milk", "Call mum"]);
+        assert_eq!(merged[1].progress, Progress::Done);
+        assert!(conflicts.is_empty());
+
+        // Edited in memory, deleted on disk
+        let local = [task("Buy milk", "1"), edited];
+        let (merged, conflicts) = merge(&base, &local, &base[..1]);
+        assert_eq!(names(&merged), ["Buy milk", "Call mum"]);
+        assert_eq!(merged[1].progress, Progress::Done);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn different_fields_changed_on_both_sides_are_combined() {
+        let base = [task("Buy milk", "1")];
+        let mut local = task("Buy oat milk", "1");
+        local.starred = true;
+        let mut remote = task("Buy milk", "1");
+        remote.progress = Progress::Done;
+        let (merged, conflicts) = merge(&base, &[local], &[remote]);
+        assert!(conflicts.is_empty());
+        assert_eq!(merged[0].name, "Buy oat milk");
+        assert!(merged[0].starred);
+        assert_eq!(merged[0].progress, Progress::Done);
+    }
+
+    #[test]
+    fn the_same_field_changed_on_both_sides_is_a_conflict() {
+        let base = [task("Buy milk", "1")];
+        let mut local = task("Buy milk", "1");
+        local.description = "Two liters".to_string();
+        let mut remote = task("Buy milk", "1");
+        remote.description = "Oat milk".to_string();
+        remote.starred = true;
+        let (merged, mut conflicts) = merge(&base, &[local], &[remote]);
+
+        // The local value is kept until the conflict is resolved
+        assert_eq!(merged[0].description, "Two liters");
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &mut conflicts[0];
+        assert!(conflict.fields == [Field::Description]);
+        assert_eq!(conflict.merged().description, "Two liters");
+        conflict.use_remote[0] = true;
+        let resolved = conflict.merged();
+        assert_eq!(resolved.description, "Oat milk");
+        // The other side's change that didn't conflict is kept either way
+        assert!(resolved.starred);
+    }
+
+    #[test]
+    fn todos_are_matched_by_creation_time_not_name_or_position() {
+        let base = [task("Buy milk", "1"), task("Buy milk", "2")];
+        let renamed = task("Buy bread", "2");
+        let local = [task("Buy milk", "2"), task("Buy milk", "1")];
+        let remote = [task("Buy milk", "1"), renamed];
+        let (merged, conflicts) = merge(&base, &local, &remote);
+        assert!(conflicts.is_empty());
+        let created: Vec<&str> = merged.iter().map(|item| item.created.as_str()).collect();
+        assert_eq!(created, ["1", "2"]);
+        assert_eq!(names(&merged), ["Buy milk", "Buy bread"]);
+    }
+
+    #[test]
+    fn same_items_compares_fields_order_and_creation_times() {
+        let items = [task("Buy milk", "1"), task("Call mum", "2")];
+        assert!(same_items(&items, &items.clone()));
+        assert!(!same_items(&items, &items[..1]));
+        assert!(!same_items(&items, &[items[1].clone(), items[0].clone()]));
+        assert!(!same_items(
+            &items,
+            &[task("Buy milk", "3"), task("Call mum", "2")]
+        ));
+        let mut done = items.clone();
+        done[1].progress = Progress::Done;
+        assert!(!same_items(&items, &done));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::icons::Icons;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Modifier, Style, Stylize, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+
+/// Every tag in the list as a checkbox for the selected todo, applied all at
+/// once, with a field to type in a new one.
+pub struct TagPopup<'a> {
+    pub name: &'a str,              // The todo's name, for the title
+    pub tags: &'a [(String, bool)], // Each tag and whether the todo will have it
+    pub selected: usize,
+    pub input: &'a str,
+    pub icons: &'a Icons,
+    pub style: Style,
+}
+
+impl TagPopup<'_> {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        Clear.render(area, buf);
+
+        let [tags_area, input_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(area);
+
+        let lines: Vec<Line> = if self.tags.is_empty() {
+            vec![Line::from("No tags yet, type one below").italic()]
+        } else {
+            self.tags
+                .iter()
+                .enumerate()
+                .map(|(i, (tag, checked))| {
+                    let line = Line::from(format!("{} #{tag}", self.icons.checkbox(*checked)));
+                    if i == self.selected {
+                        line.style(
+                            Style::new()
+                                .fg(selected_style_fg)
+                                .add_modifier(Modifier::REVERSED),
+                        )
+                    } else {
+                        line
+                    }
+                })
+                .collect()
+        };
+
+        // Keep the selected tag in view
+        let visible = usize::from(tags_area.height.saturating_sub(2));
+        let scroll = u16::try_from((self.selected + 1).saturating_sub(visible)).unwrap_or(0);
+
+        Paragraph::new(lines)
+            .scroll((scroll, 0))
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title(format!("Tags of \"{}\"", self.name))
+                    .title_bottom(
+                        Line::from(
+                            " (↑/↓) select | (Space) toggle | (Enter) apply | (Esc) cancel ",
+                        )
+                        .italic(),
+                    )
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(selected_style_fg)),
+            )
+            .render(tags_area, buf);
+
+        Paragraph::new(Text::from(self.input.to_string()))
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title("New tag, (Enter) adds it")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::White)),
+            )
+            .render(input_area, buf);
+    }
+}
@@ -0,0 +1,60 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::{Color, Line, Modifier, Span, Style};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Widget};
+
+pub struct TagSidebar<'a> {
+    pub tags: &'a [(String, usize)], // (tag, open task count)
+    pub open_total: usize,
+    pub selected: usize, // 0 is "All", then one entry per tag
+    pub focused: bool,
+    pub style: Style,
+}
+
+impl TagSidebar<'_> {
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        let border_color = if self.focused {
+            selected_style_fg
+        } else {
+            Color::White
+        };
+
+        let entries = std::iter::once(("All".to_string(), self.open_total)).chain(
+            self.tags
+                .iter()
+                .map(|(tag, count)| (format!("#{tag}"), *count)),
+        );
+        let lines: Vec<Line> = entries
+            .enumerate()
+            .map(|(i, (label, count))| {
+                let style = if i == self.selected {
+                    Style::new()
+                        .fg(selected_style_fg)
+                        .add_modifier(Modifier::REVERSED)
+                } else {
+                    self.style
+                };
+                Line::from(vec![
+                    Span::styled(label, style),
+                    Span::styled(format!(" ({count})"), Style::new().fg(Color::DarkGray)),
+                ])
+            })
+            .collect();
+
+        // Keep the selected entry in view
+        let visible = usize::from(area.height.saturating_sub(2));
+        let scroll = u16::try_from((self.selected + 1).saturating_sub(visible)).unwrap_or(0);
+
+        Paragraph::new(lines)
+            .scroll((scroll, 0))
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title("Tags")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(border_color)),
+            )
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::markdown;
+use crate::timestamps::Clock;
+use crate::{Data, Progress};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::{Color, Line, Span, Style, Stylize};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap};
+use tui_big_text::{BigText, PixelSize};
+
+const ACTIONS_TEXT: &str =
+    "(R) edit | (F) set field | (A) add note | (N) progress | (X) delete | (↑/↓) scroll | (Esc) back";
+const MAX_NOTES_HEIGHT: u16 = 8; // The latest notes show when there are more
+
+pub struct TaskReader<'a> {
+    pub item: &'a Data,
+    pub logged: u64, // Seconds spent on it in focus mode
+    pub clock: Clock,
+    pub scroll: u16,
+    pub blockers: Vec<&'a str>, // The open todos it waits on
+    pub style: Style,
+}
+
+impl TaskReader<'_> {
+    /// Splits the full-screen area into title, metadata, description, notes
+    /// (empty without any) and actions.
+    fn layout(item: &Data, area: Rect) -> [Rect; 5] {
+        let inner = Block::new().borders(Borders::ALL).inner(area);
+        let notes = match u16::try_from(item.notes.len()).unwrap_or(u16::MAX) {
+            0 => 0,
+            notes => notes.saturating_add(2).min(MAX_NOTES_HEIGHT),
+        };
+        Layout::vertical([
+            Constraint::Length(5),
+            Constraint::Length(1),
+            Constraint::Min(3),
+            Constraint::Length(notes),
+            Constraint::Length(1),
+        ])
+        .areas(inner)
+    }
+
+    /// Largest useful scroll offset for the description at this terminal size.
+    pub fn max_scroll(item: &Data, area: Rect) -> u16 {
+        let [_, _, description_area, _, _] = Self::layout(item, area);
+        let width = usize::from(description_area.width.saturating_sub(2).max(1));
+        let lines: usize = item
+            .description
+            .lines()
+            .map(|line| line.chars().count().max(1).div_ceil(width))
+            .sum();
+        let visible = usize::from(description_area.height.saturating_sub(2));
+        u16::try_from(lines.saturating_sub(visible)).unwrap_or(u16::MAX)
+    }
+
+    pub fn render(self, area: Rect, buf: &mut Buffer, selected_style_fg: Color) {
+        Clear.render(area, buf);
+
+        Block::new()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(selected_style_fg))
+            .style(self.style)
+            .render(area, buf);
+
+        let [title_area, meta_area, description_area, notes_area, actions_area] =
+            Self::layout(self.item, area);
+
+        BigText::builder()
+            .pixel_size(PixelSize::HalfHeight)
+            .style(Style::new().fg(selected_style_fg))
+            .lines(vec![Line::from(self.item.name.clone())])
+            .centered()
+            .build()
+            .render(title_area, buf);
+
+        let (progress_color, progress_text) = self.item.progress.display();
+        let mut meta = vec![
+            Span::from("Progress: "),
+            Span::styled(progress_text, Style::new().fg(progress_color)),
+        ];
+        if !self.blockers.is_empty() && self.item.progress != Progress::Done {
+            meta.push(Span::styled(
+                format!(" | Blocked by {}", self.blockers.join(", ")),
+                Style::new().fg(Color::Red).bold(),
+            ));
+        }
+        meta.extend([
+            Span::from(" | Created: "),
+            Span::from(self.clock.display(&self.item.created)),
+        ]);
+        if let Some(updated) = &self.item.updated {
+            meta.push(Span::from(format!(
+                " | Updated: {}",
+                self.clock.display(updated)
+            )));
+        }
+        if let Some(priority) = self.item.priority {
+            let (priority_color, priority_text) = priority.display();
+            meta.push(Span::from(" | Priority: "));
+            meta.push(Span::styled(priority_text, Style::new().fg(priority_color)));
+        }
+        if let Some(due) = &self.item.due {
+            meta.push(Span::from(format!(" | Due: {due}")));
+        }
+        if let Some(effort) = self.item.effort(self.logged) {
+            meta.push(Span::from(format!(" | {effort}")));
+        }
+        for (key, value) in &self.item.fields {
+            meta.push(Span::from(format!(" | {key}: {value}")));
+        }
+        Paragraph::new(Line::from(meta))
+            .centered()
+            .style(self.style)
+            .render(meta_area, buf);
+
+        Paragraph::new(markdown::to_text(&self.item.description))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .style(self.style)
+            .block(
+                Block::new()
+                    .title("Description")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(selected_style_fg)),
+            )
+            .render(description_area, buf);
+
+        if !self.item.notes.is_empty() {
+            let visible = usize::from(notes_area.height.saturating_sub(2));
+            let skipped = self.item.notes.len().saturating_sub(visible);
+            let lines: Vec<Line> = self.item.notes[skipped..]
+                .iter()
+                .map(|note| {
+                    Line::from(vec![
+                        Span::from(self.clock.display(&note.at)).fg(Color::DarkGray),
+                        Span::from(format!("  {}", note.text)),
+                    ])
+                })
+                .collect();
+            Paragraph::new(lines)
+                .style(self.style)
+                .block(
+                    Block::new()
+                        .title(format!("Notes ({})", self.item.notes.len()))
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(selected_style_fg)),
+                )
+                .render(notes_area, buf);
+        }
+
+        Paragraph::new(Line::from(ACTIONS_TEXT).italic())
+            .centered()
+            .style(self.style)
+            .render(actions_area, buf);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::CREATED_FORMAT;
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc,
+};
+use serde::{Deserialize, Serialize};
+
+/// The current time as stored in the data files: UTC in RFC 3339.
+pub fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Parses a stored timestamp. Files from before timestamps were stored in UTC
+/// hold the local time without an offset, which is read as this machine's time.
+pub fn parse(timestamp: &str) -> Option<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(timestamp) {
+        return Some(at.with_timezone(&Utc));
+    }
+    let naive = NaiveDateTime::parse_from_str(timestamp, CREATED_FORMAT).ok()?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// The time from `since` to `now` in its largest whole unit, e.g. `5m`, `3h`,
+/// `12d`, `6w` or `2y`.
+pub fn short_age(since: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let age = now - since;
+    match age.num_days() {
+        0 if age.num_hours() == 0 => format!("{}m", age.num_minutes().max(0)),
+        0 => format!("{}h", age.num_hours()),
+        days @ ..14 => format!("{days}d"),
+        days @ ..365 => format!("{}w", days / 7),
+        days => format!("{}y", days / 365),
+    }
+}
+
+/// The time zone timestamps are shown in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    #[default]
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl Zone {
+    /// Parses the `timezone` preference: `UTC` or an offset like `+02:00`, with
+    /// no setting meaning the system's local time.
+    pub fn parse(setting: Option<&str>) -> Result<Self, String> {
+        let Some(setting) = setting.map(str::trim) else {
+            return Ok(Self::Local);
+        };
+        if setting.eq_ignore_ascii_case("utc") || setting == "Z" {
+            return Ok(Self::Fixed(FixedOffset::east_opt(0).expect("zero offset")));
+        }
+        setting
+            .parse::<FixedOffset>()
+            .map(Self::Fixed)
+            .map_err(|_| {
+                format!("Unknown timezone \"{setting}\", use UTC or an offset like +02:00")
+            })
+    }
+}
+
+/// How timestamps are written out, from the `date_format` preference.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DateFormat {
+    #[default]
+    Iso, // 2024-01-31 14:05:00
+    American, // 01/31/2024 02:05 PM
+    European, // 31.01.2024 14:05
+}
+
+impl DateFormat {
+    pub const ALL: [Self; 3] = [Self::Iso, Self::American, Self::European];
+
+    pub const fn pattern(self) -> &'static str {
+        match self {
+            Self::Iso => CREATED_FORMAT,
+            Self::American => "%m/%d/%Y %I:%M %p",
+            Self::European => "%d.%m.%Y %H:%M",
+        }
+    }
+}
+
+/// Where and how timestamps are shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Clock {
+    pub zone: Zone,
+    pub format: DateFormat,
+}
+
+impl Clock {
+    pub fn format(self, at: DateTime<Utc>) -> String {
+        let pattern = self.format.pattern();
+        match self.zone {
+            Zone::Local => at.with_timezone(&Local).format(pattern).to_string(),
+            Zone::Fixed(offset) => at.with_timezone(&offset).format(pattern).to_string(),
+        }
+    }
+
+    /// The calendar day `at` falls on in this zone.
+    pub fn date(self, at: DateTime<Utc>) -> NaiveDate {
+        match self.zone {
+            Zone::Local => at.with_timezone(&Local).date_naive(),
+            Zone::Fixed(offset) => at.with_timezone(&offset).date_naive(),
+        }
+    }
+
+    /// A stored timestamp in this zone, or as stored if it doesn't parse.
+    pub fn display(self, timestamp: &str) -> String {
+        parse(timestamp).map_or_else(|| timestamp.to_string(), |at| self.format(at))
+    }
+}
@@ -0,0 +1,79 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::{Color, Style};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+const MAX_VISIBLE: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToastKind {
+    Info,
+    Warning,
+}
+
+/// A short message that dismisses itself after a few seconds.
+pub struct Toast {
+    pub message: String,
+    pub kind: ToastKind,
+    pub shown_at: Instant,
+}
+
+impl Toast {
+    pub fn new(message: String, kind: ToastKind) -> Self {
+        Self {
+            message,
+            kind,
+            shown_at: Instant::now(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.shown_at.elapsed() >= TOAST_DURATION
+    }
+}
+
+/// The newest toasts, stacked upwards from the bottom-right corner of the area.
+pub struct ToastStack<'a> {
+    pub toasts: &'a VecDeque<Toast>,
+    pub labelled: bool, // Say "Warning:" for warnings, when the border color can't
+    pub style: Style,
+}
+
+impl ToastStack<'_> {
+    pub fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut bottom = area.bottom();
+        for toast in self.toasts.iter().rev().take(MAX_VISIBLE) {
+            let message = match toast.kind {
+                ToastKind::Warning if self.labelled => format!("Warning: {}", toast.message),
+                _ => toast.message.clone(),
+            };
+            let width = u16::try_from(message.width() + 4)
+                .unwrap_or(u16::MAX)
+                .min(area.width / 2);
+            if bottom < area.y + 3 || width < 5 {
+                break;
+            }
+            let toast_area = Rect::new(area.right() - width - 1, bottom - 3, width, 3);
+            bottom -= 3;
+
+            let border_color = match toast.kind {
+                ToastKind::Info => Color::Green,
+                ToastKind::Warning => Color::Yellow,
+            };
+            Clear.render(toast_area, buf);
+            Paragraph::new(format!(" {message}"))
+                .style(self.style)
+                .block(
+                    Block::new()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(border_color)),
+                )
+                .render(toast_area, buf);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Trello board exports (Menu > Print, export and share > Export as JSON).
+//! Lists named like a status set the cards' status, other lists become a
+//! `#project` tag; labels become tags too.
+
+use crate::{Data, Progress, DUE_FORMAT};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Board {
+    lists: Vec<List>,
+    cards: Vec<Card>,
+}
+
+#[derive(Deserialize)]
+struct List {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Card {
+    id: String,
+    name: String,
+    #[serde(default)]
+    desc: String,
+    id_list: String,
+    #[serde(default)]
+    closed: bool,
+    due: Option<String>,
+    #[serde(default)]
+    due_complete: bool,
+    #[serde(default)]
+    labels: Vec<Label>,
+}
+
+#[derive(Deserialize)]
+struct Label {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    color: Option<String>,
+}
+
+/// How a list's cards are imported.
+enum Column {
+    Status(Progress),
+    Project(String),
+}
+
+impl Column {
+    fn of(list: &List) -> Self {
+        let name = list.name.trim().to_lowercase();
+        match name.as_str() {
+            "done" | "complete" | "completed" | "finished" => Self::Status(Progress::Done),
+            "doing" | "in progress" | "in-progress" | "wip" | "started" => {
+                Self::Status(Progress::InProgress)
+            }
+            "to do" | "todo" | "backlog" | "waiting" | "next" => Self::Status(Progress::Waiting),
+            _ => Self::Project(tag(&list.name)),
+        }
+    }
+}
+
+impl Board {
+    /// The open cards as todos. Archived cards and lists are skipped.
+    pub fn into_items(self) -> Vec<Data> {
+        self.cards
+            .into_iter()
+            .filter(|card| !card.closed)
+            .filter_map(|card| {
+                let list = self.lists.iter().find(|list| list.id == card.id_list)?;
+                let mut tags: Vec<String> = card
+                    .labels
+                    .iter()
+                    .map(|label| match &label.color {
+                        Some(color) if label.name.trim().is_empty() => tag(color),
+                        _ => tag(&label.name),
+                    })
+                    .filter(|tag| !tag.is_empty())
+                    .collect();
+                let mut progress = match Column::of(list) {
+                    Column::Status(progress) => progress,
+                    Column::Project(project) => {
+                        tags.insert(0, project);
+                        Progress::Waiting
+                    }
+                };
+                if card.due_complete {
+                    progress = Progress::Done;
+                }
+
+                let mut description = card.desc.trim().to_string();
+                if !tags.is_empty() {
+                    let tags: Vec<String> = tags.iter().map(|tag| format!("#{tag}")).collect();
+                    if !description.is_empty() {
+                        description.push_str("\n\n");
+                    }
+                    description.push_str(&tags.join(" "));
+                }
+
+                Some(Data {
+                    created: created(&card.id),
+                    name: card.name.trim().to_string(),
+                    description,
+                    progress,
+                    due: card
+                        .due
+                        .as_deref()
+                        .and_then(|due| DateTime::parse_from_rfc3339(due).ok())
+                        .map(|due| {
+                            due.with_timezone(&Local)
+                                .date_naive()
+                                .format(DUE_FORMAT)
+                                .to_string()
+                        }),
+                    ..Data::default()
+                })
+            })
+            .collect()
+    }
+}
+
+/// Trello ids start with the creation time in hex seconds, which keeps a
+/// card's creation time the same on every import, so re-imports merge.
+fn created(id: &str) -> String {
+    id.get(..8)
+        .and_then(|seconds| i64::from_str_radix(seconds, 16).ok())
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .unwrap_or_else(Utc::now)
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// A tag from a list or label name, e.g. `Q3 Launch` becomes `q3-launch`.
+fn tag(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
@@ -0,0 +1,2764 @@
+//! Drives the whole app with synthetic key presses against a `TestBackend`
+//! and checks what ends up on screen and on disk.
+
+use crate::capture::Template;
+use crate::date_range::DateFilter;
+use crate::density::Density;
+use crate::lists::TodoList;
+use crate::onboarding::Onboarding;
+use crate::pick::{self, Format};
+use crate::preferences::{
+    ColumnWidths, Preferences, QuickAction, Reminders, SortMode, StaleDays, Swimlanes, ViewLayout,
+    WeekStart,
+};
+use crate::recovery::Recovery;
+use crate::reminders::REMIND_FORMAT;
+use crate::settings::Setting;
+use crate::stats::Session;
+use crate::timestamps::DateFormat;
+use crate::view_model::{Entry, ViewModel};
+use crate::workdays::Workdays;
+use crate::{
+    generate, jira, maintenance, read_json, reminders, save_json, scan, App, Data, Priority,
+    Progress, COMPLETION_FLASH, DESCRIPTION_LIMIT, DUE_FORMAT, MIN_INLINE_HEIGHT, NAME_LIMIT,
+};
+use chrono::{Datelike, Days, Local, NaiveDate, Utc, Weekday};
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::style::Color;
+use ratatui::widgets::TableState;
+use ratatui::Terminal;
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// An app whose only list lives in a fresh temporary directory.
+struct Harness {
+    app: App<'static>,
+    terminal: Terminal<TestBackend>,
+    dir: PathBuf,
+}
+
+impl Harness {
+    fn new(items: Vec<Data>) -> Self {
+        Self::with_preferences(items, Preferences::default())
+    }
+
+    fn with_preferences(items: Vec<Data>, preferences: Preferences) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "todo-tui-test-{}-{}",
+            std::process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.json");
+        save_json(&path, &items).unwrap();
+
+        let list = TodoList {
+            name: "Todo".to_string(),
+            path,
+            items,
+            state: TableState::default().with_selected(0),
+            ..TodoList::default()
+        };
+        Self {
+            app: App::with_lists(vec![list], preferences, None),
+            terminal: Terminal::new(TestBackend::new(100, 30)).unwrap(),
+            dir,
+        }
+    }
+
+    fn press(&mut self, code: KeyCode) -> &mut Self {
+        self.app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        self
+    }
+
+    fn paste(&mut self, text: &str) -> &mut Self {
+        self.app.handle_paste(text);
+        self
+    }
+
+    /// Presses the left button at `from` and releases it at `to`.
+    fn drag(&mut self, from: (u16, u16), to: (u16, u16)) -> &mut Self {
+        for (kind, (column, row)) in [
+            (MouseEventKind::Down(MouseButton::Left), from),
+            (MouseEventKind::Up(MouseButton::Left), to),
+        ] {
+            self.app.handle_mouse(MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            });
+        }
+        self
+    }
+
+    fn resize(&mut self, width: u16, height: u16) -> &mut Self {
+        self.terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        self
+    }
+
+    fn type_text(&mut self, text: &str) -> &mut Self {
+        for c in text.chars() {
+            self.press(KeyCode::Char(c));
+        }
+        self
+    }
+
+    /// The rendered screen, one string per row.
+    fn screen(&mut self) -> String {
+        let app = &mut self.app;
+        self.terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = self.terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn saved(&self) -> Vec<Data> {
+        read_json(&self.dir.join("data.json")).unwrap()
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn task(name: &str, progress: Progress) -> Data {
+    Data {
+        name: name.to_string(),
+        description: format!("About {name}"),
+        progress,
+        created: "2024-01-01 12:00:00".to_string(),
+        ..Data::default()
+    }
+}
+
+#[test]
+fn create_adds_and_saves_a_task() {
+    let mut harness = Harness::new(Vec::new());
+    harness
+        .press(KeyCode::Char('a'))
+        .type_text("Buy milk")
+        .press(KeyCode::Enter)
+        .type_text("Two liters")
+        .press(KeyCode::Enter);
+
+    assert!(!harness.app.show_create);
+    assert!(harness.screen().contains("Buy milk"));
+    let saved = harness.saved();
+    assert_eq!(saved.len(), 1);
+    assert_eq!(saved[0].name, "Buy milk");
+    assert_eq!(saved[0].description, "Two liters");
+}
+
+#[test]
+fn saving_without_a_name_shows_why() {
+    let mut harness = Harness::new(Vec::new());
+    harness
+        .press(KeyCode::Char('a'))
+        .press(KeyCode::Tab)
+        .press(KeyCode::Enter);
+    assert!(harness.app.show_create);
+    assert!(harness.screen().contains("Name is required"));
+
+    harness.type_text("Write report");
+    let screen = harness.screen();
+    assert!(!screen.contains("Name is required"));
+    assert!(screen.contains("38 left"));
+}
+
+#[test]
+fn descriptions_from_the_editor_are_cut_by_characters_onto_one_line() {
+    let mut harness = Harness::new(Vec::new());
+    harness.press(KeyCode::Char('a'));
+    harness
+        .app
+        .take_edited_description("Première ligne\r\nDeuxième ligne");
+    assert_eq!(
+        harness.app.input_description,
+        "Première ligne Deuxième ligne"
+    );
+    assert!(harness.app.toasts.is_empty());
+
+    // Exactly at the limit in characters, though well over it in bytes
+    let full = "é".repeat(DESCRIPTION_LIMIT);
+    harness.app.take_edited_description(&full);
+    assert_eq!(harness.app.input_description, full);
+    assert!(harness.app.toasts.is_empty());
+
+    harness.app.take_edited_description(&format!("{full}!"));
+    assert_eq!(harness.app.input_description, full);
+    assert_eq!(
+        harness.app.toasts[0].message,
+        format!("Description cut to {DESCRIPTION_LIMIT} characters")
+    );
+}
+
+#[test]
+fn counters_count_characters_left() {
+    let mut harness = Harness::new(Vec::new());
+    harness
+        .press(KeyCode::Char('a'))
+        .type_text("Crème brûlée")
+        .press(KeyCode::Enter)
+        .type_text("🍰");
+    let screen = harness.screen();
+    assert!(screen.contains("38 left"));
+    assert!(screen.contains("254 left"));
+}
+
+#[test]
+fn same_name_warns_but_still_saves() {
+    let mut harness = Harness::new(vec![task("Write report", Progress::Waiting)]);
+    harness
+        .resize(160, 30)
+        .press(KeyCode::Char('a'))
+        .type_text("Write report");
+    assert!(harness
+        .screen()
+        .contains("Already an open task, created 2024-01-01 12:00:00"));
+
+    harness.press(KeyCode::Enter).press(KeyCode::Enter);
+    assert_eq!(harness.saved().len(), 2);
+}
+
+#[test]
+fn estimates_show_against_the_time_logged_and_add_up_for_the_week() {
+    let today = Local::now().date_naive().format(DUE_FORMAT).to_string();
+    let mut due = task("Write report", Progress::InProgress);
+    due.due = Some(today.clone());
+    let mut harness = Harness::new(vec![due, task("Someday", Progress::Waiting)]);
+    harness.app.time_log.record(Session {
+        task: "2024-01-01 12:00:00".to_string(),
+        name: "Write report".to_string(),
+        day: today,
+        seconds: 45 * 60,
+    });
+
+    harness
+        .press(KeyCode::Enter)
+        .press(KeyCode::Char('f'))
+        .type_text("estimate: two hours")
+        .press(KeyCode::Enter);
+    assert!(harness.screen().contains("Type the time as a duration"));
+    harness.press(KeyCode::Esc);
+    for _ in 0.."two hours".len() {
+        harness.press(KeyCode::Backspace);
+    }
+    harness.type_text("2h").press(KeyCode::Enter);
+    assert!(harness.screen().contains("| 2h 00m est / 45m logged"));
+    // What's left of the estimate, due today
+    harness.press(KeyCode::Esc);
+    assert!(harness.screen().contains(" 1h 15m left this week "));
+
+    harness
+        .press(KeyCode::Enter)
+        .press(KeyCode::Char('f'))
+        .type_text("remaining: 90m")
+        .press(KeyCode::Enter);
+    // The meta line is cut off at the terminal's width
+    assert!(harness.screen().contains("| 2h 00m est / 45m logged,"));
+    harness.press(KeyCode::Esc);
+    assert!(harness.screen().contains(" 1h 30m left this week "));
+
+    let saved = harness.saved();
+    assert_eq!(saved[0].estimate.as_deref(), Some("2h 00m"));
+    assert_eq!(saved[0].remaining.as_deref(), Some("1h 30m"));
+    assert!(saved[0].fields.is_empty());
+}
+
+#[test]
+fn reader_renders_markdown() {
+    let mut item = task("Release", Progress::Waiting);
+    item.description =
+        "Ship **v2** with `cargo publish`\n- update the *changelog*\n- keep snake_case".to_string();
+    let mut harness = Harness::new(vec![item]);
+    harness.press(KeyCode::Enter);
+    let screen = harness.screen();
+    assert!(screen.contains("Ship v2 with cargo publish"));
+    assert!(screen.contains("• update the changelog"));
+    assert!(screen.contains("• keep snake_case"));
+}
+
+#[test]
+fn todos_show_as_blocked_until_their_blockers_are_done() {
+    let mut vans = task("Book the van", Progress::Waiting);
+    vans.created = "2024-01-02 12:00:00".to_string();
+    let mut move_out = task("Move out", Progress::Waiting);
+    move_out.created = "2024-01-03 12:00:00".to_string();
+    let mut harness = Harness::new(vec![task("Order parts", Progress::Waiting), vans, move_out]);
+    harness
+        .press(KeyCode::Down)
+        .press(KeyCode::Down)
+        .press(KeyCode::Enter)
+        .press(KeyCode::Char('f'))
+        .type_text("blocked_by: order parts, Book the van, Nowhere")
+        .press(KeyCode::Enter);
+    assert!(harness
+        .screen()
+        .contains("Blocked by Order parts, Book the van"));
+
+    harness.press(KeyCode::Esc);
+    let screen = harness.screen();
+    let row = screen
+        .lines()
+        .find(|line| line.contains("Move out"))
+        .unwrap();
+    assert!(row.contains("Blocked Move out"));
+    assert_eq!(row.matches("Blocked").count(), 2, "badge and status: {row}");
+
+    // Clears once every blocker is done, one at a time
+    harness.app.items[0].progress = Progress::Done;
+    assert!(harness.screen().contains("Blocked Move out"));
+    harness.app.items[1].progress = Progress::Done;
+    let screen = harness.screen();
+    assert!(!screen.contains("Blocked"));
+    assert!(screen
+        .lines()
+        .any(|line| line.contains("Move out") && line.contains("Waiting")));
+}
+
+#[test]
+fn notes_are_logged_with_the_time_apart_from_the_description() {
+    let mut harness = Harness::new(vec![task("Order parts", Progress::Waiting)]);
+    harness
+        .press(KeyCode::Char('J'))
+        .type_text("asked the vendor")
+        .press(KeyCode::Enter)
+        .press(KeyCode::Enter)
+        .press(KeyCode::Char('a'))
+        .type_text("waiting on vendor reply")
+        .press(KeyCode::Enter);
+
+    // A blank note isn't logged
+    harness
+        .press(KeyCode::Char('a'))
+        .type_text("  ")
+        .press(KeyCode::Enter);
+
+    let screen = harness.screen();
+    assert!(screen.contains("Notes (2)"));
+    let first = screen.find("asked the vendor").unwrap();
+    assert!(first < screen.find("waiting on vendor reply").unwrap());
+
+    let saved = harness.saved();
+    assert_eq!(saved[0].description, "About Order parts");
+    let notes: Vec<&str> = saved[0]
+        .notes
+        .iter()
+        .map(|note| note.text.as_str())
+        .collect();
+    assert_eq!(notes, ["asked the vendor", "waiting on vendor reply"]);
+    assert!(saved[0].notes.iter().all(|note| !note.at.is_empty()));
+}
+
+#[test]
+fn custom_fields_are_set_in_the_reader_and_shown_as_columns() {
+    let mut harness = Harness::new(vec![task("Fix login", Progress::Waiting)]);
+    harness
+        .press(KeyCode::Enter)
+        .press(KeyCode::Char('f'))
+        .type_text("ticket: ABC-123")
+        .press(KeyCode::Enter)
+        .press(KeyCode::Char('f'))
+        .type_text("owner: Sam")
+        .press(KeyCode::Enter);
+    assert!(harness.screen().contains("| owner: Sam | ticket: ABC-123"));
+
+    // Without a colon it's kept for fixing; without a value it removes the field
+    harness
+        .press(KeyCode::Char('f'))
+        .type_text("oops")
+        .press(KeyCode::Enter);
+    assert!(harness.screen().contains("Type the field as key: value"));
+    harness
+        .press(KeyCode::Esc)
+        .press(KeyCode::Esc)
+        .press(KeyCode::Char('f'))
+        .type_text("owner:")
+        .press(KeyCode::Enter);
+
+    let saved = harness.saved();
+    assert_eq!(saved[0].fields.len(), 1);
+    assert_eq!(saved[0].fields["ticket"], "ABC-123");
+    let json = fs::read_to_string(harness.dir.join("data.json")).unwrap();
+    assert!(json.contains("\"fields\": {"));
+
+    let mut harness = Harness::with_preferences(
+        saved,
+        Preferences {
+            field_columns: vec!["ticket".to_string()],
+            ..Preferences::default()
+        },
+    );
+    let screen = harness.screen();
+    assert!(screen.lines().any(|line| line.contains("ticket")));
+    assert!(screen
+        .lines()
+        .any(|line| line.contains("Fix login") && line.contains("ABC-123")));
+}
+
+#[test]
+fn date_filters_narrow_the_table_and_the_search() {
+    let fresh = |name: &str| Data {
+        created: Utc::now().to_rfc3339(),
+        ..task(name, Progress::Waiting)
+    };
+    let mut shipped = fresh("Ship the release");
+    shipped.created = (Utc::now() - chrono::Duration::seconds(5)).to_rfc3339();
+    let mut harness = Harness::new(vec![
+        task("Old report", Progress::Done),
+        fresh("Fresh idea"),
+        shipped,
+    ]);
+    harness
+        .press(KeyCode::Down)
+        .press(KeyCode::Down)
+        .press(KeyCode::Char('3'));
+    assert!(harness.saved()[2].completed.is_some());
+    assert!(harness.saved()[0].completed.is_none());
+
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text("filter completed:today")
+        .press(KeyCode::Enter);
+    let screen = harness.screen();
+    assert!(screen.contains("Ship the release"));
+    assert!(!screen.contains("Fresh idea"));
+    assert!(!screen.contains("Old report"));
+    assert!(screen.contains(" Filter: completed:today "));
+
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text("filter created:2024-01-01")
+        .press(KeyCode::Enter);
+    let screen = harness.screen();
+    assert!(screen.contains("Old report"));
+    assert!(!screen.contains("Ship the release"));
+
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text("filter created:someday");
+    assert!(harness.screen().contains("Unknown date range \"someday\""));
+    harness
+        .press(KeyCode::Esc)
+        .press(KeyCode::Char(':'))
+        .type_text("filter")
+        .press(KeyCode::Enter);
+    assert!(harness.screen().contains("Fresh idea"));
+    assert!(!harness.screen().contains("Filter:"));
+
+    // The same ranges in the search, next to the words
+    harness
+        .press(KeyCode::Char('/'))
+        .type_text("created:..2024-12-31 report");
+    assert_eq!(harness.app.search_results().len(), 1);
+
+    // Relative ranges follow the week start
+    let bounds = |term: &str, week_start| {
+        DateFilter::parse(term)
+            .unwrap()
+            .unwrap()
+            .range
+            .bounds(NaiveDate::from_ymd_opt(2024, 3, 13).unwrap(), week_start)
+    };
+    let day = |month, day| NaiveDate::from_ymd_opt(2024, month, day);
+    assert_eq!(
+        bounds("created:last-month", WeekStart::Monday),
+        (day(2, 1), day(2, 29))
+    );
+    assert_eq!(
+        bounds("created:this-week", WeekStart::Monday),
+        (day(3, 11), day(3, 17))
+    );
+    assert_eq!(
+        bounds("done:last-week", WeekStart::Sunday),
+        (day(3, 3), day(3, 9))
+    );
+    assert_eq!(
+        bounds("created:7d", WeekStart::Monday),
+        (day(3, 7), day(3, 13))
+    );
+}
+
+#[test]
+fn paste_inserts_the_whole_text() {
+    let mut harness = Harness::new(Vec::new());
+    harness
+        .press(KeyCode::Char('a'))
+        .paste("Read https://ratatui.rs/concepts\nand take notes")
+        .press(KeyCode::Enter)
+        .paste(&"x".repeat(300))
+        .press(KeyCode::Enter);
+
+    let saved = harness.saved();
+    assert_eq!(
+        saved[0].name,
+        "Read https://ratatui.rs/concepts and take notes"
+    );
+    assert_eq!(saved[0].description.len(), 255);
+}
+
+#[test]
+fn paste_stops_at_the_typing_limit_in_characters() {
+    let mut harness = Harness::new(Vec::new());
+    harness.press(KeyCode::Char('N')).type_text("Café ");
+    harness.paste(&"ø".repeat(40));
+    // The same 30 characters typing allows, however many bytes they take
+    assert_eq!(
+        harness.app.input_list_name,
+        format!("Café {}", "ø".repeat(25))
+    );
+}
+
+#[test]
+fn edit_updates_the_selected_task() {
+    let mut harness = Harness::new(vec![task("Write report", Progress::Waiting)]);
+    harness
+        .press(KeyCode::Char('r'))
+        .type_text(" today")
+        .press(KeyCode::Enter)
+        .press(KeyCode::Enter);
+
+    assert!(harness.screen().contains("Write report today"));
+    assert_eq!(harness.saved()[0].name, "Write report today");
+}
+
+#[test]
+fn edit_keeps_the_status_unless_changed() {
+    let mut harness = Harness::new(vec![task("Renew passport", Progress::Done)]);
+    harness
+        .press(KeyCode::Char('r'))
+        .press(KeyCode::Enter)
+        .press(KeyCode::Enter);
+    assert_eq!(harness.saved()[0].progress, Progress::Done);
+    assert_eq!(harness.saved()[0].created, "2024-01-01 12:00:00");
+
+    harness
+        .press(KeyCode::Char('r'))
+        .press(KeyCode::Tab)
+        .press(KeyCode::Left)
+        .press(KeyCode::Tab)
+        .press(KeyCode::Left)
+        .press(KeyCode::Enter);
+    let saved = &harness.saved()[0];
+    assert_eq!(saved.progress, Progress::InProgress);
+    assert_eq!(saved.priority, Some(Priority::High));
+}
+
+#[test]
+fn edit_round_trips_what_the_popup_doesnt_show() {
+    let todo: Data = serde_json::from_str(
+        r#"{"name": "Renew passport", "description": "Photos first", "progress": "Done",
+            "created": "2024-01-01T12:00:00Z", "starred": true, "percent": 40,
+            "completed": "2024-02-01T09:00:00Z", "fields": {"ticket": "ABC-123"},
+            "source": "jira"}"#,
+    )
+    .unwrap();
+    let mut harness = Harness::new(vec![todo]);
+    let before = fs::read_to_string(harness.dir.join("data.json")).unwrap();
+
+    // Saving without changes writes nothing new
+    harness
+        .press(KeyCode::Char('r'))
+        .press(KeyCode::Enter)
+        .press(KeyCode::Enter);
+    let unchanged = serde_json::to_value(&harness.saved()[0]).unwrap();
+    let original: serde_json::Value = serde_json::from_str(&before).unwrap();
+    assert_eq!(unchanged, original[0]);
+
+    harness
+        .press(KeyCode::Char('r'))
+        .type_text(" soon")
+        .press(KeyCode::Enter)
+        .press(KeyCode::Enter);
+    let saved = &harness.saved()[0];
+    assert_eq!(saved.name, "Renew passport soon");
+    assert!(saved.updated.is_some());
+    assert_eq!(saved.created, "2024-01-01T12:00:00Z");
+    assert_eq!(saved.progress, Progress::Done);
+    assert_eq!(saved.completed.as_deref(), Some("2024-02-01T09:00:00Z"));
+    assert_eq!(saved.fields["ticket"], "ABC-123");
+    assert_eq!((saved.starred, saved.percent), (true, Some(40)));
+    assert_eq!(saved.extra["source"], "jira");
+
+    harness.press(KeyCode::Enter);
+    assert!(harness.screen().contains("Updated: "));
+}
+
+#[test]
+fn an_empty_table_says_how_to_fill_it() {
+    let mut harness = Harness::new(Vec::new());
+    let screen = harness.screen();
+    assert!(screen.contains("No todos yet"));
+    assert!(screen.contains("Press A to add one"));
+
+    let mut harness = Harness::new(vec![task("Renew passport", Progress::Done)]);
+    harness.press(KeyCode::Char('t'));
+    let screen = harness.screen();
+    assert!(screen.contains("No todos match"));
+    assert!(screen.contains("Press T to show completed todos too"));
+
+    harness.press(KeyCode::Char('t'));
+    assert!(!harness.screen().contains("No todos match"));
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text("filter created:today")
+        .press(KeyCode::Enter);
+    assert!(harness.screen().contains(":filter without ranges"));
+}
+
+#[test]
+fn navigating_an_empty_or_filtered_table_does_nothing() {
+    let empty = ViewModel::new(Vec::new(), Some(0), 1);
+    assert_eq!(
+        (empty.selected(), empty.next_row(), empty.previous_row()),
+        (None, None, None)
+    );
+    let single = ViewModel::new(vec![Entry::Task(0)], None, 1);
+    assert_eq!(
+        (single.next_row(), single.previous_row()),
+        (Some(0), Some(0))
+    );
+
+    let mut harness = Harness::new(Vec::new());
+    for key in ['j', 'k', 'x', 'r', 'n', '3', '+', '-', 'f', 's'] {
+        harness.press(KeyCode::Char(key));
+    }
+    harness
+        .press(KeyCode::Down)
+        .press(KeyCode::Up)
+        .press(KeyCode::Enter)
+        .press(KeyCode::Left)
+        .press(KeyCode::Right);
+    assert_eq!(harness.app.state.selected(), None);
+    assert!(harness.screen().contains("No todos yet"));
+
+    let mut harness = Harness::new(vec![
+        task("Renew passport", Progress::Done),
+        task("Buy milk", Progress::Waiting),
+    ]);
+    harness.press(KeyCode::Down).press(KeyCode::Char('3'));
+    harness
+        .press(KeyCode::Char('t'))
+        .press(KeyCode::Down)
+        .press(KeyCode::Up);
+    assert_eq!(harness.app.state.selected(), None);
+    assert!(harness.app.selected_item().is_none());
+
+    // Back to the todo it was on once they show again
+    harness.press(KeyCode::Char('t'));
+    assert_eq!(
+        harness.app.selected_item().map(|item| item.name.as_str()),
+        Some("Buy milk")
+    );
+}
+
+#[test]
+fn delete_removes_the_selected_task() {
+    let mut harness = Harness::new(vec![
+        task("First", Progress::Waiting),
+        task("Second", Progress::Waiting),
+    ]);
+    harness.press(KeyCode::Char('j')).press(KeyCode::Char('x'));
+
+    let screen = harness.screen();
+    assert!(screen.contains("First"));
+    assert!(!screen.contains("Second"));
+    assert_eq!(harness.saved().len(), 1);
+}
+
+#[test]
+fn hiding_completed_filters_done_tasks() {
+    let mut harness = Harness::new(vec![
+        task("Open task", Progress::Waiting),
+        task("Finished task", Progress::Done),
+    ]);
+    assert!(harness.screen().contains("Finished task"));
+    assert!(harness
+        .screen()
+        .contains(" 1 open · 0 in progress · 1 done "));
+
+    // The footer still counts what's hidden
+    harness.press(KeyCode::Char('t'));
+    let screen = harness.screen();
+    assert!(screen.contains("Open task"));
+    assert!(!screen.contains("Finished task"));
+    assert!(screen.contains(" 1 open · 0 in progress · 1 done (hidden) "));
+}
+
+#[test]
+fn the_age_column_shows_time_since_creation() {
+    let created_ago = |name: &str, ago: chrono::Duration| Data {
+        created: (Utc::now() - ago).to_rfc3339(),
+        ..task(name, Progress::Waiting)
+    };
+    let todos = vec![
+        created_ago("Fresh", chrono::Duration::hours(3)),
+        created_ago("Older", chrono::Duration::days(40)),
+    ];
+    let mut harness = Harness::new(todos.clone());
+    assert!(!harness.screen().contains(" Age "));
+
+    let mut harness = Harness::with_preferences(
+        todos,
+        Preferences {
+            show_age: true,
+            ..Preferences::default()
+        },
+    );
+    let screen = harness.screen();
+    assert!(screen.contains(" Age "));
+    assert!(screen
+        .lines()
+        .any(|line| line.contains("Fresh") && line.contains(" 3h ")));
+    assert!(screen
+        .lines()
+        .any(|line| line.contains("Older") && line.contains(" 5w ")));
+
+    harness.press(KeyCode::Char(','));
+    assert!(harness
+        .screen()
+        .lines()
+        .any(|line| line.contains("Show Age") && line.contains("[✔]")));
+}
+
+#[test]
+fn the_focused_preview_pane_edits_every_field() {
+    let mut todo = task("Buy milk #shop", Progress::Waiting);
+    todo.description = "From the corner".to_string();
+    let mut harness = Harness::with_preferences(
+        vec![todo],
+        Preferences {
+            layout: ViewLayout::Split,
+            ..Preferences::default()
+        },
+    );
+    harness.press(KeyCode::Tab).press(KeyCode::Tab);
+    assert!(harness.screen().contains("Description"));
+
+    harness.press(KeyCode::Enter);
+    assert_eq!(harness.saved()[0].progress, Progress::InProgress);
+    assert!(harness.saved()[0].updated.is_some());
+    harness.press(KeyCode::Down).press(KeyCode::Right);
+    assert_eq!(harness.saved()[0].priority, Some(Priority::Low));
+
+    // A typo keeps the date open to fix, Esc leaves it as it was
+    harness.press(KeyCode::Down).press(KeyCode::Enter);
+    harness.type_text("2024-13-01").press(KeyCode::Enter);
+    assert!(harness.app.preview_input.is_some());
+    harness.press(KeyCode::Esc).press(KeyCode::Enter);
+    harness.type_text("2024-02-01").press(KeyCode::Enter);
+    assert_eq!(harness.saved()[0].due.as_deref(), Some("2024-02-01"));
+
+    harness.press(KeyCode::Down).press(KeyCode::Enter);
+    harness.type_text("someday").press(KeyCode::Enter);
+    assert!(harness.app.preview_input.is_some());
+    harness.press(KeyCode::Esc).press(KeyCode::Enter);
+    harness
+        .type_text("2024-03-01 14:30, fri 9am")
+        .press(KeyCode::Enter);
+    let remind = harness.saved()[0].remind.clone();
+    assert_eq!(remind.len(), 2);
+    assert_eq!(remind[0], "2024-03-01 14:30");
+
+    harness.press(KeyCode::Down).press(KeyCode::Enter);
+    assert!(harness.screen().contains("Tags: #shop_"));
+    for _ in 0..5 {
+        harness.press(KeyCode::Backspace);
+    }
+    harness.type_text("#errands, home").press(KeyCode::Enter);
+    harness.press(KeyCode::Down).press(KeyCode::Enter);
+    harness.type_text(" today").press(KeyCode::Enter);
+
+    let saved = harness.saved();
+    assert_eq!(saved[0].name, "Buy milk");
+    assert_eq!(saved[0].description, "From the corner #errands #home today");
+    assert!(harness.screen().contains("Tags: #errands #home"));
+}
+
+#[test]
+fn the_tag_popup_applies_every_checked_tag_at_once() {
+    let mut harness = Harness::new(vec![
+        task("Buy milk #shop", Progress::Waiting),
+        task("Pay rent #home", Progress::Waiting),
+    ]);
+    harness.app.items[0].description = "On the way #errands".to_string();
+    harness.press(KeyCode::Char('#'));
+    let screen = harness.screen();
+    for line in ["[✔] #errands", "[ ] #home", "[✔] #shop"] {
+        assert!(screen.contains(line));
+    }
+
+    // Nothing changes until it's applied
+    harness.press(KeyCode::Char(' ')).press(KeyCode::Esc);
+    assert!(!harness.app.show_tags);
+    assert_eq!(harness.app.items[0].tags(), ["errands", "shop"]);
+
+    harness.press(KeyCode::Char('#')).press(KeyCode::Char(' '));
+    harness.press(KeyCode::Down).press(KeyCode::Char(' '));
+    harness.type_text("#Urgent").press(KeyCode::Enter);
+    assert!(harness.screen().contains("[✔] #urgent"));
+    harness.press(KeyCode::Enter);
+    assert!(!harness.app.show_tags);
+
+    let saved = harness.saved();
+    assert_eq!(saved[0].tags(), ["home", "shop", "urgent"]);
+    assert_eq!(saved[0].description, "On the way #home #urgent");
+    assert!(saved[0].updated.is_some());
+    assert_eq!(saved[1].tags(), ["home"]);
+}
+
+#[test]
+fn list_names_are_limited_in_characters_not_bytes() {
+    let mut harness = Harness::new(Vec::new());
+    harness.press(KeyCode::Char('N')).type_text(&"é".repeat(31));
+    assert_eq!(harness.app.input_list_name, "é".repeat(30));
+    harness.press(KeyCode::Esc);
+    assert!(!harness.app.show_new_list);
+}
+
+#[test]
+fn every_input_limit_counts_characters() {
+    let mut harness = Harness::new(Vec::new());
+    harness
+        .press(KeyCode::Char('o'))
+        .type_text(&"é".repeat(NAME_LIMIT + 1));
+    assert_eq!(harness.app.input_quick_add.chars().count(), NAME_LIMIT);
+    harness.press(KeyCode::Esc).press(KeyCode::Char('a'));
+    harness.type_text(&"日".repeat(NAME_LIMIT + 1));
+    assert_eq!(harness.app.input_name.chars().count(), NAME_LIMIT);
+}
+
+#[test]
+fn quick_add_keeps_the_prompt_open_for_the_next_todo() {
+    let mut harness = Harness::new(vec![task("Buy milk", Progress::Waiting)]);
+    harness.press(KeyCode::Char('o')).type_text("Call mum");
+    assert!(harness.screen().contains("Quick add"));
+    harness.press(KeyCode::Enter);
+    assert!(harness.app.show_quick_add);
+    harness.paste("Book dentist").press(KeyCode::Enter);
+    harness.press(KeyCode::Enter);
+    assert!(!harness.app.show_quick_add);
+
+    let saved = harness.saved();
+    let names: Vec<&str> = saved.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(names, ["Buy milk", "Call mum", "Book dentist"]);
+    assert_eq!(saved[2].progress, Progress::Waiting);
+    // Added within the same second, yet each still has its own creation time
+    assert_ne!(saved[1].created, saved[2].created);
+
+    // The status new todos start with is a preference, the popup's default too
+    harness.app.preferences.new_status = Progress::InProgress;
+    harness
+        .press(KeyCode::Char('o'))
+        .type_text("Water plants")
+        .press(KeyCode::Enter)
+        .press(KeyCode::Esc);
+    assert_eq!(harness.saved()[3].progress, Progress::InProgress);
+    harness.press(KeyCode::Char('a'));
+    assert_eq!(harness.app.input_progress, Progress::InProgress);
+}
+
+#[test]
+fn the_settings_screen_shows_and_changes_the_settings() {
+    let mut harness = Harness::with_preferences(
+        vec![
+            task("Buy milk", Progress::Waiting),
+            task("Renew passport", Progress::Done),
+        ],
+        Preferences {
+            lock_color: true,
+            density: Density::Compact,
+            ..Preferences::default()
+        },
+    );
+    harness.press(KeyCode::Char(','));
+    let screen = harness.screen();
+    let row = |label: &str| {
+        screen
+            .lines()
+            .find(|line| line.contains(label))
+            .unwrap_or_default()
+            .to_string()
+    };
+    for group in ["Appearance", "Behavior", "Storage"] {
+        assert!(screen.contains(group));
+    }
+    assert!(row("Lock Color").contains("[✔]"));
+    assert!(row("Row Density").contains("Compact"));
+    assert!(row("Week Starts On").contains("Monday"));
+    assert!(row("New Todo Status").contains("Waiting"));
+    assert!(row("Data Folder").contains("Default"));
+    for unchecked in ["Hide Completed", "Confirm Delete", "Relative Dates"] {
+        assert!(row(unchecked).contains("[ ]"));
+    }
+    assert!(row("Strike Done").contains("[✔]"));
+    assert!(row("Show Clock").contains("[ ]"));
+
+    // Hide Completed belongs to the view, so it's changed without saving preferences
+    for _ in 0..8 {
+        harness.press(KeyCode::Down);
+    }
+    harness.press(KeyCode::Enter);
+    let screen = harness.screen();
+    assert!(screen
+        .lines()
+        .any(|line| line.contains("Hide Completed") && line.contains("[✔]")));
+    assert!(screen.contains("Leave done todos out"));
+    harness.press(KeyCode::Esc).press(KeyCode::Char('t'));
+    harness.press(KeyCode::Char(','));
+    assert!(harness
+        .screen()
+        .lines()
+        .any(|line| line.contains("Hide Completed") && line.contains("[ ]")));
+
+    // Text is typed in, and Esc leaves it as it was
+    for _ in 0..5 {
+        harness.press(KeyCode::Down);
+    }
+    harness.press(KeyCode::Enter).type_text("/tmp/todos");
+    assert!(harness.screen().contains("/tmp/todos_"));
+    harness.press(KeyCode::Esc);
+    assert_eq!(harness.app.preferences.data_dir, None);
+    assert!(harness.app.show_settings);
+    harness.press(KeyCode::Esc);
+    assert!(!harness.app.show_settings);
+
+    harness.app.step_setting(Setting::Density, false);
+    assert_eq!(harness.app.preferences.density, Density::Full);
+    harness.app.step_setting(Setting::Theme, true);
+    assert_eq!(harness.app.color_index, 1);
+    harness.app.step_setting(Setting::RelativeDates, true);
+    assert!(harness.screen().contains(" ago "));
+
+    harness.app.step_setting(Setting::ConfirmDelete, true);
+    harness.press(KeyCode::Char('x'));
+    assert_eq!(harness.saved().len(), 2);
+    assert!(harness.app.toasts[0]
+        .message
+        .contains("Press X again to delete \"Buy milk\""));
+    // Anything else in between calls it off
+    harness
+        .press(KeyCode::Down)
+        .press(KeyCode::Up)
+        .press(KeyCode::Char('x'));
+    assert_eq!(harness.saved().len(), 2);
+    harness.press(KeyCode::Char('x'));
+    assert_eq!(harness.saved().len(), 1);
+}
+
+#[test]
+fn sort_modes_order_the_table_and_show_in_the_footer() {
+    let today = chrono::Local::now().date_naive();
+    let todos = vec![
+        Data {
+            priority: Some(Priority::High),
+            ..task("Bake bread", Progress::Waiting)
+        },
+        Data {
+            due: Some(
+                (today - chrono::Days::new(1))
+                    .format(DUE_FORMAT)
+                    .to_string(),
+            ),
+            priority: Some(Priority::Low),
+            ..task("Call the bank", Progress::Waiting)
+        },
+        Data {
+            due: Some(
+                (today + chrono::Days::new(30))
+                    .format(DUE_FORMAT)
+                    .to_string(),
+            ),
+            ..task("Apply for visa", Progress::Waiting)
+        },
+    ];
+    let order = |sort: SortMode| {
+        let mut harness = Harness::with_preferences(
+            todos.clone(),
+            Preferences {
+                sort,
+                ..Preferences::default()
+            },
+        );
+        let screen = harness.screen();
+        assert!(screen.contains(&format!(" Sort: {} ", sort.label())));
+        let mut names = ["Bake bread", "Call the bank", "Apply for visa"];
+        names.sort_by_key(|name| screen.find(name));
+        names
+    };
+
+    assert_eq!(
+        order(SortMode::Manual),
+        ["Bake bread", "Call the bank", "Apply for visa"]
+    );
+    assert_eq!(
+        order(SortMode::Due),
+        ["Call the bank", "Apply for visa", "Bake bread"]
+    );
+    assert_eq!(
+        order(SortMode::Priority),
+        ["Bake bread", "Call the bank", "Apply for visa"]
+    );
+    // Overdue outweighs a high priority
+    assert_eq!(
+        order(SortMode::Urgency),
+        ["Call the bank", "Bake bread", "Apply for visa"]
+    );
+    assert_eq!(
+        order(SortMode::Name),
+        ["Apply for visa", "Bake bread", "Call the bank"]
+    );
+
+    let mut sort = SortMode::Manual;
+    let mut labels = Vec::new();
+    for _ in 0..6 {
+        sort = sort.next();
+        labels.push(sort.label());
+    }
+    assert_eq!(
+        labels,
+        ["created", "due", "priority", "urgency", "name", "manual"]
+    );
+}
+
+#[test]
+fn the_selection_follows_its_todo_as_the_rows_change() {
+    let mut harness = Harness::new(vec![
+        task("Old report", Progress::Done),
+        task("Buy milk", Progress::Waiting),
+        task("Answer mail", Progress::Waiting),
+        task("Walk the dog", Progress::Waiting),
+    ]);
+    let selected = |harness: &Harness| harness.app.selected_item().map(|item| item.name.clone());
+    harness.press(KeyCode::Down).press(KeyCode::Down);
+    assert_eq!(selected(&harness).as_deref(), Some("Answer mail"));
+
+    // Hiding the row above moves it up a row
+    harness.press(KeyCode::Char('t'));
+    assert_eq!(selected(&harness).as_deref(), Some("Answer mail"));
+    assert_eq!(harness.app.state.selected(), Some(1));
+
+    // And so does sorting
+    harness.app.preferences.sort = SortMode::Name;
+    harness.app.update_selected_index();
+    assert_eq!(selected(&harness).as_deref(), Some("Answer mail"));
+    assert_eq!(harness.app.state.selected(), Some(0));
+
+    // A deleted todo hands the selection to the one that took its row
+    harness.press(KeyCode::Char('x'));
+    assert_eq!(selected(&harness).as_deref(), Some("Buy milk"));
+    harness.app.preferences.sort = SortMode::Manual;
+    harness.press(KeyCode::Char('t'));
+    assert_eq!(selected(&harness).as_deref(), Some("Buy milk"));
+    assert_eq!(harness.app.state.selected(), Some(1));
+}
+
+#[test]
+fn the_scrollbar_thumb_covers_the_rows_in_view() {
+    let todos = (0..80)
+        .map(|i| task(&format!("Todo {i}"), Progress::Waiting))
+        .collect();
+    let mut harness = Harness::with_preferences(
+        todos,
+        Preferences {
+            density: Density::Compact,
+            ..Preferences::default()
+        },
+    );
+    // The track runs down the table's right edge, between its borders
+    let track = |harness: &mut Harness| {
+        let screen = harness.screen();
+        screen
+            .lines()
+            .filter_map(|line| line.chars().nth(98))
+            .filter(|&c| c == '█' || c == '║')
+            .collect::<String>()
+    };
+
+    let top = track(&mut harness);
+    let thumb = top.chars().filter(|&c| c == '█').count();
+    assert!(top.starts_with('█'), "{top:?}\n{}", harness.screen());
+    assert!(
+        thumb > 0 && thumb < top.chars().count() / 2,
+        "{top:?}\n{}",
+        harness.screen()
+    );
+
+    // Wrapping to the last todo scrolls the thumb to the end
+    harness.press(KeyCode::Up);
+    let bottom = track(&mut harness);
+    assert!(bottom.ends_with('█') && bottom.starts_with('║'));
+    assert_eq!(bottom.chars().filter(|&c| c == '█').count(), thumb);
+
+    // Once every todo fits, there's nothing to scroll
+    for _ in 0..75 {
+        harness.press(KeyCode::Char('3')).press(KeyCode::Down);
+    }
+    harness.press(KeyCode::Char('t'));
+    assert_eq!(track(&mut harness), "");
+}
+
+#[test]
+fn cut_off_text_ends_in_an_ellipsis_and_enter_shows_all_of_it() {
+    let long = Data {
+        description: format!("{} the end", "word ".repeat(30)),
+        ..task("Write the report", Progress::Waiting)
+    };
+    let mut harness = Harness::with_preferences(
+        vec![long],
+        Preferences {
+            density: Density::Compact,
+            ..Preferences::default()
+        },
+    );
+    let screen = harness.screen();
+    assert!(screen.contains("…"));
+    assert!(!screen.contains("the end"));
+
+    harness.press(KeyCode::Enter);
+    assert!(harness.screen().contains("the end"));
+
+    // Wide characters move to the next line whole
+    assert_eq!(crate::wrap_text("日本語", 5, 2), "日本\n語");
+    assert_eq!(crate::wrap_text("日本語です", 5, 2), "日本\n語で…");
+}
+
+#[test]
+fn ctrl_c_quits_and_asks_again_with_a_draft_open() {
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    let mut harness = Harness::new(vec![task("Buy milk", Progress::Waiting)]);
+    harness.press(KeyCode::Char('a')).type_text("Call");
+    assert!(harness.app.handle_key(ctrl_c));
+    assert_eq!(harness.app.input_name, "Call");
+    assert!(harness.app.toasts[0].message.contains("press Ctrl+C again"));
+
+    // Any other key in between asks again
+    harness.type_text(" mum");
+    assert!(harness.app.handle_key(ctrl_c));
+    assert_eq!(harness.app.input_name, "Call mum");
+    assert!(!harness.app.handle_key(ctrl_c));
+
+    let mut harness = Harness::new(vec![task("Buy milk", Progress::Waiting)]);
+    harness.press(KeyCode::Char('i'));
+    assert!(!harness.app.handle_key(ctrl_c));
+}
+
+#[test]
+fn a_crashed_sessions_draft_and_unsaved_todos_are_restored() {
+    let mut harness = Harness::new(vec![task("Buy milk", Progress::Waiting)]);
+    let list = harness.app.lists[0].path.clone();
+    harness.app.restore(Recovery {
+        list,
+        draft_name: "Call the dentist".to_string(),
+        draft_description: "Before Friday".to_string(),
+        editing_index: None,
+        unsaved_items: Some(vec![
+            task("Buy milk", Progress::Done),
+            task("Pay rent", Progress::Waiting),
+        ]),
+    });
+
+    // The todos that couldn't be saved are saved now
+    let names: Vec<String> = harness.saved().into_iter().map(|item| item.name).collect();
+    assert_eq!(names, ["Buy milk", "Pay rent"]);
+    assert_eq!(harness.saved()[0].progress, Progress::Done);
+    assert!(harness.app.show_create);
+    assert_eq!(harness.app.input_name, "Call the dentist");
+    assert_eq!(harness.app.input_description, "Before Friday");
+    assert_eq!(
+        harness.app.toasts[0].message,
+        "Recovered unsaved changes from the last session"
+    );
+}
+
+#[test]
+fn a_recovered_edit_of_a_todo_gone_since_is_added_instead() {
+    let mut harness = Harness::new(vec![task("Buy milk", Progress::Waiting)]);
+    let list = harness.app.lists[0].path.clone();
+    harness.app.restore(Recovery {
+        list,
+        draft_name: "Buy oat milk".to_string(),
+        draft_description: String::new(),
+        editing_index: Some(3),
+        unsaved_items: None,
+    });
+    assert_eq!(harness.app.editing_index, None);
+
+    harness.press(KeyCode::Enter).press(KeyCode::Enter);
+    let names: Vec<String> = harness.saved().into_iter().map(|item| item.name).collect();
+    assert_eq!(names, ["Buy milk", "Buy oat milk"]);
+}
+
+#[test]
+fn the_window_title_counts_todos_due_today() {
+    let today = Local::now().date_naive();
+    let due = |name: &str, progress: Progress, days_ago: u64| Data {
+        due: Some(
+            (today - chrono::Days::new(days_ago))
+                .format(DUE_FORMAT)
+                .to_string(),
+        ),
+        ..task(name, progress)
+    };
+    let mut harness = Harness::new(vec![
+        due("Pay rent", Progress::Waiting, 0),
+        due("File taxes", Progress::InProgress, 0),
+        due("Renew passport", Progress::Waiting, 3),
+        due("Book flights", Progress::Done, 0),
+        task("Someday", Progress::Waiting),
+    ]);
+    assert_eq!(
+        harness.app.window_title(),
+        "todo-tui — 2 due today, 1 overdue"
+    );
+
+    harness.press(KeyCode::Char('3'));
+    assert_eq!(
+        harness.app.window_title(),
+        "todo-tui — 1 due today, 1 overdue"
+    );
+    harness.press(KeyCode::Down).press(KeyCode::Char('3'));
+    assert_eq!(harness.app.window_title(), "todo-tui — 1 overdue");
+    harness.press(KeyCode::Down).press(KeyCode::Char('3'));
+    assert_eq!(harness.app.window_title(), "todo-tui");
+}
+
+#[test]
+fn the_clock_header_shows_the_date_and_todos_due_today() {
+    let today = Local::now().date_naive();
+    let mut harness = Harness::with_preferences(
+        vec![
+            Data {
+                due: Some(today.format(DUE_FORMAT).to_string()),
+                ..task("Pay rent", Progress::Waiting)
+            },
+            task("Someday", Progress::Waiting),
+        ],
+        Preferences {
+            show_clock: true,
+            ..Preferences::default()
+        },
+    );
+    let screen = harness.screen();
+    let header = screen.lines().next().unwrap();
+    assert!(header.contains(&today.format("%a %Y-%m-%d").to_string()));
+    assert!(header.contains("1 due today"));
+
+    harness.press(KeyCode::Char('3'));
+    assert!(harness
+        .screen()
+        .lines()
+        .next()
+        .unwrap()
+        .contains("Nothing due today"));
+
+    harness.app.preferences.show_clock = false;
+    let screen = harness.screen();
+    assert!(!screen.contains("Nothing due today"));
+    assert!(!screen
+        .lines()
+        .next()
+        .unwrap()
+        .contains(&today.format("%Y-%m-%d").to_string()));
+}
+
+#[test]
+fn each_list_and_layout_keeps_its_own_sort_and_filters() {
+    let mut harness = Harness::new(vec![
+        task("Old report", Progress::Done),
+        task("Buy milk", Progress::Waiting),
+    ]);
+    let work = harness.dir.join("work.json");
+    save_json(&work, &[task("Review PR", Progress::Done)]).unwrap();
+    harness.app.lists.push(TodoList {
+        name: "Work".to_string(),
+        path: work,
+        items: vec![task("Review PR", Progress::Done)],
+        state: TableState::default().with_selected(0),
+        ..TodoList::default()
+    });
+
+    harness.app.preferences.sort = SortMode::Name;
+    harness
+        .press(KeyCode::Char('t'))
+        .press(KeyCode::Char(':'))
+        .type_text("filter created:2024-01-01")
+        .press(KeyCode::Enter);
+    assert!(!harness.screen().contains("Old report"));
+
+    // A list that wasn't used yet starts out unfiltered
+    harness.app.switch_list(1);
+    assert!(harness.screen().contains("Review PR"));
+    assert_eq!(harness.app.preferences.sort, SortMode::Manual);
+    assert!(harness.app.date_filters.is_empty());
+
+    harness.app.switch_list(0);
+    assert!(harness.app.hide_completed);
+    assert_eq!(harness.app.preferences.sort, SortMode::Name);
+    assert_eq!(harness.app.date_filters.len(), 1);
+    assert!(!harness.screen().contains("Old report"));
+
+    // The kanban board has its own, and the split layout shares the table's
+    harness.app.set_layout(ViewLayout::Kanban);
+    assert!(!harness.app.hide_completed);
+    harness.app.set_layout(ViewLayout::Split);
+    assert!(harness.app.hide_completed);
+
+    // And they're back after a restart
+    let saved = fs::read_to_string(harness.dir.join("view_state.json")).unwrap();
+    assert!(saved.contains("\"data.json table\""));
+    assert!(saved.contains("\"created:2024-01-01\""));
+    let list = TodoList {
+        name: "Todo".to_string(),
+        path: harness.dir.join("data.json"),
+        items: harness.saved(),
+        state: TableState::default().with_selected(0),
+        ..TodoList::default()
+    };
+    let app = App::with_lists(vec![list], Preferences::default(), None);
+    assert!(app.hide_completed);
+    assert_eq!(app.preferences.sort, SortMode::Name);
+}
+
+#[test]
+fn grouping_by_date_adds_headers_and_skips_them() {
+    let today = chrono::Local::now().date_naive();
+    let due_in = |name: &str, days: Option<u64>| Data {
+        due: days.map(|days| {
+            (today + chrono::Days::new(days))
+                .format(DUE_FORMAT)
+                .to_string()
+        }),
+        ..task(name, Progress::Waiting)
+    };
+    let mut harness = Harness::with_preferences(
+        vec![
+            due_in("Someday", None),
+            due_in("Next month", Some(40)),
+            due_in("Right now", Some(0)),
+        ],
+        Preferences {
+            group_by_date: true,
+            ..Preferences::default()
+        },
+    );
+
+    let screen = harness.screen();
+    let position = |text: &str| screen.find(text).unwrap();
+    assert!(position("Today") < position("Right now"));
+    assert!(position("Right now") < position("Later"));
+    assert!(position("Later") < position("Next month"));
+    assert!(position("No due date") < position("Someday"));
+
+    // The selection starts below the first header and steps over the others
+    harness.press(KeyCode::Char('j')).press(KeyCode::Char('n'));
+    let saved = harness.saved();
+    assert_eq!(saved[1].progress, Progress::Done);
+    assert_eq!(saved[2].progress, Progress::Waiting);
+}
+
+#[test]
+fn moving_up_wraps_within_the_visible_rows() {
+    let mut harness = Harness::new(vec![
+        task("First", Progress::Waiting),
+        task("Finished", Progress::Done),
+        task("Last", Progress::Waiting),
+    ]);
+    harness
+        .press(KeyCode::Char('t'))
+        .press(KeyCode::Char('k'))
+        .press(KeyCode::Char('n'));
+
+    let saved = harness.saved();
+    assert_eq!(saved[0].progress, Progress::Waiting);
+    assert_eq!(saved[2].progress, Progress::Done);
+}
+
+#[test]
+fn run_stops_on_quit() {
+    let mut harness = Harness::new(vec![task("Only task", Progress::Waiting)]);
+    let mut events: VecDeque<Event> = [KeyCode::Char('i'), KeyCode::Esc, KeyCode::Char('q')]
+        .into_iter()
+        .map(|code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+        .collect();
+
+    let Harness { app, terminal, .. } = &mut harness;
+    app.run(terminal, |_| {
+        events
+            .pop_front()
+            .map(Some)
+            .ok_or_else(|| io::Error::other("ran out of events before quitting"))
+    })
+    .unwrap();
+    assert!(events.is_empty());
+}
+
+/// A fixed board for snapshots. Age badges are turned off so the output
+/// doesn't depend on today's date.
+fn snapshot_harness() -> Harness {
+    let preferences = Preferences {
+        stale_days: StaleDays {
+            warning: i64::MAX,
+            alert: i64::MAX,
+        },
+        ..Preferences::default()
+    };
+    let mut starred = task("Pay rent #home", Progress::InProgress);
+    starred.starred = true;
+    let mut long = task(
+        "Plan the team offsite with a name too long to fit",
+        Progress::Waiting,
+    );
+    long.description = "Book a venue, collect dietary requirements from everyone on the team and \
+        send out the agenda at least a week before, with directions"
+        .to_string();
+
+    Harness::with_preferences(
+        vec![
+            task("Write report #work", Progress::Waiting),
+            starred,
+            long,
+            task("Renew passport", Progress::Done),
+        ],
+        preferences,
+    )
+}
+
+#[test]
+fn table_snapshots_at_several_sizes() {
+    let mut harness = snapshot_harness();
+    for (width, height) in [(60, 16), (80, 24), (120, 40)] {
+        harness.resize(width, height).screen();
+        insta::assert_snapshot!(
+            format!("table_{width}x{height}"),
+            harness.terminal.backend()
+        );
+    }
+}
+
+#[test]
+fn popup_snapshots() {
+    let mut harness = snapshot_harness();
+
+    harness.press(KeyCode::Char('a')).type_text("New task");
+    // The date picker opens on today's month
+    harness.app.due_cursor = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+    harness.screen();
+    insta::assert_snapshot!("create_popup", harness.terminal.backend());
+    harness.press(KeyCode::Esc);
+
+    harness.press(KeyCode::Char('i'));
+    harness.screen();
+    insta::assert_snapshot!("info_popup", harness.terminal.backend());
+    harness.press(KeyCode::Esc);
+
+    harness.press(KeyCode::Char(','));
+    harness.screen();
+    insta::assert_snapshot!("settings_popup", harness.terminal.backend());
+    harness.press(KeyCode::Esc);
+
+    harness.press(KeyCode::Enter);
+    harness.screen();
+    insta::assert_snapshot!("task_reader", harness.terminal.backend());
+    harness.press(KeyCode::Esc);
+
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text("%s/#work/#office");
+    harness.screen();
+    insta::assert_snapshot!("command_preview", harness.terminal.backend());
+}
+
+#[test]
+fn date_picker_sets_the_due_date() {
+    let mut harness = Harness::new(vec![task("Write report", Progress::Waiting)]);
+    harness.press(KeyCode::Char('r'));
+    harness.app.due_cursor = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+    // Name -> Status -> Priority -> Description -> Due
+    for _ in 0..4 {
+        harness.press(KeyCode::Tab);
+    }
+    harness
+        .press(KeyCode::Right)
+        .press(KeyCode::PageDown)
+        .press(KeyCode::Up);
+    harness.press(KeyCode::Enter);
+    assert_eq!(harness.saved()[0].due.as_deref(), Some("2024-02-23"));
+
+    harness.press(KeyCode::Char('r'));
+    assert_eq!(
+        harness.app.due_cursor,
+        NaiveDate::from_ymd_opt(2024, 2, 23).unwrap()
+    );
+    for _ in 0..4 {
+        harness.press(KeyCode::Tab);
+    }
+    harness.press(KeyCode::Backspace).press(KeyCode::Enter);
+    assert_eq!(harness.saved()[0].due, None);
+}
+
+#[test]
+fn due_times_sort_within_the_day_and_show_in_reminders() {
+    let today = Local::now().date_naive();
+    let mut untimed = task("Water plants", Progress::Waiting);
+    untimed.due = Some(today.format(DUE_FORMAT).to_string());
+    let mut evening = task("Pack bags", Progress::Waiting);
+    evening.due = Some(format!("{} 18:00", today.format(DUE_FORMAT)));
+    let mut harness = Harness::with_preferences(
+        vec![untimed, evening],
+        Preferences {
+            sort: SortMode::Due,
+            ..Preferences::default()
+        },
+    );
+
+    harness.press(KeyCode::Char('a')).type_text("Call Sam");
+    harness.app.due_cursor = today;
+    // Name -> Status -> Priority -> Description -> Due
+    for _ in 0..4 {
+        harness.press(KeyCode::Tab);
+    }
+    harness.type_text("25:00").press(KeyCode::Enter);
+    assert!(harness.app.due_time_invalid);
+    assert_eq!(harness.saved().len(), 2);
+
+    for _ in 0..5 {
+        harness.press(KeyCode::Backspace);
+    }
+    harness.type_text("09:15").press(KeyCode::Enter);
+    let saved = harness.saved();
+    let call = saved.iter().find(|item| item.name == "Call Sam").unwrap();
+    assert_eq!(
+        call.due.as_deref(),
+        Some(format!("{} 09:15", today.format(DUE_FORMAT)).as_str())
+    );
+
+    let screen = harness.screen();
+    assert!(screen.contains("09:15 Call Sam"));
+    assert!(screen.contains("18:00 Pack bags"));
+    let mut names = ["Water plants", "Pack bags", "Call Sam"];
+    names.sort_by_key(|name| screen.find(name));
+    assert_eq!(names, ["Call Sam", "Pack bags", "Water plants"]);
+
+    let mut item = task("Call Sam", Progress::Waiting);
+    item.due = Some("2024-03-01 14:30".to_string());
+    let at = |time: &str| {
+        NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_time(time.parse().unwrap())
+    };
+    let reminders = Reminders::default();
+    assert_eq!(
+        reminders::reminder(&item, at("10:00"), &reminders).as_deref(),
+        Some("Due today at 14:30: Call Sam")
+    );
+    assert_eq!(
+        reminders::reminder(&item, at("15:00"), &reminders).as_deref(),
+        Some("Overdue since today at 14:30: Call Sam")
+    );
+}
+
+#[test]
+fn postpone_keys_move_the_due_date_and_keep_the_todo_selected() {
+    let today = Local::now().date_naive();
+    let mut overdue = task("Pay rent", Progress::Waiting);
+    overdue.due = Some("2024-01-01 14:00".to_string());
+    let mut soon = task("Call Sam", Progress::Waiting);
+    soon.due = Some((today + Days::new(3)).format(DUE_FORMAT).to_string());
+    let mut harness = Harness::with_preferences(
+        vec![overdue, soon, task("Plan trip", Progress::Waiting)],
+        Preferences {
+            sort: SortMode::Due,
+            ..Preferences::default()
+        },
+    );
+    let due = |harness: &Harness, name: &str| {
+        harness
+            .saved()
+            .into_iter()
+            .find(|item| item.name == name)
+            .and_then(|item| item.due)
+    };
+
+    // Overdue todos count from today and keep their time
+    harness.press(KeyCode::Char('e'));
+    let tomorrow = today + Days::new(1);
+    assert_eq!(
+        due(&harness, "Pay rent"),
+        Some(format!("{} 14:00", tomorrow.format(DUE_FORMAT)))
+    );
+    // It's now the sooner of the two, still selected
+    assert_eq!(harness.app.selected_item().unwrap().name, "Pay rent");
+    harness.press(KeyCode::Char('E'));
+    assert_eq!(
+        due(&harness, "Pay rent"),
+        Some(format!(
+            "{} 14:00",
+            (tomorrow + Days::new(7)).format(DUE_FORMAT)
+        ))
+    );
+    assert_eq!(harness.app.selected_item().unwrap().name, "Pay rent");
+
+    harness.press(KeyCode::Char('k'));
+    assert_eq!(harness.app.selected_item().unwrap().name, "Call Sam");
+    harness.press(KeyCode::Char('e'));
+    assert_eq!(
+        due(&harness, "Call Sam"),
+        Some((today + Days::new(4)).format(DUE_FORMAT).to_string())
+    );
+
+    harness.press(KeyCode::Char('j')).press(KeyCode::Char('j'));
+    assert_eq!(harness.app.selected_item().unwrap().name, "Plan trip");
+    harness.press(KeyCode::Char('m'));
+    let monday =
+        NaiveDate::parse_from_str(&due(&harness, "Plan trip").unwrap(), DUE_FORMAT).unwrap();
+    assert_eq!(monday.weekday(), Weekday::Mon);
+    assert!(monday > today && monday <= today + Days::new(7));
+    assert!(harness.screen().contains("Postponed to Mon"));
+}
+
+#[test]
+fn postponing_skips_weekends_and_holidays_when_asked() {
+    let date = |day| NaiveDate::from_ymd_opt(2024, 12, day).unwrap();
+    let preferences = Preferences {
+        holidays: vec!["12-25".to_string(), "2024-12-26".to_string()],
+        ..Preferences::default()
+    };
+    // Friday the 20th, with weekends still allowed
+    let workdays = Workdays::new(&preferences);
+    assert_eq!(workdays.on_or_after(date(21)), date(21));
+    assert_eq!(workdays.on_or_after(date(25)), date(27));
+    assert_eq!(workdays.next_business_day(date(20)), date(23));
+    assert_eq!(workdays.next_business_day(date(24)), date(27));
+    let workdays = Workdays::new(&Preferences {
+        skip_weekends: true,
+        ..preferences
+    });
+    assert_eq!(workdays.on_or_after(date(21)), date(23));
+
+    // Due on Friday, a day later is Monday
+    let today = Local::now().date_naive();
+    let friday = today + Days::new(u64::from((11 - today.weekday().num_days_from_monday()) % 7));
+    let mut todo = task("Send invoice", Progress::Waiting);
+    todo.due = Some(friday.format(DUE_FORMAT).to_string());
+    let mut harness = Harness::new(vec![todo]);
+    harness.app.preferences.skip_weekends = true;
+    harness.press(KeyCode::Char('e'));
+    let monday = friday + Days::new(3);
+    assert_eq!(
+        harness.saved()[0].due,
+        Some(monday.format(DUE_FORMAT).to_string())
+    );
+
+    harness.app.preferences.quick_actions = vec![QuickAction {
+        name: "Hand over".to_string(),
+        key: None,
+        steps: vec!["due next-business-day".to_string()],
+    }];
+    harness.app.run_quick_action(0);
+    let due = harness.saved()[0].due.clone().unwrap();
+    let due = NaiveDate::parse_from_str(&due, DUE_FORMAT).unwrap();
+    assert!(due > today && !matches!(due.weekday(), Weekday::Sat | Weekday::Sun));
+}
+
+#[test]
+fn a_todo_marked_done_flashes_before_it_hides() {
+    let mut harness = Harness::new(vec![
+        task("Write report", Progress::Waiting),
+        task("Call Sam", Progress::Waiting),
+    ]);
+    harness.press(KeyCode::Char('t')).press(KeyCode::Char('3'));
+
+    // Still shown, lit up green, until the flash is over
+    let screen = harness.screen();
+    let y = screen
+        .lines()
+        .position(|line| line.contains("Write report"))
+        .unwrap();
+    let buffer = harness.terminal.backend().buffer();
+    assert!((0..buffer.area.width).any(|x| buffer[(x, y as u16)].bg == Color::Green));
+
+    harness.app.flashing[0].1 -= COMPLETION_FLASH;
+    harness.app.end_flashes();
+    let screen = harness.screen();
+    assert!(!screen.contains("Write report"));
+    assert_eq!(harness.app.selected_item().unwrap().name, "Call Sam");
+}
+
+#[test]
+fn a_todos_own_reminders_fire_once_apart_from_its_due_date() {
+    // Wednesday 2024-03-06, 10:00
+    let now = NaiveDate::from_ymd_opt(2024, 3, 6)
+        .unwrap()
+        .and_hms_opt(10, 0, 0)
+        .unwrap();
+    let parse = |text: &str| {
+        reminders::parse_time(text, now).map(|at| at.format(REMIND_FORMAT).to_string())
+    };
+    assert_eq!(parse("Friday 9am").as_deref(), Some("2024-03-08 09:00"));
+    assert_eq!(parse("wed 9am").as_deref(), Some("2024-03-13 09:00"));
+    assert_eq!(parse("wed 4:30pm").as_deref(), Some("2024-03-06 16:30"));
+    assert_eq!(parse("tomorrow").as_deref(), Some("2024-03-07 09:00"));
+    assert_eq!(parse("8:15").as_deref(), Some("2024-03-07 08:15"));
+    assert_eq!(
+        parse("2024-04-01 at 12:00").as_deref(),
+        Some("2024-04-01 12:00")
+    );
+    assert_eq!(parse("in 1h 30m").as_deref(), Some("2024-03-06 11:30"));
+    for invalid in ["", "soon", "13pm", "9"] {
+        assert_eq!(parse(invalid), None);
+    }
+
+    let mut todo = task("Call Sam", Progress::Waiting);
+    let past = Local::now().naive_local() - chrono::Duration::minutes(5);
+    let later = Local::now().naive_local() + chrono::Duration::days(1);
+    todo.remind = vec![
+        past.format(REMIND_FORMAT).to_string(),
+        later.format(REMIND_FORMAT).to_string(),
+    ];
+    let mut harness = Harness::new(vec![todo]);
+    harness.app.check_reminders();
+    assert!(harness.screen().contains("Reminder: Call Sam"));
+    // Shown, so it's taken off and the later one stays
+    assert_eq!(
+        harness.saved()[0].remind,
+        [later.format(REMIND_FORMAT).to_string()]
+    );
+}
+
+#[test]
+fn timestamps_are_stored_in_utc_and_shown_in_the_timezone() {
+    let mut item = task("Write report", Progress::Waiting);
+    item.created = "2024-01-01T10:00:00Z".to_string();
+    let mut harness = Harness::with_preferences(
+        vec![item],
+        Preferences {
+            timezone: Some("+02:00".to_string()),
+            ..Preferences::default()
+        },
+    );
+    assert!(harness.screen().contains("2024-01-01 12:00:00"));
+
+    harness
+        .press(KeyCode::Char('a'))
+        .type_text("Book flights")
+        .press(KeyCode::Enter)
+        .press(KeyCode::Enter);
+    let created = &harness.saved()[1].created;
+    assert!(chrono::DateTime::parse_from_rfc3339(created)
+        .is_ok_and(|at| at.offset().local_minus_utc() == 0));
+}
+
+#[test]
+fn reminders_are_shown_once() {
+    let today = chrono::Local::now().date_naive();
+    let mut due_today = task("Pay rent", Progress::Waiting);
+    due_today.due = Some(today.format("%Y-%m-%d").to_string());
+    let mut due_later = task("Renew passport", Progress::Waiting);
+    due_later.due = Some(
+        (today + chrono::Days::new(2))
+            .format("%Y-%m-%d")
+            .to_string(),
+    );
+    let mut harness = Harness::new(vec![due_today, due_later]);
+
+    harness.app.check_reminders();
+    harness.app.check_reminders();
+    let toasts: Vec<&str> = harness
+        .app
+        .toasts
+        .iter()
+        .map(|toast| toast.message.as_str())
+        .collect();
+    assert_eq!(toasts, ["Due today: Pay rent"]);
+}
+
+#[test]
+fn dragging_a_row_reorders_the_list() {
+    let mut harness = Harness::new(vec![
+        task("First", Progress::Waiting),
+        task("Second", Progress::Waiting),
+        task("Third", Progress::Waiting),
+    ]);
+    harness.screen();
+    // A header line, then four lines per row
+    harness.drag((10, 1), (10, 9));
+    let names: Vec<String> = harness.saved().into_iter().map(|item| item.name).collect();
+    assert_eq!(names, ["Second", "Third", "First"]);
+    assert_eq!(harness.app.selected_index(), Some(2));
+}
+
+#[test]
+fn dragging_a_card_changes_its_column() {
+    let mut harness = Harness::with_preferences(
+        vec![task("Write report", Progress::Waiting)],
+        Preferences {
+            layout: ViewLayout::Kanban,
+            ..Preferences::default()
+        },
+    );
+    harness.screen();
+    // Below the border, the column titles and the lane title
+    harness.drag((3, 3), (50, 3));
+    assert_eq!(harness.saved()[0].progress, Progress::InProgress);
+}
+
+#[test]
+fn kanban_swimlanes_collapse() {
+    let mut harness = Harness::with_preferences(
+        vec![
+            task("Write report #work", Progress::Waiting),
+            task("Review budget #work", Progress::Done),
+            task("Pay rent #home", Progress::InProgress),
+            task("Renew passport", Progress::Waiting),
+        ],
+        Preferences {
+            layout: ViewLayout::Kanban,
+            swimlanes: Swimlanes::Tags,
+            ..Preferences::default()
+        },
+    );
+    insta::assert_snapshot!("kanban_swimlanes", harness.screen());
+
+    // The first task in the table is in the #work lane
+    harness.press(KeyCode::Char('z'));
+    let screen = harness.screen();
+    assert!(screen.contains("▸ #work (2)"));
+    assert!(!screen.contains("Review budget"));
+    assert!(screen.contains("Pay rent"));
+
+    harness.press(KeyCode::Char('[')).press(KeyCode::Char('z'));
+    let screen = harness.screen();
+    assert!(screen.contains("▸ #home (1)"));
+    assert!(!screen.contains("Pay rent"));
+}
+
+#[test]
+fn org_files_round_trip() {
+    let mut harness = Harness::new(vec![
+        Data {
+            priority: Some(Priority::High),
+            due: Some("2024-03-01".to_string()),
+            ..task("Ship #release", Progress::InProgress)
+        },
+        task("Water plants", Progress::Waiting),
+    ]);
+    let path = harness.dir.join("todos.org");
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text(&format!("export {}", path.display()))
+        .press(KeyCode::Enter);
+
+    let org = fs::read_to_string(&path).unwrap();
+    assert!(org.starts_with("* TODO [#A] Ship #release :release:\n  DEADLINE: <2024-03-01 Fri>\n"));
+    assert!(org.contains(":PROGRESS: InProgress"));
+
+    // Finished in Emacs
+    fs::write(&path, org.replace("* TODO Water", "* DONE Water")).unwrap();
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text(&format!("import {}", path.display()))
+        .press(KeyCode::Enter);
+
+    let saved = harness.saved();
+    assert_eq!(saved.len(), 2);
+    assert_eq!(saved[0].progress, Progress::InProgress);
+    assert_eq!(saved[0].description, "About Ship #release");
+    assert_eq!(saved[1].progress, Progress::Done);
+}
+
+#[test]
+fn piped_lines_are_captured_through_the_template() {
+    let template = Template::parse("{3} | {1}:{2}");
+    assert_eq!(
+        template.apply("src/main.rs:12:    // TODO: retry: twice"),
+        Some((
+            "// TODO: retry: twice".to_string(),
+            "src/main.rs:12".to_string()
+        ))
+    );
+    // Lines without a name are left out
+    assert_eq!(template.apply("README.md"), None);
+    assert_eq!(
+        Template::parse("{line}").apply("  Buy milk "),
+        Some(("Buy milk".to_string(), String::new()))
+    );
+
+    let mut harness = Harness::new(vec![task("Water plants", Progress::Waiting)]);
+    let lines = ["a.rs:1:fix the parser", "", "b.rs:7:add tests"];
+    harness.app.capture(
+        lines
+            .iter()
+            .filter_map(|line| template.apply(line))
+            .collect(),
+    );
+    assert!(harness.screen().contains("Captured 2 todo(s)"));
+    let saved = harness.saved();
+    assert_eq!(saved.len(), 3);
+    assert_eq!(saved[1].name, "fix the parser");
+    assert_eq!(saved[2].description, "b.rs:7");
+    assert_ne!(saved[1].created, saved[2].created);
+}
+
+#[test]
+fn trello_boards_import_as_todos() {
+    let mut harness = Harness::new(Vec::new());
+    let path = harness.dir.join("board.json");
+    let board = r#"{
+        "name": "Launch",
+        "lists": [
+            {"id": "l1", "name": "Doing"},
+            {"id": "l2", "name": "Q3 Marketing"}
+        ],
+        "cards": [
+            {"id": "65a0c0000000000000000001", "name": "Write copy", "desc": "For the site",
+             "idList": "l1", "closed": false, "due": "2024-03-01T12:00:00.000Z",
+             "dueComplete": false, "labels": [{"name": "Urgent", "color": "red"}]},
+            {"id": "65a0c0000000000000000002", "name": "Book ads", "desc": "",
+             "idList": "l2", "closed": false, "due": null, "dueComplete": false,
+             "labels": [{"name": "", "color": "green"}]},
+            {"id": "65a0c0000000000000000003", "name": "Old idea", "desc": "",
+             "idList": "l2", "closed": true, "labels": []}
+        ]
+    }"#;
+    fs::write(&path, board).unwrap();
+    let import = format!("import {}", path.display());
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text(&import)
+        .press(KeyCode::Enter);
+
+    let saved = harness.saved();
+    assert_eq!(saved.len(), 2);
+    assert_eq!(saved[0].name, "Write copy");
+    assert_eq!(saved[0].progress, Progress::InProgress);
+    assert_eq!(saved[0].description, "For the site\n\n#urgent");
+    assert_eq!(saved[0].due.as_deref(), Some("2024-03-01"));
+    assert_eq!(saved[0].created, "2024-01-12T04:28:48Z");
+    assert_eq!(saved[1].progress, Progress::Waiting);
+    assert_eq!(saved[1].description, "#q3-marketing #green");
+
+    // Importing again matches the cards instead of adding them twice
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text(&import)
+        .press(KeyCode::Enter);
+    assert_eq!(harness.saved().len(), 2);
+}
+
+#[test]
+fn the_import_preview_reads_the_file_once_per_path_typed() {
+    let mut harness = Harness::new(Vec::new());
+    let path = harness.dir.join("todos.csv");
+    fs::write(&path, "name\nBuy milk\n").unwrap();
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text(&format!("import {}", path.display()));
+    assert!(harness.screen().contains("1 added, 0 updated"));
+
+    // Redrawing on the tick keeps what was read
+    fs::write(&path, "name\nBuy milk\nCall mum\n").unwrap();
+    assert!(harness.screen().contains("1 added, 0 updated"));
+    harness.press(KeyCode::Backspace);
+    assert!(harness.screen().contains("No such file"));
+    harness.press(KeyCode::Char('v'));
+    assert!(harness.screen().contains("2 added, 0 updated"));
+}
+
+#[test]
+fn a_shared_todo_is_imported_with_its_details() {
+    let mut item = task("Fix login", Progress::InProgress);
+    item.fields
+        .insert("ticket".to_string(), "ABC-123".to_string());
+    item.estimate = Some("2h 00m".to_string());
+    item.priority = Some(Priority::High);
+    let mut harness = Harness::new(vec![item, task("Water plants", Progress::Waiting)]);
+    let path = harness.dir.join("login.json");
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text(&format!("share {}", path.display()));
+    assert!(harness.screen().contains("Will be shared as"));
+    harness.press(KeyCode::Enter);
+    assert!(harness.screen().contains("Shared as"));
+    let shared = fs::read_to_string(&path).unwrap();
+    assert!(shared.starts_with('{'));
+
+    // A teammate's list takes it in as it was, once
+    let mut teammate = Harness::new(vec![task("Review PR", Progress::Waiting)]);
+    let import = format!("import {}", path.display());
+    for _ in 0..2 {
+        teammate
+            .press(KeyCode::Char(':'))
+            .type_text(&import)
+            .press(KeyCode::Enter);
+    }
+    let saved = teammate.saved();
+    assert_eq!(saved.len(), 2);
+    assert_eq!(saved[1].name, "Fix login");
+    assert_eq!(saved[1].progress, Progress::InProgress);
+    assert_eq!(saved[1].priority, Some(Priority::High));
+    assert_eq!(saved[1].fields["ticket"], "ABC-123");
+    assert_eq!(saved[1].estimate.as_deref(), Some("2h 00m"));
+    assert_eq!(saved[1].created, "2024-01-01 12:00:00");
+}
+
+#[test]
+fn jira_issues_import_into_their_own_list() {
+    let harness = Harness::new(Vec::new());
+    let path = harness.dir.join("lists").join("jira.json");
+    let csv = "\u{feff}Summary,Issue key,Status,Priority,Created,Due Date,Labels,Labels,Description,Status Category
+Fix login,APP-12,In Review,Highest,31/Jan/24 10:00 AM,15/Feb/24 12:00 AM,auth,,\"Users get \"\"401\"\"\",In Progress
+Write docs,APP-13,Backlog,Low,01/Feb/24 09:30 AM,,,,,To Do
+";
+    let (added, updated, _) = jira::store(&path, jira::from_csv(csv)).unwrap();
+    assert_eq!((added, updated), (2, 0));
+
+    let saved = read_json(&path).unwrap();
+    assert_eq!(saved[0].name, "APP-12 Fix login");
+    assert_eq!(saved[0].progress, Progress::InProgress);
+    assert_eq!(saved[0].priority, Some(Priority::High));
+    assert_eq!(saved[0].description, "Users get \"401\"\n\n#auth");
+    assert_eq!(saved[0].created, "2024-01-31T10:00:00Z");
+    assert_eq!(saved[0].due.as_deref(), Some("2024-02-15"));
+    assert_eq!(saved[1].progress, Progress::Waiting);
+
+    // Finished in Jira since
+    let csv = csv.replacen(",In Progress\n", ",Done\n", 1);
+    let (added, updated, skipped) = jira::store(&path, jira::from_csv(&csv)).unwrap();
+    assert_eq!((added, updated, skipped), (0, 1, 1));
+    assert_eq!(read_json(&path).unwrap()[0].progress, Progress::Done);
+}
+
+#[test]
+fn scanned_code_comments_import_and_follow_the_code() {
+    let harness = Harness::new(Vec::new());
+    let src = harness.dir.join("src");
+    fs::create_dir_all(src.join(".git")).unwrap();
+    let main = src.join("main.rs");
+    fs::write(
+        &main,
+        "fn main() {\n    // TODO: handle errors\n    run(); // FIXME(sam) flaky on CI */\n    let todo = 1;\n}\n",
+    )
+    .unwrap();
+    fs::write(src.join("notes.py"), "# TODO\n").unwrap();
+    fs::write(src.join(".git").join("HEAD"), "# TODO: not code\n").unwrap();
+
+    let found = scan::scan(&src).unwrap();
+    let texts: Vec<(&str, &str, usize)> = found
+        .iter()
+        .map(|found| (found.kind.as_str(), found.text.as_str(), found.line))
+        .collect();
+    assert_eq!(
+        texts,
+        [
+            ("todo", "handle errors", 2),
+            ("fixme", "flaky on CI", 3),
+            ("todo", "", 1)
+        ]
+    );
+
+    let path = harness.dir.join("lists").join("code.json");
+    assert_eq!(scan::store(&path, &src, found).unwrap(), (3, 0, 0));
+    let saved = read_json(&path).unwrap();
+    assert_eq!(saved[0].name, "handle errors");
+    assert_eq!(saved[0].description, "#todo");
+    assert_eq!(saved[0].fields["source"], format!("{}:2", main.display()));
+    assert_eq!(
+        saved[2].name,
+        format!("TODO in {}", src.join("notes.py").display())
+    );
+
+    // A line added above moves one, the fixed one is resolved
+    fs::write(
+        &main,
+        "use std::io;\nfn main() {\n    // TODO: handle errors\n    run();\n}\n",
+    )
+    .unwrap();
+    let found = scan::scan(&src).unwrap();
+    assert_eq!(scan::store(&path, &src, found).unwrap(), (0, 1, 1));
+    let saved = read_json(&path).unwrap();
+    assert_eq!(saved.len(), 3);
+    assert_eq!(saved[0].fields["source"], format!("{}:3", main.display()));
+    assert_eq!(saved[1].progress, Progress::Done);
+    assert_eq!(saved[2].progress, Progress::Waiting);
+}
+
+#[test]
+fn maintenance_dedupes_fixes_ids_and_sorts_the_file() {
+    let harness = Harness::new(Vec::new());
+    let path = harness.dir.join("data.json");
+    fs::write(
+        &path,
+        r#"[
+            {"name": "Later", "description": "", "progress": "Done", "created": "2024-03-01T10:00:00Z", "starred": false},
+            {"name": "Earlier", "description": "", "progress": "Waiting", "created": "2024-02-01T10:00:00Z", "starred": false},
+            {"name": "Later", "description": "", "progress": "Done", "created": "2024-03-01T10:00:00Z", "starred": false},
+            {"name": "Same time", "description": "", "progress": "Waiting", "created": "2024-02-01T10:00:00Z", "starred": false},
+            {"name": "No time", "progress": "Sideways"}
+        ]"#,
+    )
+    .unwrap();
+
+    let dry_run = maintenance::tidy(&path, false).unwrap();
+    assert_eq!(read_json(&path).unwrap().len(), 5);
+
+    let report = maintenance::tidy(&path, true).unwrap();
+    assert_eq!(report, dry_run);
+    assert_eq!(
+        report,
+        maintenance::Report {
+            todos: 4,
+            duplicates: 1,
+            new_ids: 2,
+            fixed: 1,
+            sorted: true,
+        }
+    );
+    let saved = read_json(&path).unwrap();
+    let names: Vec<_> = saved.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(names, ["Earlier", "Later", "Same time", "No time"]);
+    assert_eq!(saved[3].progress, Progress::Waiting);
+
+    // Nothing left to do, and a broken file is left alone
+    assert_eq!(maintenance::tidy(&path, true).unwrap().todos, 4);
+    assert!(!maintenance::tidy(&path, true).unwrap().sorted);
+    fs::write(&path, "[{").unwrap();
+    assert!(maintenance::tidy(&path, true).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "[{");
+}
+
+#[test]
+fn html_reports_group_by_status_and_flag_overdue_todos() {
+    let mut harness = Harness::new(vec![
+        Data {
+            due: Some("2000-01-01".to_string()),
+            ..task("Renew <passport>", Progress::Waiting)
+        },
+        Data {
+            due: Some("2000-01-01".to_string()),
+            ..task("Old trip", Progress::Done)
+        },
+        task("Draft #blog post", Progress::InProgress),
+    ]);
+    let path = harness.dir.join("report.html");
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text(&format!("export {}", path.display()))
+        .press(KeyCode::Enter);
+
+    let html = fs::read_to_string(&path).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    let in_progress = html.find("In Progress (1)").unwrap();
+    let waiting = html.find("Waiting (1)").unwrap();
+    let done = html.find("Done (1)").unwrap();
+    assert!(in_progress < waiting && waiting < done);
+    assert!(html.contains("<span class=\"tag\">#blog</span>"));
+    assert!(html.contains("Renew &lt;passport&gt;"));
+    // Only the open todo past its due date is overdue
+    assert_eq!(html.matches("<li class=\"waiting overdue\">").count(), 1);
+    assert!(html.contains("<li class=\"done\">"));
+}
+
+#[test]
+fn completions_count_towards_the_daily_goal() {
+    let preferences = Preferences {
+        daily_goal: 2,
+        ..Preferences::default()
+    };
+    let mut harness =
+        Harness::with_preferences(vec![task("Write report", Progress::Waiting)], preferences);
+    let path = harness.dir.join("completions.json");
+    let today = chrono::Local::now()
+        .date_naive()
+        .format(DUE_FORMAT)
+        .to_string();
+
+    harness.press(KeyCode::Char('n'));
+    let completions: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(completions[&today], 1);
+    assert!(harness.screen().contains("0 1/2"));
+
+    harness.press(KeyCode::Char('S'));
+    assert!(harness.screen().contains("Completed today: 1"));
+    assert!(harness.screen().contains("1 of 2 today"));
+    assert!(harness.screen().contains("Streak: 0 days"));
+    harness.press(KeyCode::Esc);
+
+    // Reopening takes the completion back
+    harness.press(KeyCode::Char('n'));
+    let completions: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(completions[&today], 0);
+}
+
+#[test]
+fn focus_sessions_add_up_in_the_time_stats() {
+    let mut harness = Harness::new(vec![task("Write report", Progress::InProgress)]);
+    harness.press(KeyCode::Char('f'));
+    harness.app.focus_started = Instant::now() - Duration::from_secs(26 * 60);
+    harness.press(KeyCode::Esc);
+
+    let log: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(harness.dir.join("time_log.json")).unwrap())
+            .unwrap();
+    assert_eq!(log[0]["name"], "Write report");
+    assert_eq!(log[0]["seconds"], 26 * 60);
+
+    harness.press(KeyCode::Char('S')).press(KeyCode::Tab);
+    let screen = harness.screen();
+    assert!(screen.contains("Today: 26m"));
+    assert!(screen
+        .lines()
+        .any(|line| line.contains("26m  1🍅") && line.contains("Write report")));
+}
+
+#[test]
+fn search_history_recalls_earlier_queries() {
+    let mut harness = Harness::new(vec![
+        task("Buy milk", Progress::Waiting),
+        task("Call mum", Progress::Waiting),
+    ]);
+    for query in ["milk", "mum"] {
+        harness
+            .press(KeyCode::Char('/'))
+            .type_text(query)
+            .press(KeyCode::Enter);
+    }
+    let saved = fs::read_to_string(harness.dir.join("search_history.json")).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Vec<String>>(&saved).unwrap(),
+        ["milk", "mum"]
+    );
+
+    harness.press(KeyCode::Char('/')).type_text("ca");
+    harness.press(KeyCode::Up).press(KeyCode::Up);
+    assert_eq!(harness.app.search_query, "milk");
+    harness.press(KeyCode::Up);
+    assert_eq!(harness.app.search_query, "milk");
+    harness.press(KeyCode::Down);
+    assert_eq!(harness.app.search_query, "mum");
+    harness.press(KeyCode::Down);
+    assert_eq!(harness.app.search_query, "ca");
+
+    // Enter opens the recalled query's result
+    harness
+        .press(KeyCode::Up)
+        .press(KeyCode::Up)
+        .press(KeyCode::Enter);
+    assert_eq!(harness.app.selected_item().unwrap().name, "Buy milk");
+}
+
+#[test]
+fn command_palette_runs_actions_by_name() {
+    let mut harness = Harness::new(vec![
+        task("Buy milk", Progress::Done),
+        task("Call mum", Progress::Waiting),
+    ]);
+    let ctrl_p = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL);
+    harness.app.handle_key(ctrl_p);
+    assert!(harness.screen().contains("Command palette"));
+    harness.type_text("hide comp");
+    assert!(harness.screen().contains("Toggle hide completed"));
+    harness.press(KeyCode::Enter);
+    assert!(harness.app.hide_completed);
+    assert_eq!(harness.app.view().len(), 1);
+
+    // Actions without a key are only in the palette
+    harness.app.handle_key(ctrl_p);
+    harness.type_text("exp md").press(KeyCode::Enter);
+    assert!(harness.app.show_command);
+    assert_eq!(harness.app.input_command, "export todos.md");
+}
+
+#[test]
+fn random_pick_selects_an_open_todo_in_view() {
+    let mut harness = Harness::new(vec![
+        task("Ship the release", Progress::Done),
+        task("Write report", Progress::Waiting),
+        task("Pay rent", Progress::InProgress),
+        Data {
+            created: "2025-06-01 12:00:00".to_string(),
+            ..task("Call mum", Progress::Waiting)
+        },
+    ]);
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text("filter created:2024-01-01")
+        .press(KeyCode::Enter);
+    for _ in 0..20 {
+        harness.press(KeyCode::Char('w'));
+        let picked = harness.app.selected_index();
+        assert!(matches!(picked, Some(1 | 2)), "picked {picked:?}");
+    }
+    assert!(harness.app.toasts.is_empty());
+}
+
+#[test]
+fn random_pick_warns_without_open_todos() {
+    let mut harness = Harness::new(Vec::new());
+    harness.press(KeyCode::Char('w'));
+    assert_eq!(harness.app.toasts[0].message, "No open todos to pick from");
+
+    let mut harness = Harness::new(vec![task("Ship the release", Progress::Done)]);
+    harness.press(KeyCode::Char('w'));
+    assert_eq!(harness.app.toasts[0].message, "No open todos to pick from");
+    assert_eq!(harness.app.selected_index(), Some(0));
+}
+
+#[test]
+fn substitute_previews_and_replaces_across_todos() {
+    let mut harness = Harness::new(vec![
+        task("Apollo launch plan", Progress::Waiting),
+        task("Call mum", Progress::Waiting),
+    ]);
+    harness
+        .press(KeyCode::Char(':'))
+        .type_text("s/Apollo/Artemis");
+    let screen = harness.screen();
+    assert!(screen.contains("1 todo(s) will change"));
+    assert!(screen.contains("Artemis launch plan"));
+    harness.press(KeyCode::Enter);
+
+    let saved = harness.saved();
+    assert_eq!(saved[0].name, "Artemis launch plan");
+    assert_eq!(saved[0].description, "About Artemis launch plan");
+    assert_eq!(saved[1].name, "Call mum");
+    assert_eq!(harness.app.toasts[0].message, "1 todo(s) changed");
+}
+
+#[test]
+fn substitute_without_a_match_changes_nothing() {
+    let mut harness = Harness::new(vec![task("Call mum", Progress::Waiting)]);
+    harness.press(KeyCode::Char(':')).type_text("s/Apollo");
+    assert!(harness
+        .screen()
+        .contains("Missing replacement, use old/new"));
+    harness.type_text("/Artemis/");
+    assert!(harness.screen().contains("0 todo(s) will change"));
+    harness.press(KeyCode::Enter);
+
+    assert_eq!(harness.saved()[0].name, "Call mum");
+    assert_eq!(harness.saved()[0].description, "About Call mum");
+}
+
+#[test]
+fn digits_set_the_status_and_alt_digits_switch_lists() {
+    let mut harness = Harness::new(vec![task("Buy milk", Progress::Waiting)]);
+    assert!(harness
+        .screen()
+        .contains("(1) waiting (2) in progress (3) done"));
+    harness.press(KeyCode::Char('3'));
+    assert_eq!(harness.saved()[0].progress, Progress::Done);
+    harness.press(KeyCode::Char('2'));
+    assert_eq!(harness.saved()[0].progress, Progress::InProgress);
+
+    // Other digits still switch lists, and so do all of them with Alt
+    harness.app.preferences.status_keys = "789".to_string();
+    harness.press(KeyCode::Char('1'));
+    assert_eq!(harness.saved()[0].progress, Progress::InProgress);
+    harness.press(KeyCode::Char('7'));
+    assert_eq!(harness.saved()[0].progress, Progress::Waiting);
+    assert!(harness
+        .screen()
+        .contains("(7) waiting (8) in progress (9) done"));
+    let alt_9 = KeyEvent::new(KeyCode::Char('9'), KeyModifiers::ALT);
+    harness.app.handle_key(alt_9);
+    assert_eq!(harness.saved()[0].progress, Progress::Waiting);
+}
+
+#[test]
+fn quick_actions_apply_every_step_and_undo_in_one_go() {
+    let steps = [
+        "priority high",
+        "tag urgent",
+        "due today",
+        "status in-progress",
+    ];
+    let preferences = Preferences {
+        quick_actions: vec![
+            QuickAction {
+                name: "Urgent today".to_string(),
+                key: Some('!'),
+                steps: steps.iter().map(ToString::to_string).collect(),
+            },
+            QuickAction {
+                name: "Broken".to_string(),
+                key: Some('@'),
+                steps: vec!["star".to_string(), "due someday".to_string()],
+            },
+        ],
+        ..Preferences::default()
+    };
+    let mut harness =
+        Harness::with_preferences(vec![task("Call mum", Progress::Waiting)], preferences);
+    harness.press(KeyCode::Char('!'));
+    let saved = harness.saved();
+    assert_eq!(saved[0].priority, Some(Priority::High));
+    assert_eq!(saved[0].tags(), vec!["urgent"]);
+    assert_eq!(saved[0].due_date(), Some(Local::now().date_naive()));
+    assert_eq!(saved[0].progress, Progress::InProgress);
+
+    harness.press(KeyCode::Char('u'));
+    let saved = harness.saved();
+    assert_eq!(saved[0].priority, None);
+    assert!(saved[0].tags().is_empty());
+    assert_eq!(saved[0].due, None);
+    assert_eq!(saved[0].progress, Progress::Waiting);
+
+    // An invalid step stops the whole action
+    harness.press(KeyCode::Char('@'));
+    assert!(harness
+        .screen()
+        .contains("Quick action \"Broken\" is invalid"));
+    assert!(!harness.saved()[0].starred);
+}
+
+#[test]
+fn macros_record_keys_and_replay_them_a_number_of_times() {
+    let names = [
+        "Buy milk",
+        "Call mum",
+        "Fix bike",
+        "Pay rent",
+        "Water plants",
+    ];
+    let items = names
+        .iter()
+        .map(|name| task(name, Progress::Waiting))
+        .collect();
+    let mut harness = Harness::new(items);
+    harness.press(KeyCode::Char('Q'));
+    assert!(harness.screen().contains("Recording macro"));
+    harness
+        .press(KeyCode::Char('s'))
+        .press(KeyCode::Char('j'))
+        .press(KeyCode::Char('Q'));
+    assert_eq!(harness.app.macro_keys.len(), 2);
+
+    harness.press(KeyCode::Char('@')).press(KeyCode::Enter);
+    harness
+        .press(KeyCode::Char('@'))
+        .type_text("2")
+        .press(KeyCode::Enter);
+    let starred: Vec<bool> = harness.saved().iter().map(|item| item.starred).collect();
+    assert_eq!(starred, [true, true, true, true, false]);
+
+    // Replaying can't quit the app
+    harness.app.macro_keys = vec![KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)];
+    harness.press(KeyCode::Char('@'));
+    assert!(harness
+        .app
+        .handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)));
+}
+
+#[test]
+fn picking_a_todo_quits_with_it() {
+    let mut harness = Harness::new(vec![
+        task("Buy milk", Progress::Waiting),
+        task("Call mum", Progress::Waiting),
+    ]);
+    harness.app.picking = true;
+    assert!(harness.screen().contains("(Enter) pick | (Esc) cancel"));
+    harness.press(KeyCode::Char('j'));
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    assert!(!harness.app.handle_key(enter));
+
+    let picked = harness.app.picked.as_ref().unwrap();
+    assert_eq!(pick::output(Format::Name, picked).unwrap(), "Call mum");
+    assert_eq!(pick::output(Format::Id, picked).unwrap(), picked.created);
+    let json = pick::output(Format::Json, picked).unwrap();
+    assert!(!json.contains('\n'));
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["name"], "Call mum");
+}
+
+#[test]
+fn no_color_mode_uses_text_markers_instead() {
+    let mut stale = task("Buy milk", Progress::Waiting);
+    stale.created = (Utc::now() - chrono::Duration::days(40)).to_rfc3339();
+    let mut harness = Harness::new(vec![stale]);
+    harness.app.no_color = true;
+    harness.app.warn("Almost out of space");
+    let screen = harness.screen();
+    // Past the stale alert at 30 days
+    assert!(screen.contains(" 40d!"));
+    assert!(screen.contains("Warning: Almost out of space"));
+
+    let buffer = harness.terminal.backend().buffer();
+    assert!(buffer
+        .content
+        .iter()
+        .all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+}
+
+#[test]
+fn ascii_only_mode_draws_nothing_but_ascii() {
+    let preferences = Preferences {
+        ascii_only: true,
+        ..Preferences::default()
+    };
+    let mut starred = task("Pay rent", Progress::Done);
+    starred.starred = true;
+    let mut harness = Harness::with_preferences(
+        vec![task("Buy milk", Progress::InProgress), starred],
+        preferences,
+    );
+    assert!(harness.screen().is_ascii());
+    harness.press(KeyCode::Char('i'));
+    // Apart from the author's name
+    assert!(harness.screen().replace("Jørgensen", "").is_ascii());
+    harness.press(KeyCode::Esc).press(KeyCode::Char(','));
+    let screen = harness.screen();
+    assert!(screen
+        .lines()
+        .any(|line| line.contains("Lock Color") && line.contains("[ ]")));
+    assert!(screen.is_ascii());
+}
+
+#[test]
+fn inline_mode_fits_a_row_per_line() {
+    let mut harness = Harness::new(vec![
+        task("Buy milk", Progress::Waiting),
+        task("Call mum", Progress::Waiting),
+    ]);
+    harness.app.inline = true;
+    harness.terminal = Terminal::new(TestBackend::new(100, 8)).unwrap();
+    let screen = harness.screen();
+    assert!(screen.contains("Buy milk") && screen.contains("Call mum"));
+    // The density preference is left for the full screen
+    assert_eq!(harness.app.preferences.density, Density::Full);
+}
+
+#[test]
+fn row_density_sets_the_lines_each_todo_takes() {
+    let mut harness = Harness::with_preferences(
+        vec![
+            task(
+                "Renew the passport before the summer trip",
+                Progress::Waiting,
+            ),
+            task("Call mum", Progress::Waiting),
+        ],
+        Preferences {
+            density: Density::Compact,
+            ..Preferences::default()
+        },
+    );
+    let screen = harness.screen();
+    let lines: Vec<&str> = screen.lines().collect();
+    // A line a todo, the rest of the name cut off
+    assert!(lines[1].contains("Renew the passport"));
+    assert!(!screen.contains("ummer trip"));
+    assert!(lines[2].contains("Call mum"));
+
+    harness.app.step_setting(Setting::Density, true);
+    assert_eq!(harness.app.preferences.density, Density::Cozy);
+    let screen = harness.screen();
+    let lines: Vec<&str> = screen.lines().collect();
+    assert!(lines[2].contains("ummer trip"));
+    assert!(lines[3].contains("Call mum"));
+
+    // Full goes back around to compact
+    harness.app.step_setting(Setting::Density, true);
+    assert_eq!(harness.app.preferences.density, Density::Full);
+    assert!(harness
+        .screen()
+        .lines()
+        .nth(5)
+        .unwrap()
+        .contains("Call mum"));
+    harness.app.step_setting(Setting::Density, true);
+    assert_eq!(harness.app.preferences.density, Density::Compact);
+}
+
+#[test]
+fn columns_resize_within_limits_and_rewrap() {
+    let mut harness = Harness::new(vec![task(
+        "Renew the passport before the summer trip",
+        Progress::Waiting,
+    )]);
+    assert!(harness.screen().contains("Renew the passport bef"));
+
+    harness.app.step_column_width(10);
+    assert_eq!(harness.app.preferences.column_widths.name, 32);
+    assert!(harness.screen().contains("Renew the passport before the s"));
+
+    // Tab moves on to the description
+    harness.press(KeyCode::Tab);
+    harness.app.step_column_width(-2);
+    assert_eq!(harness.app.preferences.column_widths.description, 40);
+    harness.app.step_column_width(-100);
+    assert_eq!(
+        harness.app.preferences.column_widths.description,
+        ColumnWidths::MIN
+    );
+    harness.app.step_column_width(500);
+    assert_eq!(
+        harness.app.preferences.column_widths.description,
+        ColumnWidths::MAX
+    );
+    assert_eq!(harness.app.preferences.column_widths.name, 32);
+}
+
+#[test]
+fn popups_fit_the_smallest_inline_viewport() {
+    let mut harness = Harness::new(vec![task("Buy milk", Progress::Waiting)]);
+    harness.app.inline = true;
+    harness.terminal = Terminal::new(TestBackend::new(100, MIN_INLINE_HEIGHT)).unwrap();
+    harness
+        .app
+        .show_error("Couldn't save: disk full".to_string(), None);
+    // Cut down to the viewport rather than overflowing it
+    assert!(harness.screen().contains("disk full"));
+}
+
+#[test]
+fn markdown_checklists_store_todos() {
+    let mut harness = Harness::new(Vec::new());
+    let path = harness.dir.join("todo.md");
+    harness.app.lists[0].path = path.clone();
+    harness
+        .press(KeyCode::Char('a'))
+        .type_text("Buy milk")
+        .press(KeyCode::Enter)
+        .type_text("Two liters")
+        .press(KeyCode::Enter);
+
+    let markdown = fs::read_to_string(&path).unwrap();
+    assert!(markdown.starts_with("- [ ] Buy milk — Two liters <!-- created: "));
+
+    // As on the next tick, so the hand edit below is merged against the save.
+    // Writes can land within the same file time tick, so don't rely on it.
+    harness.app.disk_modified = None;
+    harness.app.check_disk();
+
+    // Edited by hand in a notes app
+    fs::write(
+        &path,
+        format!(
+            "# Groceries\n\n{}- [ ] Eggs — a dozen\n  free range\n",
+            markdown.replacen("[ ]", "[x]", 1)
+        ),
+    )
+    .unwrap();
+    harness.app.disk_modified = None;
+    harness.app.check_disk();
+
+    let items = &harness.app.items;
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].progress, Progress::Done);
+    assert_eq!(items[1].name, "Eggs");
+    assert_eq!(items[1].description, "a dozen\nfree range");
+}
+
+#[test]
+fn saving_merges_edits_made_on_disk_since_the_last_read() {
+    let mut harness = Harness::new(vec![task("Write report", Progress::Waiting)]);
+    let path = harness.app.lists[0].path.clone();
+
+    // Another program adds a todo before the app's next tick noticed it
+    let mut call = task("Call Bob", Progress::Waiting);
+    call.created = "2024-01-02 09:00:00".to_string();
+    save_json(&path, &[task("Write report", Progress::Waiting), call]).unwrap();
+
+    harness.press(KeyCode::Char('3'));
+
+    let saved = harness.saved();
+    assert_eq!(saved.len(), 2);
+    assert_eq!(saved[0].progress, Progress::Done);
+    assert_eq!(saved[1].name, "Call Bob");
+    assert_eq!(harness.app.items.len(), 2);
+}
+
+#[test]
+fn onboarding_collects_the_settings() {
+    let mut onboarding = Onboarding::default();
+    for c in "notes/todo".chars() {
+        onboarding.handle_key(KeyCode::Char(c));
+    }
+    onboarding.handle_key(KeyCode::Enter);
+    onboarding.handle_key(KeyCode::Down);
+    onboarding.handle_key(KeyCode::Enter);
+    // Wraps around to the last format
+    onboarding.handle_key(KeyCode::Up);
+    onboarding.handle_key(KeyCode::Enter);
+    assert!(!onboarding.handle_key(KeyCode::Enter));
+
+    let preferences = onboarding.preferences();
+    assert_eq!(preferences.data_dir, Some(PathBuf::from("notes/todo")));
+    assert_eq!(preferences.theme, 1);
+    assert_eq!(preferences.date_format, DateFormat::European);
+    assert_eq!(onboarding.example_tasks().len(), 3);
+
+    // Skipping keeps the defaults and starts empty
+    assert!(!onboarding.handle_key(KeyCode::Esc));
+    assert_eq!(onboarding.preferences().data_dir, None);
+    assert!(onboarding.example_tasks().is_empty());
+}
+
+#[test]
+fn generated_tasks_load_and_fit_the_limits() {
+    let items = generate::tasks(200, &mut fastrand::Rng::with_seed(7));
+    assert!(items
+        .iter()
+        .all(|item| item.name.len() <= NAME_LIMIT && item.description.len() <= DESCRIPTION_LIMIT));
+    assert!(items.iter().any(|item| item.progress == Progress::Done));
+    assert_eq!(
+        generate::tasks(5, &mut fastrand::Rng::with_seed(7))[4].name,
+        items[4].name
+    );
+
+    let mut harness = Harness::new(items);
+    harness.screen();
+    assert_eq!(harness.app.view().len(), 200);
+}
+
+#[test]
+fn theme_snapshots() {
+    let mut harness = snapshot_harness();
+    for theme in 0..crate::THEMES.len() {
+        harness.screen();
+        // Styles included, as that is all that changes between themes
+        insta::assert_debug_snapshot!(
+            format!("theme_{theme}"),
+            harness.terminal.backend().buffer()
+        );
+        harness.press(KeyCode::Char('l'));
+    }
+}
+
+#[test]
+fn save_failure_shows_an_error_popup() {
+    let mut harness = Harness::new(Vec::new());
+    // Saving over a directory fails
+    harness.app.lists[0].path = harness.dir.clone();
+    harness
+        .press(KeyCode::Char('a'))
+        .type_text("Unsaved")
+        .press(KeyCode::Enter)
+        .press(KeyCode::Enter);
+
+    assert!(harness.screen().contains("Couldn't save"));
+    harness.press(KeyCode::Esc);
+    assert!(harness.app.errors.is_empty());
+    assert!(harness.screen().contains("Unsaved"));
+}
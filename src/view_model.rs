@@ -0,0 +1,240 @@
+use crate::preferences::WeekStart;
+use crate::Data;
+use chrono::{Days, NaiveDate, NaiveTime};
+use ratatui::widgets::ScrollbarState;
+
+/// One table row: a task, by its index into the stored items, or the header
+/// starting a group when the table is grouped by due date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    Task(usize),
+    Header(DateGroup),
+}
+
+/// The sections of the table grouped by due date, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DateGroup {
+    Overdue,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    Later,
+    NoDate,
+}
+
+impl DateGroup {
+    pub fn of(due: Option<NaiveDate>, today: NaiveDate, week_start: WeekStart) -> Self {
+        let Some(due) = due else {
+            return Self::NoDate;
+        };
+        let days_left = (due - today).num_days();
+        let week_end = today + Days::new(u64::from(6 - week_start.day_of_week(today)));
+        match days_left {
+            ..=-1 => Self::Overdue,
+            0 => Self::Today,
+            1 => Self::Tomorrow,
+            _ if due <= week_end => Self::ThisWeek,
+            _ => Self::Later,
+        }
+    }
+
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::Overdue => "Overdue",
+            Self::Today => "Today",
+            Self::Tomorrow => "Tomorrow",
+            Self::ThisWeek => "This week",
+            Self::Later => "Later",
+            Self::NoDate => "No due date",
+        }
+    }
+}
+
+/// Sorts tasks into date groups, soonest first, with a header before each
+/// group. Within a day, tasks due at a time come first by time; the others
+/// keep their order.
+pub fn group_by_date(
+    tasks: Vec<(usize, Option<NaiveDate>, Option<NaiveTime>)>,
+    today: NaiveDate,
+    week_start: WeekStart,
+) -> Vec<Entry> {
+    let mut tasks: Vec<(DateGroup, Option<NaiveDate>, Option<NaiveTime>, usize)> = tasks
+        .into_iter()
+        .map(|(index, due, time)| (DateGroup::of(due, today, week_start), due, time, index))
+        .collect();
+    tasks.sort_by_key(|&(group, due, time, _)| (group, due, time.is_none(), time));
+
+    let mut entries = Vec::new();
+    let mut current = None;
+    for (group, _, _, index) in tasks {
+        if current != Some(group) {
+            entries.push(Entry::Header(group));
+            current = Some(group);
+        }
+        entries.push(Entry::Task(index));
+    }
+    entries
+}
+
+/// The task the selection stays on as rows move around, by its creation time,
+/// which identifies it, and where it last was in the stored items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FollowedTask {
+    index: usize,
+    created: String,
+}
+
+impl FollowedTask {
+    pub fn new(index: usize, item: &Data) -> Self {
+        Self {
+            index,
+            created: item.created.clone(),
+        }
+    }
+
+    /// Index into `items` of the task, looked up again after a reload or undo
+    /// moved it. `None` once it's gone.
+    pub fn index_in(&self, items: &[Data]) -> Option<usize> {
+        if items
+            .get(self.index)
+            .is_some_and(|item| item.created == self.created)
+        {
+            Some(self.index)
+        } else {
+            items.iter().position(|item| item.created == self.created)
+        }
+    }
+}
+
+/// What the table shows, derived from the stored items on demand: the visible
+/// rows in display order, the selected row and the scrollbar metrics. Rendering
+/// and input both go through it, so they can't disagree about what a row is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewModel {
+    rows: Vec<Entry>,
+    selected: Option<usize>,
+    row_height: usize,
+}
+
+impl ViewModel {
+    /// A selection past the last row moves to the first one, as when filtering
+    /// hides the selected task, and one on a header to the task below it.
+    pub fn new(rows: Vec<Entry>, selected: Option<usize>, row_height: usize) -> Self {
+        let mut view = Self {
+            rows,
+            selected: None,
+            row_height,
+        };
+        let start = selected.filter(|&row| row < view.rows.len()).unwrap_or(0);
+        view.selected = view.task_row_from(start, true);
+        view
+    }
+
+    pub fn rows(&self) -> &[Entry] {
+        &self.rows
+    }
+
+    /// The number of rows, headers included.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub const fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Index into the stored items of the selected row.
+    pub fn selected_index(&self) -> Option<usize> {
+        self.task_at(self.selected?)
+    }
+
+    /// Index into the stored items of the task in `row`, if it is one.
+    pub fn task_at(&self, row: usize) -> Option<usize> {
+        match self.rows.get(row)? {
+            Entry::Task(index) => Some(*index),
+            Entry::Header(_) => None,
+        }
+    }
+
+    /// The row showing the stored item at `index`, if it's visible.
+    pub fn row_of(&self, index: usize) -> Option<usize> {
+        self.rows.iter().position(|&row| row == Entry::Task(index))
+    }
+
+    /// The row showing the followed task, if it's still there and visible.
+    pub fn row_following(&self, task: &FollowedTask, items: &[Data]) -> Option<usize> {
+        self.row_of(task.index_in(items)?)
+    }
+
+    /// The task in `row` to follow from now on, if it is one.
+    pub fn follow(&self, row: usize, items: &[Data]) -> Option<FollowedTask> {
+        let index = self.task_at(row)?;
+        Some(FollowedTask::new(index, items.get(index)?))
+    }
+
+    /// The task row after the selected one, wrapping to the top. `None` when
+    /// no task is shown, so there's nowhere to go.
+    pub fn next_row(&self) -> Option<usize> {
+        let start = self.selected.map_or(0, |row| row + 1);
+        self.task_row_from(start, true)
+    }
+
+    /// The task row before the selected one, wrapping to the bottom. `None`
+    /// when no task is shown.
+    pub fn previous_row(&self) -> Option<usize> {
+        let start = match self.selected {
+            Some(0) | None => self.rows.len().checked_sub(1)?,
+            Some(row) => row - 1,
+        };
+        self.task_row_from(start, false)
+    }
+
+    /// The first task row from `start` on in the given direction, wrapping.
+    fn task_row_from(&self, start: usize, forward: bool) -> Option<usize> {
+        let len = self.rows.len();
+        (0..len)
+            .map(|step| {
+                if forward {
+                    (start + step) % len
+                } else {
+                    (start + len - step) % len
+                }
+            })
+            .find(|&row| matches!(self.rows[row], Entry::Task(_)))
+    }
+
+    /// Lines the row takes up; headers always take one.
+    pub fn height(&self, row: usize) -> usize {
+        match self.rows.get(row) {
+            Some(Entry::Header(_)) => 1,
+            _ => self.row_height,
+        }
+    }
+
+    /// The row shown on `line` of the table body, when it's scrolled to `offset`.
+    pub fn row_at_line(&self, offset: usize, line: usize) -> Option<usize> {
+        let mut top = 0;
+        (offset..self.rows.len()).find(|&row| {
+            top += self.height(row);
+            line < top
+        })
+    }
+
+    /// Lines above `row`.
+    fn line_of(&self, row: usize) -> usize {
+        (0..row).map(|row| self.height(row)).sum()
+    }
+
+    /// The table scrolled to `offset` showing `viewport` lines of rows: the
+    /// thumb covers the lines in view, and there's none when every row fits.
+    pub fn scrollbar(&self, offset: usize, viewport: usize) -> ScrollbarState {
+        let lines = self.line_of(self.rows.len());
+        if lines <= viewport {
+            return ScrollbarState::default();
+        }
+        // One position per line the top of the viewport can be on
+        ScrollbarState::new(lines - viewport + 1)
+            .position(self.line_of(offset.min(self.rows.len())))
+            .viewport_content_length(viewport)
+    }
+}
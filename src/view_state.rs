@@ -0,0 +1,70 @@
+//! The sort and filters of every list, separately for the table and the kanban
+//! board, kept in `view_state.json` next to the main list so switching back to
+//! a list or layout, or restarting, finds them as they were left.
+
+use crate::preferences::{SortMode, ViewLayout};
+use crate::serde_compat::or_default;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const VIEW_STATE_FILE: &str = "view_state.json";
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ViewState {
+    #[serde(deserialize_with = "or_default")]
+    pub sort: SortMode,
+    pub hide_completed: bool,
+    pub tag_filter: Option<String>,
+    pub date_filters: Vec<String>, // As typed after `:filter`, e.g. `created:this-week`
+}
+
+/// View states by list file and layout, see `key`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(transparent)]
+pub struct ViewStates(BTreeMap<String, ViewState>);
+
+impl ViewStates {
+    pub fn path(main_list: &Path) -> PathBuf {
+        main_list.with_file_name(VIEW_STATE_FILE)
+    }
+
+    /// Empty if the file is missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// e.g. `work.json table`. The split layout is the table with a preview
+    /// next to it, so they share their state.
+    pub fn key(list: &Path, layout: ViewLayout) -> String {
+        let view = match layout {
+            ViewLayout::Table | ViewLayout::Split => "table",
+            ViewLayout::Kanban => "kanban",
+        };
+        let file = list.file_name().unwrap_or_default().to_string_lossy();
+        format!("{file} {view}")
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ViewState> {
+        self.0.get(key)
+    }
+
+    /// Stores `state` under `key`, returning whether it changed.
+    pub fn set(&mut self, key: String, state: ViewState) -> bool {
+        if self.0.get(&key) == Some(&state) {
+            return false;
+        }
+        self.0.insert(key, state);
+        true
+    }
+}
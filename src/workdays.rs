@@ -0,0 +1,59 @@
+//! The days work todos may land on when they're postponed or due on the next
+//! business day: not on the `holidays`, nor on weekends with `skip_weekends`.
+
+use crate::preferences::Preferences;
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+const MAX_DAYS_OFF: u64 = 366; // Gives up on a list of holidays covering a whole year
+
+#[derive(Debug, Default, Clone)]
+pub struct Workdays {
+    skip_weekends: bool,
+    holidays: Vec<String>, // `YYYY-MM-DD`, or `MM-DD` for every year
+}
+
+impl Workdays {
+    pub fn new(preferences: &Preferences) -> Self {
+        Self {
+            skip_weekends: preferences.skip_weekends,
+            holidays: preferences.holidays.clone(),
+        }
+    }
+
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        let (full, yearly) = (
+            date.format("%Y-%m-%d").to_string(),
+            date.format("%m-%d").to_string(),
+        );
+        self.holidays
+            .iter()
+            .any(|holiday| holiday.trim() == full || holiday.trim() == yearly)
+    }
+
+    fn is_weekend(date: NaiveDate) -> bool {
+        matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
+    /// `date`, or the first day after it that isn't a holiday, nor a weekend
+    /// when they're skipped.
+    pub fn on_or_after(&self, date: NaiveDate) -> NaiveDate {
+        first_from(date, |day| {
+            !(self.is_holiday(day) || self.skip_weekends && Self::is_weekend(day))
+        })
+    }
+
+    /// The first weekday after `date` that isn't a holiday, whether weekends
+    /// are skipped otherwise or not.
+    pub fn next_business_day(&self, date: NaiveDate) -> NaiveDate {
+        first_from(date + Days::new(1), |day| {
+            !(self.is_holiday(day) || Self::is_weekend(day))
+        })
+    }
+}
+
+fn first_from(date: NaiveDate, is_workday: impl Fn(NaiveDate) -> bool) -> NaiveDate {
+    (0..MAX_DAYS_OFF)
+        .map(|days| date + Days::new(days))
+        .find(|&day| is_workday(day))
+        .unwrap_or(date)
+}